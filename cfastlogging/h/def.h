@@ -66,7 +66,9 @@ typedef enum CWriterTypeEnum: uint8_t
     WriterTypeEnum_Clients = 5,
     WriterTypeEnum_Server = 6,
     WriterTypeEnum_Servers = 7,
-    WriterTypeEnum_Syslog = 8
+    WriterTypeEnum_Syslog = 8,
+    WriterTypeEnum_Recorder = 9,
+    WriterTypeEnum_Capture = 10
 } CWriterTypeEnum;*/
 
 typedef void *WriterTypeEnum;
@@ -80,7 +82,9 @@ typedef enum WriterConfigEnum: uint8_t
     WriterConfigEnum_Client = 3,
     WriterConfigEnum_Server = 4,
     WriterConfigEnum_Callback = 5,
-    WriterConfigEnum_Syslog = 6
+    WriterConfigEnum_Syslog = 6,
+    WriterConfigEnum_Recorder = 7,
+    WriterConfigEnum_Capture = 8
 } WriterConfigEnum;
 
 typedef struct CWriterConfig
@@ -105,7 +109,9 @@ typedef enum CWriterEnum: uint8_t
     WriterEnum_Client = 3,
     WriterEnum_Server = 4,
     WriterEnum_Callback = 5,
-    WriterEnum_Syslog = 6
+    WriterEnum_Syslog = 6,
+    WriterEnum_Recorder = 7,
+    WriterEnum_Capture = 8
 } CWriterEnum;
 
 typedef struct CWriter
@@ -125,7 +131,9 @@ typedef struct WriterEnums {
 typedef enum MessageStructEnum : uint8_t {
   MessageStructEnum_String = 0,
   MessageStructEnum_Json = 1,
-  MessageStructEnum_Xml = 2
+  MessageStructEnum_Xml = 2,
+  MessageStructEnum_Logfmt = 3,
+  MessageStructEnum_Binary = 4
 } MessageStructEnum;
 
 typedef enum EncryptionMethodEnum : uint8_t {
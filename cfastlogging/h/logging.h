@@ -65,8 +65,18 @@ int logging_sync(Logging logging, WriterTypeEnum *types, uint32_t type_cnt,
 
 int logging_sync_all(Logging logging, double timeout);
 
+int logging_dump_recorder(Logging logging);
+
+void logging_install_panic_hook(Logging logging);
+
+int logging_install_sighup_handler(Logging logging);
+
 // File writer
 
+int logging_fsync(Logging logging, double timeout);
+
+int logging_reopen_files(Logging logging);
+
 int logging_rotate(Logging logging, const char *path);
 
 // Network
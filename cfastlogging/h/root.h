@@ -49,8 +49,18 @@ int root_sync(WriterTypeEnum *types, uint32_t type_cnt, double timeout);
 
 int root_sync_all(double timeout);
 
+int root_dump_recorder();
+
+void root_install_panic_hook();
+
+int root_install_sighup_handler();
+
 // File writer
 
+int root_fsync(double timeout);
+
+int root_reopen_files();
+
 int root_rotate(const char *path);
 
 // Network
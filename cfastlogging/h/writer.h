@@ -35,4 +35,8 @@ WriterConfigEnum syslog_writer_config_new(uint8_t level, const char *hostname,
 WriterConfigEnum callback_writer_config_new(
     uint8_t level, void (*callback)(uint8_t, const char *, const char *));
 
+// Recorder writer
+
+WriterConfigEnum recorder_writer_config_new(uint32_t capacity, const char *path);
+
 #endif
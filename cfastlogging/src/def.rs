@@ -19,6 +19,8 @@ pub enum WriterEnum {
     Server,
     Callback,
     Syslog,
+    Recorder,
+    Capture,
 }
 
 impl From<fastlogging::WriterEnum> for WriterEnum {
@@ -31,6 +33,8 @@ impl From<fastlogging::WriterEnum> for WriterEnum {
             fastlogging::WriterEnum::Server(_logging_server) => WriterEnum::Server,
             fastlogging::WriterEnum::Callback(_callback_writer) => WriterEnum::Callback,
             fastlogging::WriterEnum::Syslog(_syslog_writer) => WriterEnum::Syslog,
+            fastlogging::WriterEnum::Recorder(_recorder_writer) => WriterEnum::Recorder,
+            fastlogging::WriterEnum::Capture(_capture_writer) => WriterEnum::Capture,
         }
     }
 }
@@ -186,6 +190,8 @@ pub unsafe extern "C" fn ext_config_new(
         0 => fastlogging::MessageStructEnum::String,
         1 => fastlogging::MessageStructEnum::Json,
         2 => fastlogging::MessageStructEnum::Xml,
+        3 => fastlogging::MessageStructEnum::Logfmt,
+        4 => fastlogging::MessageStructEnum::Binary,
         _ => fastlogging::MessageStructEnum::String,
     };
     Box::into_raw(Box::new(fastlogging::ExtConfig::new(
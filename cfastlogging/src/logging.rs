@@ -1,4 +1,4 @@
-use std::ffi::{c_char, c_double, c_uint, c_void, CString};
+use std::ffi::{CString, c_char, c_double, c_uint, c_void};
 use std::path::PathBuf;
 use std::ptr::null;
 use std::slice;
@@ -416,8 +416,69 @@ pub unsafe extern "C" fn logging_sync_all(
     }
 }
 
+/// # Safety
+///
+/// Dump log messages of recorder writers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn logging_dump_recorder(logging: &fastlogging::Logging) -> isize {
+    if let Err(err) = logging.dump_recorder() {
+        eprintln!("logging_dump_recorder failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
+/// # Safety
+///
+/// Log panics with backtrace to logging instance.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn logging_install_panic_hook(logging: &fastlogging::Logging) {
+    fastlogging::install_panic_hook(logging);
+}
+
+/// # Safety
+///
+/// Reopen log files of logging instance on SIGHUP.
+#[cfg(target_family = "unix")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn logging_install_sighup_handler(logging: &fastlogging::Logging) -> isize {
+    if let Err(err) = fastlogging::install_sighup_handler(logging) {
+        eprintln!("logging_install_sighup_handler failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
 // File writer
 
+/// # Safety
+///
+/// Write log messages of file writers to disk.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn logging_fsync(logging: &fastlogging::Logging, timeout: c_double) -> isize {
+    if let Err(err) = logging.fsync(timeout) {
+        eprintln!("logging_fsync failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
+/// # Safety
+///
+/// Reopen log files.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn logging_reopen_files(logging: &fastlogging::Logging) -> isize {
+    if let Err(err) = logging.reopen_files() {
+        eprintln!("logging_reopen_files failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
 /// # Safety
 ///
 /// Rotate file.
@@ -1,17 +1,17 @@
 use std::{
-    ffi::{c_char, c_double, c_uint, CString},
+    ffi::{CString, c_char, c_double, c_uint},
     path::PathBuf,
     ptr::null,
     slice,
 };
 
 use crate::{
+    EncryptionMethodEnum, KeyStruct,
     def::{
         Cu32StringVec, Cu32u16Vec, CusizeVec, ServerConfig, ServerConfigs, WriterConfigEnums,
         WriterEnum, WriterEnums,
     },
     util::char2string,
-    EncryptionMethodEnum, KeyStruct,
 };
 
 /// # Safety
@@ -314,8 +314,69 @@ pub unsafe extern "C" fn root_sync_all(timeout: c_double) -> isize {
     }
 }
 
+/// # Safety
+///
+/// Dump log messages of recorder writers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn root_dump_recorder() -> isize {
+    if let Err(err) = fastlogging::root::dump_recorder() {
+        eprintln!("dump_recorder failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
+/// # Safety
+///
+/// Log panics with backtrace to root logger.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn root_install_panic_hook() {
+    fastlogging::root::install_panic_hook();
+}
+
+/// # Safety
+///
+/// Reopen log files of root logger on SIGHUP.
+#[cfg(target_family = "unix")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn root_install_sighup_handler() -> isize {
+    if let Err(err) = fastlogging::root::install_sighup_handler() {
+        eprintln!("install_sighup_handler failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
 // File writer
 
+/// # Safety
+///
+/// Write log messages of file writers to disk.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn root_fsync(timeout: c_double) -> isize {
+    if let Err(err) = fastlogging::root::fsync(timeout) {
+        eprintln!("fsync failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
+/// # Safety
+///
+/// Reopen log files.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn root_reopen_files() -> isize {
+    if let Err(err) = fastlogging::root::reopen_files() {
+        eprintln!("reopen_files failed: {err:?}");
+        err.as_int() as isize
+    } else {
+        0
+    }
+}
+
 /// # Safety
 ///
 /// Rotate file.
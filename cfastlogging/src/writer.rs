@@ -202,3 +202,20 @@ pub unsafe extern "C" fn callback_writer_config_new(
         fastlogging::CallbackWriterConfig::new(level, Some(Box::new(callback_func))),
     )))
 }
+
+/// # Safety
+///
+/// Create and return new config for recorder writer. If `path` is NULL the recorded
+/// messages are dumped to the other writers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn recorder_writer_config_new(
+    capacity: c_uint,
+    path: *const c_char,
+) -> *mut fastlogging::WriterConfigEnum {
+    Box::into_raw(Box::new(fastlogging::WriterConfigEnum::Recorder(
+        fastlogging::RecorderWriterConfig::new(
+            capacity as usize,
+            option_char2string(path).map(PathBuf::from),
+        ),
+    )))
+}
@@ -42,7 +42,7 @@ enum class WriterTypeEnum : uint8_t {
   Syslog  = 8
 };
 
-enum class MessageStructEnum : uint8_t { String = 0, Json = 1, Xml = 2 };
+enum class MessageStructEnum : uint8_t { String = 0, Json = 1, Xml = 2, Logfmt = 3 };
 
 enum class EncryptionMethodEnum : uint8_t { NONE = 0, AuthKey = 1, AES = 2 };
 
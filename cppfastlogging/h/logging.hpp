@@ -85,7 +85,7 @@ intptr_t logging_exception(const rust::Logging *logging, const char *message);
 
 namespace logging {
 
-enum class MessageStruct : uint8_t { String = 0, Json = 1, Xml = 2 };
+enum class MessageStruct : uint8_t { String = 0, Json = 1, Xml = 2, Logfmt = 3 };
 
 class ExtConfig {
 public:
@@ -106,6 +106,356 @@ impl WriterConfig {
     }
 }
 
+/// Opaque wrapper around a [`fastlogging::ConsoleWriterConfigBuilder`].
+pub struct ConsoleWriterConfigBuilder(fastlogging::ConsoleWriterConfigBuilder);
+
+impl ConsoleWriterConfigBuilder {
+    /// Create builder for a console writer configuration.
+    pub fn create() -> Box<ConsoleWriterConfigBuilder> {
+        Box::new(ConsoleWriterConfigBuilder(
+            fastlogging::ConsoleWriterConfig::builder(),
+        ))
+    }
+
+    pub fn enabled(&mut self, enabled: bool) -> &mut ConsoleWriterConfigBuilder {
+        self.0.enabled(enabled);
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut ConsoleWriterConfigBuilder {
+        self.0.level(level);
+        self
+    }
+
+    pub fn domain_filter(&mut self, domain_filter: String) -> &mut ConsoleWriterConfigBuilder {
+        self.0.domain_filter(domain_filter);
+        self
+    }
+
+    pub fn message_filter(&mut self, message_filter: String) -> &mut ConsoleWriterConfigBuilder {
+        self.0.message_filter(message_filter);
+        self
+    }
+
+    pub fn structured(
+        &mut self,
+        structured: ffi::MessageStructEnum,
+    ) -> &mut ConsoleWriterConfigBuilder {
+        self.0.structured(message_struct_from_ffi(structured));
+        self
+    }
+
+    pub fn level2sym(&mut self, level2sym: ffi::LevelSymsEnum) -> &mut ConsoleWriterConfigBuilder {
+        self.0.level2sym(level_syms_from_ffi(level2sym));
+        self
+    }
+
+    pub fn time_format(&mut self, time_format: String) -> &mut ConsoleWriterConfigBuilder {
+        self.0.time_format(time_format);
+        self
+    }
+
+    pub fn colors(&mut self, colors: bool) -> &mut ConsoleWriterConfigBuilder {
+        self.0.colors(colors);
+        self
+    }
+
+    pub fn target(&mut self, target: ffi::ConsoleTargetEnum) -> &mut ConsoleWriterConfigBuilder {
+        self.0.target(console_target_from_ffi(target));
+        self
+    }
+
+    pub fn split(&mut self, level: u8) -> &mut ConsoleWriterConfigBuilder {
+        self.0.split(level);
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut ConsoleWriterConfigBuilder {
+        self.0.debug(debug);
+        self
+    }
+
+    /// Validate settings and create the writer configuration.
+    pub fn build(&self) -> Result<Box<WriterConfig>, LoggingError> {
+        Ok(Box::new(WriterConfig(
+            fastlogging::WriterConfigEnum::Console(self.0.build()?),
+        )))
+    }
+}
+
+/// Opaque wrapper around a [`fastlogging::FileWriterConfigBuilder`].
+pub struct FileWriterConfigBuilder(fastlogging::FileWriterConfigBuilder);
+
+impl FileWriterConfigBuilder {
+    /// Create builder for a file writer configuration writing to `path`.
+    pub fn create(path: &str) -> Box<FileWriterConfigBuilder> {
+        Box::new(FileWriterConfigBuilder(
+            fastlogging::FileWriterConfig::builder(path),
+        ))
+    }
+
+    pub fn enabled(&mut self, enabled: bool) -> &mut FileWriterConfigBuilder {
+        self.0.enabled(enabled);
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut FileWriterConfigBuilder {
+        self.0.level(level);
+        self
+    }
+
+    pub fn domain_filter(&mut self, domain_filter: String) -> &mut FileWriterConfigBuilder {
+        self.0.domain_filter(domain_filter);
+        self
+    }
+
+    pub fn message_filter(&mut self, message_filter: String) -> &mut FileWriterConfigBuilder {
+        self.0.message_filter(message_filter);
+        self
+    }
+
+    pub fn structured(
+        &mut self,
+        structured: ffi::MessageStructEnum,
+    ) -> &mut FileWriterConfigBuilder {
+        self.0.structured(message_struct_from_ffi(structured));
+        self
+    }
+
+    pub fn level2sym(&mut self, level2sym: ffi::LevelSymsEnum) -> &mut FileWriterConfigBuilder {
+        self.0.level2sym(level_syms_from_ffi(level2sym));
+        self
+    }
+
+    pub fn time_format(&mut self, time_format: String) -> &mut FileWriterConfigBuilder {
+        self.0.time_format(time_format);
+        self
+    }
+
+    pub fn size(&mut self, size: u64) -> &mut FileWriterConfigBuilder {
+        self.0.size(size as usize);
+        self
+    }
+
+    pub fn backlog(&mut self, backlog: u64) -> &mut FileWriterConfigBuilder {
+        self.0.backlog(backlog as usize);
+        self
+    }
+
+    pub fn timeout(&mut self, timeout_secs: u64) -> &mut FileWriterConfigBuilder {
+        self.0.timeout(Duration::from_secs(timeout_secs));
+        self
+    }
+
+    /// Rotate log file `time_secs` seconds from now.
+    pub fn time(&mut self, time_secs: u64) -> &mut FileWriterConfigBuilder {
+        self.0
+            .time(SystemTime::now() + Duration::from_secs(time_secs));
+        self
+    }
+
+    pub fn compression(
+        &mut self,
+        compression: ffi::CompressionMethodEnum,
+    ) -> &mut FileWriterConfigBuilder {
+        self.0.compression(compression_from_ffi(compression));
+        self
+    }
+
+    /// Validate settings and create the writer configuration.
+    pub fn build(&self) -> Result<Box<WriterConfig>, LoggingError> {
+        Ok(Box::new(WriterConfig(fastlogging::WriterConfigEnum::File(
+            self.0.build()?,
+        ))))
+    }
+}
+
+/// Opaque wrapper around a [`fastlogging::ClientWriterConfigBuilder`].
+pub struct ClientWriterConfigBuilder(fastlogging::ClientWriterConfigBuilder);
+
+impl ClientWriterConfigBuilder {
+    /// Create builder for a network client writer configuration connecting to `address`.
+    pub fn create(address: &str) -> Box<ClientWriterConfigBuilder> {
+        Box::new(ClientWriterConfigBuilder(
+            fastlogging::ClientWriterConfig::builder(address),
+        ))
+    }
+
+    pub fn enabled(&mut self, enabled: bool) -> &mut ClientWriterConfigBuilder {
+        self.0.enabled(enabled);
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut ClientWriterConfigBuilder {
+        self.0.level(level);
+        self
+    }
+
+    pub fn domain_filter(&mut self, domain_filter: String) -> &mut ClientWriterConfigBuilder {
+        self.0.domain_filter(domain_filter);
+        self
+    }
+
+    pub fn message_filter(&mut self, message_filter: String) -> &mut ClientWriterConfigBuilder {
+        self.0.message_filter(message_filter);
+        self
+    }
+
+    pub fn structured(
+        &mut self,
+        structured: ffi::MessageStructEnum,
+    ) -> &mut ClientWriterConfigBuilder {
+        self.0.structured(message_struct_from_ffi(structured));
+        self
+    }
+
+    pub fn level2sym(&mut self, level2sym: ffi::LevelSymsEnum) -> &mut ClientWriterConfigBuilder {
+        self.0.level2sym(level_syms_from_ffi(level2sym));
+        self
+    }
+
+    pub fn time_format(&mut self, time_format: String) -> &mut ClientWriterConfigBuilder {
+        self.0.time_format(time_format);
+        self
+    }
+
+    pub fn key(
+        &mut self,
+        key_type: ffi::EncryptionMethodEnum,
+        key: Vec<u8>,
+    ) -> &mut ClientWriterConfigBuilder {
+        self.0.key(encryption_from_ffi(key_type, &key));
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut ClientWriterConfigBuilder {
+        self.0.debug(debug);
+        self
+    }
+
+    /// Validate settings and create the writer configuration.
+    pub fn build(&self) -> Result<Box<WriterConfig>, LoggingError> {
+        Ok(Box::new(WriterConfig(
+            fastlogging::WriterConfigEnum::Client(self.0.build()?),
+        )))
+    }
+}
+
+/// Opaque wrapper around a [`fastlogging::ServerConfigBuilder`].
+pub struct ServerConfigBuilder(fastlogging::ServerConfigBuilder);
+
+impl ServerConfigBuilder {
+    /// Create builder for a network server configuration listening on `address`.
+    pub fn create(address: &str) -> Box<ServerConfigBuilder> {
+        Box::new(ServerConfigBuilder(fastlogging::ServerConfig::builder(
+            address,
+        )))
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut ServerConfigBuilder {
+        self.0.level(level);
+        self
+    }
+
+    pub fn key(
+        &mut self,
+        key_type: ffi::EncryptionMethodEnum,
+        key: Vec<u8>,
+    ) -> &mut ServerConfigBuilder {
+        self.0.key(encryption_from_ffi(key_type, &key));
+        self
+    }
+
+    pub fn port_file(&mut self, port_file: String) -> &mut ServerConfigBuilder {
+        self.0.port_file(port_file);
+        self
+    }
+
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut ServerConfigBuilder {
+        self.0.max_message_size(max_message_size);
+        self
+    }
+
+    /// Validate settings and create the writer configuration.
+    pub fn build(&self) -> Result<Box<WriterConfig>, LoggingError> {
+        Ok(Box::new(WriterConfig(
+            fastlogging::WriterConfigEnum::Server(self.0.build()?),
+        )))
+    }
+}
+
+/// Opaque wrapper around a [`fastlogging::SyslogWriterConfigBuilder`].
+pub struct SyslogWriterConfigBuilder(fastlogging::SyslogWriterConfigBuilder);
+
+impl SyslogWriterConfigBuilder {
+    /// Create builder for a syslog / eventlog writer configuration.
+    pub fn create() -> Box<SyslogWriterConfigBuilder> {
+        Box::new(SyslogWriterConfigBuilder(
+            fastlogging::SyslogWriterConfig::builder(),
+        ))
+    }
+
+    pub fn enabled(&mut self, enabled: bool) -> &mut SyslogWriterConfigBuilder {
+        self.0.enabled(enabled);
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut SyslogWriterConfigBuilder {
+        self.0.level(level);
+        self
+    }
+
+    pub fn domain_filter(&mut self, domain_filter: String) -> &mut SyslogWriterConfigBuilder {
+        self.0.domain_filter(domain_filter);
+        self
+    }
+
+    pub fn message_filter(&mut self, message_filter: String) -> &mut SyslogWriterConfigBuilder {
+        self.0.message_filter(message_filter);
+        self
+    }
+
+    pub fn structured(
+        &mut self,
+        structured: ffi::MessageStructEnum,
+    ) -> &mut SyslogWriterConfigBuilder {
+        self.0.structured(message_struct_from_ffi(structured));
+        self
+    }
+
+    pub fn level2sym(&mut self, level2sym: ffi::LevelSymsEnum) -> &mut SyslogWriterConfigBuilder {
+        self.0.level2sym(level_syms_from_ffi(level2sym));
+        self
+    }
+
+    pub fn time_format(&mut self, time_format: String) -> &mut SyslogWriterConfigBuilder {
+        self.0.time_format(time_format);
+        self
+    }
+
+    pub fn hostname(&mut self, hostname: String) -> &mut SyslogWriterConfigBuilder {
+        self.0.hostname(hostname);
+        self
+    }
+
+    pub fn pname(&mut self, pname: String) -> &mut SyslogWriterConfigBuilder {
+        self.0.pname(pname);
+        self
+    }
+
+    pub fn pid(&mut self, pid: u32) -> &mut SyslogWriterConfigBuilder {
+        self.0.pid(pid);
+        self
+    }
+
+    /// Validate settings and create the writer configuration.
+    pub fn build(&self) -> Result<Box<WriterConfig>, LoggingError> {
+        Ok(Box::new(WriterConfig(
+            fastlogging::WriterConfigEnum::Syslog(self.0.build()?),
+        )))
+    }
+}
+
 /// Opaque wrapper around a [`fastlogging::Logging`] instance.
 pub struct Logging(fastlogging::Logging);
 
@@ -406,6 +756,64 @@ impl Logging {
     }
 }
 
+/// Opaque wrapper around a [`fastlogging::LoggingBuilder`].
+pub struct LoggingBuilder(fastlogging::LoggingBuilder);
+
+impl LoggingBuilder {
+    /// Create builder for a `Logging` instance.
+    pub fn create() -> Box<LoggingBuilder> {
+        Box::new(LoggingBuilder(fastlogging::Logging::builder()))
+    }
+
+    /// Set the global log level.
+    pub fn level(&mut self, level: u8) -> &mut LoggingBuilder {
+        self.0.level(level);
+        self
+    }
+
+    /// Set the logging domain.
+    pub fn domain(&mut self, domain: String) -> &mut LoggingBuilder {
+        self.0.domain(domain);
+        self
+    }
+
+    /// Add a writer configuration.
+    pub fn writer(&mut self, config: Box<WriterConfig>) -> &mut LoggingBuilder {
+        let WriterConfig(cfg) = *config;
+        self.0.writer(cfg);
+        self
+    }
+
+    /// Set the extended logging configuration.
+    pub fn ext_config(&mut self, ext_config: ffi::ExtConfigFfi) -> &mut LoggingBuilder {
+        self.0.ext_config(ext_config_from_ffi(ext_config));
+        self
+    }
+
+    /// Load and merge a configuration file.
+    pub fn config_path(&mut self, path: String) -> &mut LoggingBuilder {
+        self.0.config_path(path);
+        self
+    }
+
+    /// Select how log levels are rendered in log messages.
+    pub fn level2sym(&mut self, level2sym: ffi::LevelSymsEnum) -> &mut LoggingBuilder {
+        self.0.level2sym(level_syms_from_ffi(level2sym));
+        self
+    }
+
+    /// Set the debug level. Only useful for developers of `fastlogging` itself.
+    pub fn debug(&mut self, debug: u8) -> &mut LoggingBuilder {
+        self.0.debug(debug);
+        self
+    }
+
+    /// Validate settings and create the `Logging` instance.
+    pub fn build(&self) -> Result<Box<Logging>, LoggingError> {
+        Ok(Box::new(Logging(self.0.build()?)))
+    }
+}
+
 /// Opaque wrapper around a [`fastlogging::Logger`].
 pub struct Logger(fastlogging::Logger);
 
@@ -644,6 +1052,15 @@ fn compression_from_ffi(value: ffi::CompressionMethodEnum) -> fastlogging::Compr
     }
 }
 
+fn console_target_from_ffi(value: ffi::ConsoleTargetEnum) -> fastlogging::ConsoleTargetEnum {
+    match value {
+        ffi::ConsoleTargetEnum::StdErr => fastlogging::ConsoleTargetEnum::StdErr,
+        ffi::ConsoleTargetEnum::Both => fastlogging::ConsoleTargetEnum::Both,
+        ffi::ConsoleTargetEnum::Split => fastlogging::ConsoleTargetEnum::Split,
+        _ => fastlogging::ConsoleTargetEnum::StdOut,
+    }
+}
+
 fn level_syms_from_ffi(value: ffi::LevelSymsEnum) -> fastlogging::LevelSyms {
     match value {
         ffi::LevelSymsEnum::Short => fastlogging::LevelSyms::Short,
@@ -656,6 +1073,7 @@ fn message_struct_from_ffi(value: ffi::MessageStructEnum) -> fastlogging::Messag
     match value {
         ffi::MessageStructEnum::Json => fastlogging::MessageStructEnum::Json,
         ffi::MessageStructEnum::Xml => fastlogging::MessageStructEnum::Xml,
+        ffi::MessageStructEnum::Logfmt => fastlogging::MessageStructEnum::Logfmt,
         _ => fastlogging::MessageStructEnum::String,
     }
 }
@@ -728,11 +1146,20 @@ pub mod ffi {
         Lzma,
     }
 
+    /// Destination of console writer log messages.
+    enum ConsoleTargetEnum {
+        StdOut,
+        StdErr,
+        Both,
+        Split,
+    }
+
     /// How log messages are structured.
     enum MessageStructEnum {
         String,
         Json,
         Xml,
+        Logfmt,
     }
 
     /// How log levels are rendered in log messages.
@@ -827,6 +1254,231 @@ pub mod ffi {
         fn new_syslog(level: u8, hostname: &str, pname: &str, pid: u32) -> Box<WriterConfig>;
     }
 
+    extern "Rust" {
+        type ConsoleWriterConfigBuilder;
+
+        #[Self = "ConsoleWriterConfigBuilder"]
+        fn create() -> Box<ConsoleWriterConfigBuilder>;
+
+        fn enabled(
+            self: &mut ConsoleWriterConfigBuilder,
+            enabled: bool,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn level(
+            self: &mut ConsoleWriterConfigBuilder,
+            level: u8,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn domain_filter(
+            self: &mut ConsoleWriterConfigBuilder,
+            domain_filter: String,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn message_filter(
+            self: &mut ConsoleWriterConfigBuilder,
+            message_filter: String,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn structured(
+            self: &mut ConsoleWriterConfigBuilder,
+            structured: MessageStructEnum,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn level2sym(
+            self: &mut ConsoleWriterConfigBuilder,
+            level2sym: LevelSymsEnum,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn time_format(
+            self: &mut ConsoleWriterConfigBuilder,
+            time_format: String,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn colors(
+            self: &mut ConsoleWriterConfigBuilder,
+            colors: bool,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn target(
+            self: &mut ConsoleWriterConfigBuilder,
+            target: ConsoleTargetEnum,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn split(
+            self: &mut ConsoleWriterConfigBuilder,
+            level: u8,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn debug(
+            self: &mut ConsoleWriterConfigBuilder,
+            debug: u8,
+        ) -> &mut ConsoleWriterConfigBuilder;
+        fn build(self: &ConsoleWriterConfigBuilder) -> Result<Box<WriterConfig>>;
+    }
+
+    extern "Rust" {
+        type FileWriterConfigBuilder;
+
+        #[Self = "FileWriterConfigBuilder"]
+        fn create(path: &str) -> Box<FileWriterConfigBuilder>;
+
+        fn enabled(
+            self: &mut FileWriterConfigBuilder,
+            enabled: bool,
+        ) -> &mut FileWriterConfigBuilder;
+        fn level(self: &mut FileWriterConfigBuilder, level: u8) -> &mut FileWriterConfigBuilder;
+        fn domain_filter(
+            self: &mut FileWriterConfigBuilder,
+            domain_filter: String,
+        ) -> &mut FileWriterConfigBuilder;
+        fn message_filter(
+            self: &mut FileWriterConfigBuilder,
+            message_filter: String,
+        ) -> &mut FileWriterConfigBuilder;
+        fn structured(
+            self: &mut FileWriterConfigBuilder,
+            structured: MessageStructEnum,
+        ) -> &mut FileWriterConfigBuilder;
+        fn level2sym(
+            self: &mut FileWriterConfigBuilder,
+            level2sym: LevelSymsEnum,
+        ) -> &mut FileWriterConfigBuilder;
+        fn time_format(
+            self: &mut FileWriterConfigBuilder,
+            time_format: String,
+        ) -> &mut FileWriterConfigBuilder;
+        fn size(self: &mut FileWriterConfigBuilder, size: u64) -> &mut FileWriterConfigBuilder;
+        fn backlog(
+            self: &mut FileWriterConfigBuilder,
+            backlog: u64,
+        ) -> &mut FileWriterConfigBuilder;
+        fn timeout(
+            self: &mut FileWriterConfigBuilder,
+            timeout_secs: u64,
+        ) -> &mut FileWriterConfigBuilder;
+        fn time(self: &mut FileWriterConfigBuilder, time_secs: u64)
+        -> &mut FileWriterConfigBuilder;
+        fn compression(
+            self: &mut FileWriterConfigBuilder,
+            compression: CompressionMethodEnum,
+        ) -> &mut FileWriterConfigBuilder;
+        fn build(self: &FileWriterConfigBuilder) -> Result<Box<WriterConfig>>;
+    }
+
+    extern "Rust" {
+        type ClientWriterConfigBuilder;
+
+        #[Self = "ClientWriterConfigBuilder"]
+        fn create(address: &str) -> Box<ClientWriterConfigBuilder>;
+
+        fn enabled(
+            self: &mut ClientWriterConfigBuilder,
+            enabled: bool,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn level(self: &mut ClientWriterConfigBuilder, level: u8)
+        -> &mut ClientWriterConfigBuilder;
+        fn domain_filter(
+            self: &mut ClientWriterConfigBuilder,
+            domain_filter: String,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn message_filter(
+            self: &mut ClientWriterConfigBuilder,
+            message_filter: String,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn structured(
+            self: &mut ClientWriterConfigBuilder,
+            structured: MessageStructEnum,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn level2sym(
+            self: &mut ClientWriterConfigBuilder,
+            level2sym: LevelSymsEnum,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn time_format(
+            self: &mut ClientWriterConfigBuilder,
+            time_format: String,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn key(
+            self: &mut ClientWriterConfigBuilder,
+            key_type: EncryptionMethodEnum,
+            key: Vec<u8>,
+        ) -> &mut ClientWriterConfigBuilder;
+        fn debug(self: &mut ClientWriterConfigBuilder, debug: u8)
+        -> &mut ClientWriterConfigBuilder;
+        fn build(self: &ClientWriterConfigBuilder) -> Result<Box<WriterConfig>>;
+    }
+
+    extern "Rust" {
+        type ServerConfigBuilder;
+
+        #[Self = "ServerConfigBuilder"]
+        fn create(address: &str) -> Box<ServerConfigBuilder>;
+
+        fn level(self: &mut ServerConfigBuilder, level: u8) -> &mut ServerConfigBuilder;
+        fn key(
+            self: &mut ServerConfigBuilder,
+            key_type: EncryptionMethodEnum,
+            key: Vec<u8>,
+        ) -> &mut ServerConfigBuilder;
+        fn port_file(self: &mut ServerConfigBuilder, port_file: String)
+        -> &mut ServerConfigBuilder;
+        fn max_message_size(
+            self: &mut ServerConfigBuilder,
+            max_message_size: usize,
+        ) -> &mut ServerConfigBuilder;
+        fn build(self: &ServerConfigBuilder) -> Result<Box<WriterConfig>>;
+    }
+
+    extern "Rust" {
+        type SyslogWriterConfigBuilder;
+
+        #[Self = "SyslogWriterConfigBuilder"]
+        fn create() -> Box<SyslogWriterConfigBuilder>;
+
+        fn enabled(
+            self: &mut SyslogWriterConfigBuilder,
+            enabled: bool,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn level(self: &mut SyslogWriterConfigBuilder, level: u8)
+        -> &mut SyslogWriterConfigBuilder;
+        fn domain_filter(
+            self: &mut SyslogWriterConfigBuilder,
+            domain_filter: String,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn message_filter(
+            self: &mut SyslogWriterConfigBuilder,
+            message_filter: String,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn structured(
+            self: &mut SyslogWriterConfigBuilder,
+            structured: MessageStructEnum,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn level2sym(
+            self: &mut SyslogWriterConfigBuilder,
+            level2sym: LevelSymsEnum,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn time_format(
+            self: &mut SyslogWriterConfigBuilder,
+            time_format: String,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn hostname(
+            self: &mut SyslogWriterConfigBuilder,
+            hostname: String,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn pname(
+            self: &mut SyslogWriterConfigBuilder,
+            pname: String,
+        ) -> &mut SyslogWriterConfigBuilder;
+        fn pid(self: &mut SyslogWriterConfigBuilder, pid: u32) -> &mut SyslogWriterConfigBuilder;
+        fn build(self: &SyslogWriterConfigBuilder) -> Result<Box<WriterConfig>>;
+    }
+
+    extern "Rust" {
+        type LoggingBuilder;
+
+        #[Self = "LoggingBuilder"]
+        fn create() -> Box<LoggingBuilder>;
+
+        fn level(self: &mut LoggingBuilder, level: u8) -> &mut LoggingBuilder;
+        fn domain(self: &mut LoggingBuilder, domain: String) -> &mut LoggingBuilder;
+        fn writer(self: &mut LoggingBuilder, config: Box<WriterConfig>) -> &mut LoggingBuilder;
+        fn ext_config(self: &mut LoggingBuilder, ext_config: ExtConfigFfi) -> &mut LoggingBuilder;
+        fn config_path(self: &mut LoggingBuilder, path: String) -> &mut LoggingBuilder;
+        fn level2sym(self: &mut LoggingBuilder, level2sym: LevelSymsEnum) -> &mut LoggingBuilder;
+        fn debug(self: &mut LoggingBuilder, debug: u8) -> &mut LoggingBuilder;
+        fn build(self: &LoggingBuilder) -> Result<Box<Logging>>;
+    }
+
     extern "Rust" {
         type Logging;
 
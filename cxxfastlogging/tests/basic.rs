@@ -1,4 +1,4 @@
-use cxxfastlogging::{Logger, Logging, WriterConfig};
+use cxxfastlogging::{ConsoleWriterConfigBuilder, Logger, Logging, LoggingBuilder, WriterConfig};
 
 #[test]
 fn console_logging() {
@@ -30,3 +30,26 @@ fn logger_registration() {
     logging.remove_logger(&mut logger);
     logging.shutdown(false).unwrap();
 }
+
+#[test]
+fn builder_logging() {
+    let console = ConsoleWriterConfigBuilder::create()
+        .level(fastlogging::DEBUG)
+        .domain_filter("^test".to_string())
+        .build()
+        .unwrap();
+    let mut logging = LoggingBuilder::create()
+        .level(fastlogging::DEBUG)
+        .domain("test".to_string())
+        .writer(console)
+        .build()
+        .unwrap();
+    logging.info("Message from built logging").unwrap();
+    logging.shutdown(false).unwrap();
+    assert!(
+        ConsoleWriterConfigBuilder::create()
+            .message_filter("(".to_string())
+            .build()
+            .is_err()
+    );
+}
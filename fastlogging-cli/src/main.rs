@@ -0,0 +1,621 @@
+//! `fastlogging` command-line tool: view, merge and convert log files, validate config
+//! files and send test messages to a running `LoggingServer`.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, IsTerminal, Write},
+    iter::Peekable,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use fastlogging::{
+    CRITICAL, ClientWriterConfig, DEBUG, ERROR, EXCEPTION, INFO, LevelSyms, Logging, LoggingError,
+    MessageStructEnum, NOTSET, SUCCESS, TRACE, WARNING, check_config_file, level2color,
+    reader::{BinaryWriter, LogRecord, RecordReader, RecordReaderBuilder},
+    root::{port_file, read_port_file},
+    str2level,
+};
+use termcolor::{ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+/// Timeout in seconds for delivering messages to the logging server.
+const SEND_TIMEOUT: f64 = 5.0;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "fastlogging",
+    version,
+    about = "Tools for fastlogging log files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show the last records of a log file
+    Tail(TailArgs),
+    /// Show the last records of a log file and wait for new ones (`tail -f`)
+    Follow(TailArgs),
+    /// Decompress and merge log files and their backups in time order
+    Cat(CatArgs),
+    /// Convert log files between string, JSON, XML and binary layout
+    Convert(ConvertArgs),
+    /// Check config files
+    Validate {
+        /// JSON, XML or YAML config files
+        #[arg(required = true)]
+        configs: Vec<PathBuf>,
+    },
+    /// Send a test message to a running logging server
+    Send(SendArgs),
+}
+
+#[derive(Debug, Args)]
+struct InputArgs {
+    /// Layout of the log files [default: detected]
+    #[arg(long, value_enum)]
+    structured: Option<Layout>,
+    /// Timestamp format of the log files
+    #[arg(long)]
+    time_format: Option<String>,
+    /// File name pattern of the backups, e.g. `app.{index}.log` or `app-%Y%m%d.log`
+    #[arg(long)]
+    backup_pattern: Option<String>,
+    /// Only records with this level or higher
+    #[arg(short, long, value_parser = parse_level)]
+    level: Option<u8>,
+    /// Only records with a domain matching this regular expression
+    #[arg(short, long)]
+    domain: Option<String>,
+    /// Only records with a message matching this regular expression
+    #[arg(short, long)]
+    message: Option<String>,
+    /// Only records logged at or after this time
+    #[arg(long, value_parser = parse_time)]
+    from: Option<DateTime<Local>>,
+    /// Only records logged before this time
+    #[arg(long, value_parser = parse_time)]
+    to: Option<DateTime<Local>>,
+}
+
+impl InputArgs {
+    fn builder(&self, path: &Path) -> RecordReaderBuilder {
+        let mut builder = RecordReader::builder(path);
+        if let Some(structured) = self.structured {
+            builder.structured(structured.into());
+        }
+        if let Some(ref time_format) = self.time_format {
+            builder.time_format(time_format);
+        }
+        if let Some(ref backup_pattern) = self.backup_pattern {
+            builder.backup_pattern(backup_pattern);
+        }
+        if let Some(level) = self.level {
+            builder.level(level);
+        }
+        if let Some(ref domain) = self.domain {
+            builder.domain_filter(domain);
+        }
+        if let Some(ref message) = self.message {
+            builder.message_filter(message);
+        }
+        if let Some(from) = self.from {
+            builder.from(from);
+        }
+        if let Some(to) = self.to {
+            builder.to(to);
+        }
+        builder
+    }
+}
+
+#[derive(Debug, Args)]
+struct OutputArgs {
+    /// Output layout
+    #[arg(short, long, value_enum, default_value_t = Layout::String)]
+    format: Layout,
+    /// Level names in string layout
+    #[arg(long, value_enum, default_value_t = Syms::Sym)]
+    level_syms: Syms,
+    /// Timestamp format of the output
+    #[arg(long)]
+    output_time_format: Option<String>,
+}
+
+impl OutputArgs {
+    fn format(&self, record: &LogRecord) -> String {
+        let structured = match self.format {
+            // Binary output is only supported by `convert`.
+            Layout::Binary => MessageStructEnum::String,
+            layout => layout.into(),
+        };
+        record.format(
+            &structured,
+            &self.level_syms.into(),
+            &self.output_time_format,
+        )
+    }
+}
+
+#[derive(Debug, Args)]
+struct TailArgs {
+    /// Log file
+    path: PathBuf,
+    /// Number of records to show
+    #[arg(short = 'n', long, default_value_t = 10)]
+    lines: usize,
+    /// Wait for new records
+    #[arg(short = 'F', long)]
+    follow: bool,
+    /// Colorize records by level
+    #[arg(long, value_enum, default_value_t = ColorWhen::Auto)]
+    color: ColorWhen,
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct CatArgs {
+    /// Log files. Numbered and compressed backups are read too.
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+    /// Only read the given log files, not their backups
+    #[arg(long)]
+    no_backups: bool,
+    /// Colorize records by level
+    #[arg(long, value_enum, default_value_t = ColorWhen::Auto)]
+    color: ColorWhen,
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    /// Log file
+    input_path: PathBuf,
+    /// Converted log file, `-` for stdout
+    output_path: PathBuf,
+    /// Convert backups of the log file too
+    #[arg(long)]
+    backups: bool,
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct SendArgs {
+    /// Message to send
+    message: String,
+    /// Port file written by the logging server
+    #[arg(long, conflicts_with = "pid", required_unless_present = "pid")]
+    port_file: Option<PathBuf>,
+    /// Process ID of the root logger with the logging server
+    #[arg(long)]
+    pid: Option<u32>,
+    /// Level of the message
+    #[arg(short, long, value_parser = parse_level, default_value = "INFO")]
+    level: u8,
+    /// Domain of the message
+    #[arg(short, long, default_value = "fastlogging")]
+    domain: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Layout {
+    String,
+    Json,
+    Xml,
+    Binary,
+}
+
+impl From<Layout> for MessageStructEnum {
+    fn from(layout: Layout) -> Self {
+        match layout {
+            Layout::String => MessageStructEnum::String,
+            Layout::Json => MessageStructEnum::Json,
+            Layout::Xml => MessageStructEnum::Xml,
+            Layout::Binary => MessageStructEnum::Binary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Syms {
+    Str,
+    Short,
+    Sym,
+}
+
+impl From<Syms> for LevelSyms {
+    fn from(syms: Syms) -> Self {
+        match syms {
+            Syms::Str => LevelSyms::Str,
+            Syms::Short => LevelSyms::Short,
+            Syms::Sym => LevelSyms::Sym,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorWhen {
+    fn choice(self) -> ColorChoice {
+        match self {
+            // termcolor checks TERM and NO_COLOR, but not if stdout is a terminal.
+            ColorWhen::Auto if io::stdout().is_terminal() => ColorChoice::Auto,
+            ColorWhen::Auto | ColorWhen::Never => ColorChoice::Never,
+            ColorWhen::Always => ColorChoice::Always,
+        }
+    }
+}
+
+fn parse_level(text: &str) -> Result<u8, String> {
+    str2level(&text.to_uppercase())
+        .or_else(|| text.parse().ok())
+        .ok_or_else(|| format!("invalid level {text:?}"))
+}
+
+/// Parse RFC 3339 time or local time `%Y-%m-%d %H:%M:%S`.
+fn parse_time(text: &str) -> Result<DateTime<Local>, String> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date.with_timezone(&Local));
+    }
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .and_then(|date| Local.from_local_datetime(&date).earliest())
+        .ok_or_else(|| format!("invalid time {text:?}"))
+}
+
+/// Merges records of several readers in time order.
+struct Merge<I: Iterator<Item = Result<LogRecord, LoggingError>>> {
+    readers: Vec<Peekable<I>>,
+}
+
+impl<I: Iterator<Item = Result<LogRecord, LoggingError>>> Merge<I> {
+    fn new(readers: Vec<I>) -> Self {
+        Self {
+            readers: readers.into_iter().map(|r| r.peekable()).collect(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<LogRecord, LoggingError>>> Iterator for Merge<I> {
+    type Item = Result<LogRecord, LoggingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut next: Option<(usize, DateTime<Local>)> = None;
+        for (index, reader) in self.readers.iter_mut().enumerate() {
+            match reader.peek() {
+                Some(Ok(record)) if next.is_none_or(|(_, date)| record.date < date) => {
+                    next = Some((index, record.date));
+                }
+                Some(Err(_)) => return reader.next(),
+                _ => {}
+            }
+        }
+        self.readers[next?.0].next()
+    }
+}
+
+fn print_record(
+    stdout: &mut StandardStream,
+    output: &OutputArgs,
+    record: &LogRecord,
+) -> Result<(), LoggingError> {
+    stdout.set_color(ColorSpec::new().set_fg(Some(level2color(record.level))))?;
+    write!(stdout, "{}", output.format(record))?;
+    stdout.reset()?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+fn tail(args: TailArgs, follow: bool) -> Result<(), LoggingError> {
+    let mut stdout = StandardStream::stdout(args.color.choice());
+    let mut builder = args.input.builder(&args.path);
+    if follow {
+        // Skip all but the last records, then wait for new ones.
+        let count = builder.build()?.count();
+        let reader = builder
+            .follow(true)
+            .build()?
+            .skip(count.saturating_sub(args.lines));
+        for record in reader {
+            print_record(&mut stdout, &args.output, &record?)?;
+            stdout.flush()?;
+        }
+        return Ok(());
+    }
+    let mut records = VecDeque::with_capacity(args.lines + 1);
+    for record in builder.build()? {
+        records.push_back(record?);
+        if records.len() > args.lines {
+            records.pop_front();
+        }
+    }
+    for record in records {
+        print_record(&mut stdout, &args.output, &record)?;
+    }
+    Ok(())
+}
+
+fn cat(args: CatArgs) -> Result<(), LoggingError> {
+    let mut stdout = StandardStream::stdout(args.color.choice());
+    let readers = args
+        .paths
+        .iter()
+        .map(|path| args.input.builder(path).backups(!args.no_backups).build())
+        .collect::<Result<Vec<_>, _>>()?;
+    for record in Merge::new(readers) {
+        print_record(&mut stdout, &args.output, &record?)?;
+    }
+    Ok(())
+}
+
+fn convert(args: ConvertArgs) -> Result<(), LoggingError> {
+    let writer: Box<dyn Write> = if args.output_path == Path::new("-") {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(File::create(&args.output_path)?)
+    };
+    let mut writer = BufWriter::new(writer);
+    let reader = args
+        .input
+        .builder(&args.input_path)
+        .backups(args.backups)
+        .build()?;
+    if args.output.format == Layout::Binary {
+        let mut binary = BinaryWriter::new(writer);
+        for record in reader {
+            binary.write(&record?)?;
+        }
+        binary.into_inner()?;
+        return Ok(());
+    }
+    for record in reader {
+        writeln!(writer, "{}", args.output.format(&record?))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn validate(configs: Vec<PathBuf>) -> Result<bool, LoggingError> {
+    let mut valid = true;
+    for path in configs {
+        match check_config_file(&path) {
+            Ok(writers) => println!("{}: OK, {} writers", path.display(), writers.len()),
+            Err(err) => {
+                eprintln!("{}: {err}", path.display());
+                valid = false;
+            }
+        }
+    }
+    Ok(valid)
+}
+
+fn send(args: SendArgs) -> Result<(), LoggingError> {
+    let path = match args.port_file {
+        Some(path) => path,
+        None => port_file(args.pid.unwrap()),
+    };
+    let Some((address, encryption)) = read_port_file(&path)? else {
+        return Err(LoggingError::InvalidFile(format!(
+            "Port file {path:?} is incomplete"
+        )));
+    };
+    let mut logging = Logging::builder()
+        .domain(args.domain)
+        .writer(ClientWriterConfig::new(NOTSET, address, encryption))
+        .build()?;
+    let message = args.message;
+    match args.level {
+        TRACE => logging.trace(message),
+        DEBUG => logging.debug(message),
+        INFO => logging.info(message),
+        SUCCESS => logging.success(message),
+        WARNING => logging.warning(message),
+        ERROR => logging.error(message),
+        CRITICAL => logging.critical(message),
+        EXCEPTION => logging.exception(message),
+        level => Err(LoggingError::InvalidValue(format!(
+            "Level {level} can not be sent"
+        ))),
+    }?;
+    logging.sync_all(SEND_TIMEOUT)?;
+    logging.shutdown(false)
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Tail(args) => {
+            let follow = args.follow;
+            tail(args, follow)
+        }
+        Command::Follow(args) => tail(args, true),
+        Command::Cat(args) => cat(args),
+        Command::Convert(args) => convert(args),
+        Command::Validate { configs } => match validate(configs) {
+            Ok(false) => return ExitCode::FAILURE,
+            result => result.map(|_| ()),
+        },
+        Command::Send(args) => send(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output was closed, e.g. by `head`.
+        Err(LoggingError::Io { ref kind, .. })
+            if *kind == io::ErrorKind::BrokenPipe.to_string() =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("fastlogging: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use fastlogging::{
+        CaptureWriterConfig, EncryptionMethod, FileWriterConfig, ServerConfig, reader::BinaryReader,
+    };
+
+    fn write_log(path: &Path, domain: &str, structured: MessageStructEnum) {
+        let mut logging = Logging::builder()
+            .domain(domain)
+            .writer(
+                FileWriterConfig::builder(path)
+                    .structured(structured)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        for i in 0..5 {
+            logging.info(format!("{domain} message {i}")).unwrap();
+        }
+        logging.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn merge_convert() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let string_log = temp_dir.path().join("string.log");
+        let json_log = temp_dir.path().join("json.log");
+        write_log(&string_log, "string", MessageStructEnum::String);
+        write_log(&json_log, "json", MessageStructEnum::Json);
+        let readers = [&string_log, &json_log]
+            .iter()
+            .map(|path| RecordReader::open(path).unwrap())
+            .collect();
+        let records: Vec<_> = Merge::new(readers).map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 10);
+        assert!(records.windows(2).all(|w| w[0].date <= w[1].date));
+        // Convert to binary and back.
+        let binary_log = temp_dir.path().join("binary.log");
+        let cli = Cli::parse_from([
+            "fastlogging".as_ref(),
+            "convert".as_ref(),
+            json_log.as_os_str(),
+            binary_log.as_os_str(),
+            "--format".as_ref(),
+            "binary".as_ref(),
+        ]);
+        let Command::Convert(args) = cli.command else {
+            panic!("Expected convert command");
+        };
+        convert(args).unwrap();
+        let converted: Vec<_> = BinaryReader::open(&binary_log)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        let original: Vec<_> = RecordReader::open(&json_log)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(converted, original);
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn backup_pattern() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("app.log");
+        let mut logging = Logging::builder()
+            .domain("pattern")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .size(300)
+                    .backlog(100)
+                    .backup_pattern("app-%Y%m%d%H%M%S.log")
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        for i in 0..20 {
+            logging.info(format!("Pattern message {i}")).unwrap();
+        }
+        logging.shutdown(false).unwrap();
+        let json_log = temp_dir.path().join("app.json");
+        let cli = Cli::parse_from([
+            "fastlogging".as_ref(),
+            "convert".as_ref(),
+            log_file.as_os_str(),
+            json_log.as_os_str(),
+            "--backups".as_ref(),
+            "--backup-pattern".as_ref(),
+            "app-%Y%m%d%H%M%S.log".as_ref(),
+            "--format".as_ref(),
+            "json".as_ref(),
+        ]);
+        let Command::Convert(args) = cli.command else {
+            panic!("Expected convert command");
+        };
+        convert(args).unwrap();
+        let messages: Vec<_> = RecordReader::open(&json_log)
+            .unwrap()
+            .map(|r| r.unwrap().message)
+            .collect();
+        assert_eq!(
+            messages,
+            (0..20)
+                .map(|i| format!("Pattern message {i}"))
+                .collect::<Vec<_>>()
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn send_port_file() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let port_file = temp_dir.path().join("server.port");
+        let capture = CaptureWriterConfig::builder().build().unwrap();
+        let handle = capture.handle();
+        let mut logging = Logging::builder()
+            .writer(
+                ServerConfig::builder("127.0.0.1")
+                    .key(EncryptionMethod::AuthKey(b"cli-test-key".to_vec()))
+                    .port_file(&port_file)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(capture)
+            .build()
+            .unwrap();
+        send(SendArgs {
+            message: "Test message".to_string(),
+            port_file: Some(port_file.clone()),
+            pid: None,
+            level: WARNING,
+            domain: "cli".to_string(),
+        })
+        .unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        let record = &handle.records()[0];
+        assert_eq!(record.level, WARNING);
+        assert!(record.message.ends_with(" cli: W Test message"));
+        logging.shutdown(false).unwrap();
+        assert!(!port_file.exists());
+        temp_dir.close().unwrap();
+    }
+}
@@ -0,0 +1,385 @@
+//! `fastlogging-server`: standalone log collector. Runs a `LoggingServer` with the writers
+//! of a config file, so that many processes and hosts can log to one central place.
+//!
+//! Signals: SIGHUP reloads the config file, SIGUSR1 logs client statistics, SIGTERM and
+//! SIGINT shut down after writing all pending messages.
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{self, ExitCode},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use fastlogging::{
+    Logging, LoggingError, ServerConfig, WriterConfigEnum, WriterEnum, check_config_file,
+    root::write_port_file,
+};
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGTERM, SIGUSR1},
+    iterator::Signals,
+};
+
+/// Domain of the log messages of the collector itself.
+const DOMAIN: &str = "fastlogging-server";
+/// Time between checks for signals.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "fastlogging-server",
+    version,
+    about = "Standalone fastlogging log collector"
+)]
+struct Args {
+    /// Config file with exactly one server writer and the writers for the collected messages
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Write process ID into this file
+    #[arg(long)]
+    pid_file: Option<PathBuf>,
+    /// Write listening port and key into this file, for `fastlogging send --port-file`
+    #[arg(long)]
+    port_file: Option<PathBuf>,
+    /// Seconds between client statistics log messages, 0 disables them
+    #[arg(long, default_value_t = 60)]
+    stats_interval: u64,
+}
+
+enum Event {
+    Reload,
+    Stats,
+    Stop,
+}
+
+/// Split writer configs of a config file into the server config and the other writers.
+fn split_configs(
+    configs: Vec<WriterConfigEnum>,
+) -> Result<(ServerConfig, Vec<WriterConfigEnum>), LoggingError> {
+    let (servers, writers): (Vec<_>, Vec<_>) = configs
+        .into_iter()
+        .partition(|config| matches!(config, WriterConfigEnum::Server(_)));
+    match <[WriterConfigEnum; 1]>::try_from(servers) {
+        Ok([WriterConfigEnum::Server(server)]) => Ok((server, writers)),
+        Ok(_) => unreachable!(),
+        Err(servers) => Err(LoggingError::ConfigError(format!(
+            "Config file needs exactly one server writer, found {}",
+            servers.len()
+        ))),
+    }
+}
+
+struct Daemon {
+    args: Args,
+    logging: Logging,
+    server_wid: usize,
+    /// Server config of the config file, as started
+    server: ServerConfig,
+}
+
+impl Daemon {
+    fn start(args: Args) -> Result<Self, LoggingError> {
+        let (server, writers) = split_configs(check_config_file(&args.config)?)?;
+        let logging = Logging::builder()
+            .domain(DOMAIN)
+            .writers(writers)
+            .writer(server.clone())
+            .build()?;
+        let Some((server_wid, listening)) = logging.get_server_configs().into_iter().next() else {
+            return Err(LoggingError::ConfigError(
+                "Logging server did not start".to_string(),
+            ));
+        };
+        if let Some(ref pid_file) = args.pid_file {
+            fs::write(pid_file, format!("{}\n", process::id()))?;
+        }
+        if let Some(ref port_file) = args.port_file {
+            write_port_file(port_file, listening.port, &listening.key)?;
+        }
+        logging.info(format!(
+            "Listening on {}:{}",
+            listening.address, listening.port
+        ))?;
+        Ok(Self {
+            args,
+            logging,
+            server_wid,
+            server,
+        })
+    }
+
+    /// Replace all writers except the server with the writers of the config file. Keeps
+    /// the current writers if the config file is invalid or a new writer fails to start.
+    fn reload(&mut self) -> Result<(), LoggingError> {
+        match self.replace_writers() {
+            Ok(()) => self
+                .logging
+                .info(format!("Reloaded {:?}", self.args.config)),
+            Err(err) => self
+                .logging
+                .error(format!("Failed to reload {:?}: {err}", self.args.config)),
+        }
+    }
+
+    fn replace_writers(&mut self) -> Result<(), LoggingError> {
+        let (server, configs) = split_configs(check_config_file(&self.args.config)?)?;
+        let wids: Vec<_> = self
+            .logging
+            .get_writer_configs()
+            .into_keys()
+            .filter(|wid| *wid != self.server_wid)
+            .collect();
+        // Start all new writers first. The swap holds the instance lock, so that every
+        // message is written by either the current or the new writers.
+        let mut instance = self.logging.instance.write();
+        let mut writers = Vec::with_capacity(configs.len());
+        for config in configs.iter() {
+            match WriterEnum::new(&mut instance, config) {
+                Ok(writer) => writers.push(writer),
+                Err(err) => {
+                    drop(instance);
+                    for mut writer in writers {
+                        let _ = writer.shutdown();
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        let old_writers = instance.remove_writers(Some(wids));
+        instance.add_writers(writers);
+        drop(instance);
+        for mut writer in old_writers {
+            if let Err(err) = writer.shutdown() {
+                self.logging
+                    .error(format!("Failed to stop writer: {err}"))?;
+            }
+        }
+        if server.address != self.server.address
+            || server.port != self.server.port
+            || server.key.to_bytes() != self.server.key.to_bytes()
+        {
+            self.logging
+                .warning("Changes of server address or key require a restart")?;
+        }
+        self.logging.set_level(self.server_wid, server.level)
+    }
+
+    fn log_stats(&self) -> Result<(), LoggingError> {
+        let stats = self.logging.get_server_client_stats(self.server_wid)?;
+        if stats.is_empty() {
+            return self.logging.info("No clients");
+        }
+        for client in stats {
+            self.logging.info(format!(
+                "Client {}: {} connections ({} total), {} messages, {} bytes, {} errors, {} dropped, last seen {}",
+                client.address,
+                client.connections,
+                client.total_connections,
+                client.messages,
+                client.bytes,
+                client.errors,
+                client.dropped,
+                client.last_seen.format("%Y-%m-%d %H:%M:%S")
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn shutdown(mut self) -> Result<(), LoggingError> {
+        self.log_stats()?;
+        self.logging.info("Shutting down")?;
+        self.logging.shutdown(false)?;
+        for path in [&self.args.pid_file, &self.args.port_file]
+            .into_iter()
+            .flatten()
+        {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+struct EventSource(Signals);
+
+#[cfg(unix)]
+impl EventSource {
+    fn new() -> Result<Self, LoggingError> {
+        Ok(Self(Signals::new([SIGHUP, SIGUSR1, SIGTERM, SIGINT])?))
+    }
+
+    fn pending(&mut self) -> Vec<Event> {
+        self.0
+            .pending()
+            .map(|signal| match signal {
+                SIGHUP => Event::Reload,
+                SIGUSR1 => Event::Stats,
+                _ => Event::Stop,
+            })
+            .collect()
+    }
+}
+
+/// Without signals the collector runs until it is killed.
+#[cfg(not(unix))]
+struct EventSource;
+
+#[cfg(not(unix))]
+impl EventSource {
+    fn new() -> Result<Self, LoggingError> {
+        Ok(Self)
+    }
+
+    fn pending(&mut self) -> Vec<Event> {
+        Vec::new()
+    }
+}
+
+fn run(args: Args) -> Result<(), LoggingError> {
+    let mut events = EventSource::new()?;
+    let stats_interval = Duration::from_secs(args.stats_interval);
+    let mut daemon = Daemon::start(args)?;
+    let mut next_stats = Instant::now() + stats_interval;
+    loop {
+        for event in events.pending() {
+            // Failures of the collector's own log messages do not stop it.
+            let result = match event {
+                Event::Reload => daemon.reload(),
+                Event::Stats => daemon.log_stats(),
+                Event::Stop => return daemon.shutdown(),
+            };
+            if let Err(err) = result {
+                eprintln!("fastlogging-server: {err}");
+            }
+        }
+        if !stats_interval.is_zero() && Instant::now() >= next_stats {
+            if let Err(err) = daemon.log_stats() {
+                eprintln!("fastlogging-server: {err}");
+            }
+            next_stats += stats_interval;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("fastlogging-server: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fastlogging::{
+        ClientWriterConfig, DEBUG, EncryptionMethod, FileWriterConfig, INFO, root::read_port_file,
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write_config(path: &Path, log_file: &Path) {
+        write_configs(path, &[log_file]);
+    }
+
+    fn write_configs(path: &Path, log_files: &[&Path]) {
+        let mut configs: Vec<WriterConfigEnum> = log_files
+            .iter()
+            .map(|log_file| {
+                FileWriterConfig::builder(log_file)
+                    .level(DEBUG)
+                    .build()
+                    .unwrap()
+                    .into()
+            })
+            .collect();
+        configs.push(
+            ServerConfig::builder("127.0.0.1")
+                .level(INFO)
+                .key(EncryptionMethod::AuthKey(b"collector".to_vec()))
+                .build()
+                .unwrap()
+                .into(),
+        );
+        let config = serde_json::json!({ "configs": configs });
+        fs::write(path, config.to_string()).unwrap();
+    }
+
+    fn send(port_file: &Path, message: &str) {
+        let (address, key) = read_port_file(port_file).unwrap().unwrap();
+        let mut logging = Logging::builder()
+            .domain("client")
+            .writer(ClientWriterConfig::new(DEBUG, address, key))
+            .build()
+            .unwrap();
+        logging.info(message).unwrap();
+        logging.debug("Filtered by server level").unwrap();
+        logging.sync_all(5.0).unwrap();
+        logging.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn collector() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let config = temp_dir.path().join("server.json");
+        let first_log = temp_dir.path().join("first.log");
+        let second_log = temp_dir.path().join("second.log");
+        let pid_file = temp_dir.path().join("server.pid");
+        let port_file = temp_dir.path().join("server.port");
+        write_config(&config, &first_log);
+        let mut daemon = Daemon::start(Args {
+            config: config.clone(),
+            pid_file: Some(pid_file.clone()),
+            port_file: Some(port_file.clone()),
+            stats_interval: 0,
+        })
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&pid_file).unwrap(),
+            format!("{}\n", process::id())
+        );
+        send(&port_file, "First message");
+        daemon.logging.sync_all(5.0).unwrap();
+        // Invalid config files keep the current writers.
+        fs::write(&config, "{").unwrap();
+        daemon.reload().unwrap();
+        write_config(&config, &second_log);
+        daemon.reload().unwrap();
+        send(&port_file, "Second message");
+        // Writers which fail to start keep the current writers.
+        let third_log = temp_dir.path().join("third.log");
+        write_configs(&config, &[&third_log, &temp_dir.path().join("invalid.log")]);
+        let text = fs::read_to_string(&config).unwrap();
+        fs::write(&config, text.replace("invalid.log", "{date:%Q}.log")).unwrap();
+        daemon.reload().unwrap();
+        daemon.logging.sync_all(5.0).unwrap();
+        assert!(fs::read_to_string(&third_log).unwrap_or_default().is_empty());
+        let stats = daemon
+            .logging
+            .get_server_client_stats(daemon.server_wid)
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].total_connections, 2);
+        daemon.shutdown().unwrap();
+        let first = fs::read_to_string(&first_log).unwrap();
+        let second = fs::read_to_string(&second_log).unwrap();
+        assert!(first.contains("client: I First message"));
+        assert!(first.contains("Failed to reload"));
+        assert!(!first.contains("Second message"));
+        assert!(second.contains("client: I Second message"));
+        assert!(second.contains("Failed to reload"));
+        assert!(second.contains("Client 127.0.0.1: 0 connections (2 total), 4 messages"));
+        assert!(!second.contains("Filtered by server level"));
+        assert!(!pid_file.exists());
+        assert!(!port_file.exists());
+        temp_dir.close().unwrap();
+    }
+}
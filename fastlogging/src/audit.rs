@@ -0,0 +1,712 @@
+//! Tamper-evident audit logs.
+//!
+//! Every record written by a file writer with
+//! [`FileWriterConfigBuilder::audit`](crate::FileWriterConfigBuilder::audit) gets the suffix ` #<seq>:<mac>`, where `mac` is the SHA-256 or HMAC-SHA256 hash of the
+//! previous `mac`, the record number `seq` and the record text. Additional lines are:
+//!
+//! - `#AUDIT seed seed=<seed> prev=<mac> seq=<seq> mac=<mac>` starts every opened log file. The
+//!   chain continues with `mac`, the hash of the previous `mac` and the random `seed`.
+//! - `#AUDIT checkpoint seq=<seq> mac=<mac> sig=<signature>` is an Ed25519 signature of the
+//!   chain head, written after every `interval` records if checkpoints are enabled.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use ring::{
+    digest, hmac,
+    rand::{SecureRandom, SystemRandom},
+    signature::{ED25519, Ed25519KeyPair, UnparsedPublicKey},
+};
+
+use crate::{EncryptionMethod, LoggingError, file::list_backups, reader::LogReader};
+
+const MAC_LEN: usize = 32;
+const SEED_LEN: usize = 32;
+const AUDIT_PREFIX: &str = "#AUDIT ";
+/// Prefix of the data signed by checkpoints.
+const CHECKPOINT_MAGIC: &[u8] = b"FLAUDIT1";
+
+/// Hash function of the audit chain.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum AuditChain {
+    /// SHA-256. Detects modified records, but anybody can compute a new chain.
+    Sha256,
+    /// HMAC-SHA256 with a secret key.
+    Hmac(Vec<u8>),
+}
+
+/// Debug output without the key.
+impl fmt::Debug for AuditChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuditChain::Sha256 => write!(f, "Sha256"),
+            AuditChain::Hmac(_) => write!(f, "Hmac(<redacted>)"),
+        }
+    }
+}
+
+/// Ed25519 signing key of audit checkpoints in PKCS#8 format.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SigningKey(pub(crate) Vec<u8>);
+
+/// Debug output without the key.
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SigningKey(<redacted>)")
+    }
+}
+
+impl AuditChain {
+    fn hasher(&self) -> Hasher {
+        match self {
+            AuditChain::Sha256 => Hasher::Sha256,
+            AuditChain::Hmac(key) => Hasher::Hmac(hmac::Key::new(hmac::HMAC_SHA256, key)),
+        }
+    }
+}
+
+enum Hasher {
+    Sha256,
+    Hmac(hmac::Key),
+}
+
+impl Hasher {
+    fn hash(&self, prev: &[u8; MAC_LEN], data: &[&[u8]]) -> [u8; MAC_LEN] {
+        let mut mac = [0u8; MAC_LEN];
+        match self {
+            Hasher::Sha256 => {
+                let mut ctx = digest::Context::new(&digest::SHA256);
+                ctx.update(prev);
+                data.iter().for_each(|d| ctx.update(d));
+                mac.copy_from_slice(ctx.finish().as_ref());
+            }
+            Hasher::Hmac(key) => {
+                let mut ctx = hmac::Context::with_key(key);
+                ctx.update(prev);
+                data.iter().for_each(|d| ctx.update(d));
+                mac.copy_from_slice(ctx.sign().as_ref());
+            }
+        }
+        mac
+    }
+
+    fn record(&self, prev: &[u8; MAC_LEN], seq: u64, text: &str) -> [u8; MAC_LEN] {
+        self.hash(prev, &[&seq.to_le_bytes(), text.as_bytes()])
+    }
+}
+
+fn checkpoint_data(seq: u64, mac: &[u8; MAC_LEN]) -> Vec<u8> {
+    [CHECKPOINT_MAGIC, &seq.to_le_bytes(), mac].concat()
+}
+
+/// Check Ed25519 `signing_key` in PKCS#8 format.
+pub(crate) fn signing_key(signing_key: &[u8]) -> Result<Ed25519KeyPair, LoggingError> {
+    Ed25519KeyPair::from_pkcs8_maybe_unchecked(signing_key)
+        .map_err(|err| LoggingError::InvalidValue(format!("Invalid audit signing key: {err}")))
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn unhex_mac(text: &str) -> Option<[u8; MAC_LEN]> {
+    unhex(text)?.try_into().ok()
+}
+
+/// Parsed line of an audit log.
+enum Line<'a> {
+    Seed {
+        seed: Vec<u8>,
+        prev: [u8; MAC_LEN],
+        seq: u64,
+        mac: [u8; MAC_LEN],
+    },
+    Checkpoint {
+        seq: u64,
+        mac: [u8; MAC_LEN],
+        sig: Vec<u8>,
+    },
+    Record {
+        text: &'a str,
+        seq: u64,
+        mac: [u8; MAC_LEN],
+    },
+    Invalid,
+    /// Line without audit suffix, i.e. part of a multi-line record.
+    Text,
+}
+
+fn parse_line(line: &str) -> Line<'_> {
+    if let Some(fields) = line.strip_prefix(AUDIT_PREFIX) {
+        let mut fields = fields.split(' ');
+        let kind = fields.next();
+        let mut value = |key: &str| {
+            fields
+                .next()
+                .and_then(|field| field.strip_prefix(key)?.strip_prefix('='))
+        };
+        let parsed = match kind {
+            Some("seed") => (|| {
+                Some(Line::Seed {
+                    seed: unhex(value("seed")?)?,
+                    prev: unhex_mac(value("prev")?)?,
+                    seq: value("seq")?.parse().ok()?,
+                    mac: unhex_mac(value("mac")?)?,
+                })
+            })(),
+            Some("checkpoint") => (|| {
+                Some(Line::Checkpoint {
+                    seq: value("seq")?.parse().ok()?,
+                    mac: unhex_mac(value("mac")?)?,
+                    sig: unhex(value("sig")?)?,
+                })
+            })(),
+            _ => None,
+        };
+        return parsed.unwrap_or(Line::Invalid);
+    }
+    let Some((text, suffix)) = line.rsplit_once(" #") else {
+        return Line::Text;
+    };
+    let Some((seq, mac)) = suffix.split_once(':') else {
+        return Line::Text;
+    };
+    match (seq.parse(), unhex_mac(mac)) {
+        (Ok(seq), Some(mac)) => Line::Record { text, seq, mac },
+        _ => Line::Text,
+    }
+}
+
+/// State of the audit chain of a file writer.
+pub(crate) struct AuditWriter {
+    hasher: Hasher,
+    signer: Option<(Ed25519KeyPair, usize)>,
+    /// Number of the next record
+    seq: u64,
+    /// Chain head
+    mac: [u8; MAC_LEN],
+    /// Current log file starts with a seed line
+    seeded: bool,
+}
+
+impl AuditWriter {
+    pub(crate) fn new(
+        chain: &AuditChain,
+        checkpoints: &Option<(SigningKey, usize)>,
+    ) -> Result<Self, LoggingError> {
+        let signer = match checkpoints {
+            Some((key, interval)) => Some((signing_key(&key.0)?, *interval)),
+            None => None,
+        };
+        Ok(Self {
+            hasher: chain.hasher(),
+            signer,
+            seq: 0,
+            mac: [0u8; MAC_LEN],
+            seeded: false,
+        })
+    }
+
+    /// Continue the chain of an existing log file at `path`, or of its newest backup if
+    /// the log file has no records yet. A new seed is written anyway.
+    pub(crate) fn resume(
+        &mut self,
+        path: &Path,
+        pattern: Option<&str>,
+        encryption: &EncryptionMethod,
+    ) {
+        if self.resume_file(path, encryption) {
+            return;
+        }
+        if let Ok(backups) = list_backups(path, pattern)
+            && let Some((_, _, backup)) = backups.last()
+        {
+            self.resume_file(backup, encryption);
+        }
+    }
+
+    /// Continue the chain of log file at `path`. Returns if it contains a chain.
+    fn resume_file(&mut self, path: &Path, encryption: &EncryptionMethod) -> bool {
+        let Ok(reader) = LogReader::open_encrypted(path, encryption) else {
+            return false;
+        };
+        let mut resumed = false;
+        for record in reader {
+            let Ok(record) = record else {
+                break;
+            };
+            match parse_line(record.lines().last().unwrap_or_default()) {
+                Line::Seed { seq, mac, .. } => (self.seq, self.mac) = (seq, mac),
+                Line::Record { seq, mac, .. } => (self.seq, self.mac) = (seq + 1, mac),
+                _ => continue,
+            }
+            resumed = true;
+        }
+        resumed
+    }
+
+    /// Next log file starts with a new seed.
+    pub(crate) fn new_file(&mut self) {
+        self.seeded = false;
+    }
+
+    /// Lines to write for `message`.
+    pub(crate) fn records(&mut self, message: &str) -> Result<Vec<String>, LoggingError> {
+        let mut records = Vec::with_capacity(1);
+        if !self.seeded {
+            let mut seed = [0u8; SEED_LEN];
+            SystemRandom::new()
+                .fill(&mut seed)
+                .map_err(|_| std::io::Error::other("Failed to create audit seed"))?;
+            let prev = self.mac;
+            self.mac = self.hasher.hash(&prev, &[&seed]);
+            records.push(format!(
+                "{AUDIT_PREFIX}seed seed={} prev={} seq={} mac={}",
+                hex(&seed),
+                hex(&prev),
+                self.seq,
+                hex(&self.mac)
+            ));
+            self.seeded = true;
+        }
+        self.mac = self.hasher.record(&self.mac, self.seq, message);
+        records.push(format!("{message} #{}:{}", self.seq, hex(&self.mac)));
+        self.seq += 1;
+        if let Some((ref key_pair, interval)) = self.signer
+            && self.seq.is_multiple_of(interval as u64)
+        {
+            let sig = key_pair.sign(&checkpoint_data(self.seq, &self.mac));
+            records.push(format!(
+                "{AUDIT_PREFIX}checkpoint seq={} mac={} sig={}",
+                self.seq,
+                hex(&self.mac),
+                hex(sig.as_ref())
+            ));
+        }
+        Ok(records)
+    }
+}
+
+/// Why verification of an audit log failed.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AuditFailureKind {
+    /// Record or seed was modified or the chain was not continued.
+    Broken,
+    /// Records before the one with number `found` are missing.
+    Missing { expected: u64, found: u64 },
+    /// Checkpoint does not match the chain or has an invalid signature.
+    Checkpoint,
+    /// Record has no hash or the audit line is malformed.
+    Unchained,
+    /// Verified log files do not start with a seed line.
+    Unseeded,
+}
+
+/// First broken or missing record of an audit log.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuditFailure {
+    /// Log file
+    pub path: PathBuf,
+    /// Number of the record in the log file, starting at 1.
+    pub record: usize,
+    /// Number of the record in the audit chain, if known.
+    pub seq: Option<u64>,
+    pub kind: AuditFailureKind,
+}
+
+impl fmt::Display for AuditFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} at record {}", self.path, self.record)?;
+        if let Some(seq) = self.seq {
+            write!(f, " (#{seq})")?;
+        }
+        match &self.kind {
+            AuditFailureKind::Broken => write!(f, ": Broken hash chain"),
+            AuditFailureKind::Missing { expected, found } => {
+                write!(f, ": Missing records #{expected} to #{}", found - 1)
+            }
+            AuditFailureKind::Checkpoint => write!(f, ": Invalid checkpoint"),
+            AuditFailureKind::Unchained => write!(f, ": Record without hash"),
+            AuditFailureKind::Unseeded => write!(f, ": Chain does not start with a seed"),
+        }
+    }
+}
+
+/// Result of an audit log verification.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AuditReport {
+    /// Number of verified records
+    pub records: u64,
+    /// Number of verified checkpoints
+    pub checkpoints: usize,
+    /// First broken or missing record
+    pub failure: Option<AuditFailure>,
+}
+
+impl AuditReport {
+    pub fn is_ok(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Verifies the hash chain of audit logs written with
+/// [`FileWriterConfigBuilder::audit`](crate::FileWriterConfigBuilder::audit).
+#[derive(Debug, Clone)]
+pub struct AuditVerifier {
+    chain: AuditChain,
+    public_key: Option<Vec<u8>>,
+    encryption: EncryptionMethod,
+    backup_pattern: Option<String>,
+}
+
+impl AuditVerifier {
+    pub fn new(chain: AuditChain) -> Self {
+        Self {
+            chain,
+            public_key: None,
+            encryption: EncryptionMethod::NONE,
+            backup_pattern: None,
+        }
+    }
+
+    /// Verify signatures of checkpoints with Ed25519 `public_key`.
+    pub fn public_key(&mut self, public_key: Vec<u8>) -> &mut Self {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    /// Decrypt log files encrypted with `encryption`.
+    pub fn encryption(&mut self, encryption: EncryptionMethod) -> &mut Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Find backups with the file name pattern of
+    /// [`FileWriterConfigBuilder::backup_pattern`](crate::FileWriterConfigBuilder::backup_pattern).
+    pub fn backup_pattern<S: Into<String>>(&mut self, backup_pattern: S) -> &mut Self {
+        self.backup_pattern = Some(backup_pattern.into());
+        self
+    }
+
+    /// Verify log file at `path` and its backups, oldest first. Compressed backups are
+    /// decompressed.
+    pub fn verify<P: AsRef<Path>>(&self, path: P) -> Result<AuditReport, LoggingError> {
+        let path = path.as_ref();
+        let mut paths: Vec<_> = list_backups(path, self.backup_pattern.as_deref())?
+            .into_iter()
+            .map(|(_, _, backup)| backup)
+            // Skip incomplete compressed backups
+            .filter(|backup| backup.extension().is_none_or(|ext| ext != "tmp"))
+            .collect();
+        paths.push(path.to_path_buf());
+        self.verify_files(&paths)
+    }
+
+    /// Verify log files in `paths`, which have to be ordered from oldest to newest.
+    /// The first file has to start with a seed line.
+    pub fn verify_files<P: AsRef<Path>>(&self, paths: &[P]) -> Result<AuditReport, LoggingError> {
+        let hasher = self.chain.hasher();
+        let public_key = self
+            .public_key
+            .as_ref()
+            .map(|key| UnparsedPublicKey::new(&ED25519, key));
+        let mut report = AuditReport::default();
+        // Number of the next record and chain head
+        let mut head: Option<(u64, [u8; MAC_LEN])> = None;
+        for path in paths {
+            let path = path.as_ref();
+            let fail = |record: usize, seq: Option<u64>, kind: AuditFailureKind| {
+                Some(AuditFailure {
+                    path: path.to_path_buf(),
+                    record,
+                    seq,
+                    kind,
+                })
+            };
+            // Lines of a multi-line record
+            let mut text = String::new();
+            let mut record = 0;
+            // First line with an audit suffix, which did not match the chain. It is part
+            // of a multi-line record, if a following line completes the record.
+            let mut mismatch: Option<(usize, u64, AuditFailureKind)> = None;
+            let reader = LogReader::open_encrypted(path, &self.encryption)?;
+            for line in reader.flat_map(|r| match r {
+                Ok(r) => r.split('\n').map(|l| Ok(l.to_string())).collect(),
+                Err(err) => vec![Err(err)],
+            }) {
+                let line = line?;
+                if text.is_empty() {
+                    record += 1;
+                }
+                let parsed = parse_line(&line);
+                if head.is_none() && !matches!(parsed, Line::Seed { .. }) {
+                    report.failure = fail(record, None, AuditFailureKind::Unseeded);
+                    return Ok(report);
+                }
+                match parsed {
+                    Line::Text => {
+                        text.push_str(&line);
+                        text.push('\n');
+                        continue;
+                    }
+                    _ if !text.is_empty() && line.starts_with(AUDIT_PREFIX) => {
+                        report.failure = match mismatch.take() {
+                            Some((record, seq, kind)) => fail(record, Some(seq), kind),
+                            None => fail(record, None, AuditFailureKind::Unchained),
+                        };
+                    }
+                    Line::Invalid => {
+                        report.failure = fail(record, None, AuditFailureKind::Unchained);
+                    }
+                    Line::Seed {
+                        seed,
+                        prev,
+                        seq,
+                        mac,
+                    } => {
+                        if let Some((next, head_mac)) = head {
+                            if seq > next {
+                                report.failure = fail(
+                                    record,
+                                    Some(seq),
+                                    AuditFailureKind::Missing {
+                                        expected: next,
+                                        found: seq,
+                                    },
+                                );
+                            } else if seq < next || prev != head_mac {
+                                report.failure = fail(record, Some(seq), AuditFailureKind::Broken);
+                            }
+                        }
+                        if report.failure.is_none() {
+                            if hasher.hash(&prev, &[&seed]) != mac {
+                                report.failure = fail(record, Some(seq), AuditFailureKind::Broken);
+                            } else {
+                                head = Some((seq, mac));
+                            }
+                        }
+                    }
+                    Line::Checkpoint { seq, mac, sig } => {
+                        let valid = head == Some((seq, mac))
+                            && public_key.as_ref().is_none_or(|key| {
+                                key.verify(&checkpoint_data(seq, &mac), &sig).is_ok()
+                            });
+                        if valid {
+                            report.checkpoints += 1;
+                        } else {
+                            report.failure = fail(record, Some(seq), AuditFailureKind::Checkpoint);
+                        }
+                    }
+                    Line::Record {
+                        text: last,
+                        seq,
+                        mac,
+                    } => {
+                        let Some((next, head_mac)) = head else {
+                            unreachable!("Chain starts with a seed")
+                        };
+                        let len = text.len();
+                        text.push_str(last);
+                        let kind = if seq > next {
+                            Some(AuditFailureKind::Missing {
+                                expected: next,
+                                found: seq,
+                            })
+                        } else if seq < next || hasher.record(&head_mac, seq, &text) != mac {
+                            Some(AuditFailureKind::Broken)
+                        } else {
+                            None
+                        };
+                        match kind {
+                            Some(kind) => {
+                                // Line may end with text like an audit suffix
+                                text.truncate(len);
+                                text.push_str(&line);
+                                text.push('\n');
+                                mismatch.get_or_insert((record, seq, kind));
+                                continue;
+                            }
+                            None => {
+                                head = Some((seq + 1, mac));
+                                report.records += 1;
+                                mismatch = None;
+                                text.clear();
+                            }
+                        }
+                    }
+                }
+                if report.failure.is_some() {
+                    return Ok(report);
+                }
+            }
+            if !text.is_empty() {
+                report.failure = match mismatch {
+                    Some((record, seq, kind)) => fail(record, Some(seq), kind),
+                    None => fail(record, None, AuditFailureKind::Unchained),
+                };
+                return Ok(report);
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{File, read_to_string, write};
+
+    use ring::{
+        rand::SystemRandom,
+        signature::{Ed25519KeyPair, KeyPair},
+    };
+    use tempfile::TempDir;
+
+    use super::{AuditChain, AuditFailureKind, AuditVerifier};
+    use crate::{CompressionMethodEnum, DEBUG, FileWriterConfig, Logging};
+
+    #[test]
+    fn audit() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("audit.log");
+        let chain = AuditChain::Hmac(b"audit secret".to_vec());
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let public_key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+        let config = FileWriterConfig::builder(&log_file)
+            .size(1000)
+            .backlog(10)
+            .audit(chain.clone())
+            .audit_checkpoints(pkcs8.as_ref().to_vec(), 5)
+            .build()
+            .unwrap();
+        let debug = format!("{config:?}");
+        assert!(debug.contains("Hmac(<redacted>)") && debug.contains("SigningKey(<redacted>)"));
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["audit"], serde_json::json!({"Hmac": []}));
+        assert_eq!(value["audit_checkpoints"], serde_json::json!([[], 5]));
+        // The second run continues the chain of the first one.
+        for run in 0..2 {
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("audit")
+                .writer(config.clone())
+                .build()
+                .unwrap();
+            for i in 0..10 {
+                logging.info(format!("Audit #{run}.{i}")).unwrap();
+            }
+            logging.info("Multi\nline".to_string()).unwrap();
+            // Continuation line which looks like the end of a record
+            logging
+                .info(format!("Multi\nfake #3:{}\nline", "0".repeat(64)))
+                .unwrap();
+            logging.shutdown(false).unwrap();
+        }
+        let mut verifier = AuditVerifier::new(chain.clone());
+        verifier.public_key(public_key);
+        let report = verifier.verify(&log_file).unwrap();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.records, 24);
+        assert_eq!(report.checkpoints, 4);
+        assert!(temp_dir.path().join("audit.log.1").exists());
+        // Wrong key
+        let report = AuditVerifier::new(AuditChain::Hmac(b"wrong".to_vec()))
+            .verify(&log_file)
+            .unwrap();
+        assert_eq!(report.failure.unwrap().kind, AuditFailureKind::Broken);
+        // Modified record
+        let path = (1..10)
+            .map(|index| temp_dir.path().join(format!("audit.log.{index}")))
+            .chain([log_file.clone()])
+            .find(|path| read_to_string(path).is_ok_and(|text| text.contains("Audit #1.2")))
+            .unwrap();
+        let text = read_to_string(&path).unwrap();
+        // Keep modification time, which orders the backups
+        let modified = path.metadata().unwrap().modified().unwrap();
+        let write = |data: String| {
+            write(&path, data).unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+        write(text.replace("Audit #1.2", "Audit #1.3"));
+        let failure = verifier.verify(&log_file).unwrap().failure.unwrap();
+        assert_eq!(failure.kind, AuditFailureKind::Broken);
+        assert_eq!(failure.seq, Some(14));
+        assert_eq!(failure.path, path);
+        // Deleted record
+        let lines: Vec<_> = text.lines().filter(|l| !l.contains("Audit #1.3")).collect();
+        write(lines.join("\n") + "\n");
+        let failure = verifier.verify(&log_file).unwrap().failure.unwrap();
+        assert_eq!(
+            failure.kind,
+            AuditFailureKind::Missing {
+                expected: 15,
+                found: 16
+            }
+        );
+        assert_eq!(failure.seq, Some(16));
+        // Chain does not start with a seed
+        let lines: Vec<_> = text.lines().skip(1).collect();
+        write(lines.join("\n") + "\n");
+        let failure = verifier.verify_files(&[&path]).unwrap().failure.unwrap();
+        assert_eq!(failure.kind, AuditFailureKind::Unseeded);
+        assert_eq!(failure.record, 1);
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn compressed_backups() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("audit.log");
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("audit")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .size(1000)
+                    .backlog(10)
+                    .backup_pattern("audit.{index}.log")
+                    .compression(CompressionMethodEnum::Zstd)
+                    .audit(AuditChain::Sha256)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        for i in 0..30 {
+            logging.info(format!("Audit #{i}")).unwrap();
+        }
+        logging.shutdown(false).unwrap();
+        assert!(temp_dir.path().join("audit.1.log.zst").exists());
+        let mut verifier = AuditVerifier::new(AuditChain::Sha256);
+        // Backups are not found without the pattern
+        assert!(verifier.verify(&log_file).unwrap().records < 30);
+        let report = verifier
+            .backup_pattern("audit.{index}.log")
+            .verify(&log_file)
+            .unwrap();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.records, 30);
+        temp_dir.close().unwrap();
+    }
+}
@@ -0,0 +1,400 @@
+//! Compact binary log format of [`MessageStructEnum::Binary`].
+//!
+//! A log file consists of entries `[size: varint][type: u8][data]`:
+//!
+//! - Header (0): `"FLBIN1"`. Starts every opened log file and resets the string table.
+//! - String (1): UTF-8 bytes of the next interned string, e.g. a domain. IDs count from 0.
+//! - Record (2): Time in microseconds as zigzag varint delta to the previous record, level
+//!   byte, varints domain ID, hostname ID + 1, pname ID + 1, pid, thread name ID + 1 and
+//!   thread ID, message as varint length and bytes, number of fields and the fields as
+//!   key ID, varint length and value bytes. Fields are error causes and backtrace.
+//!
+//! Varints are unsigned LEB128, missing optional strings are encoded as ID 0.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, ErrorKind},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::{ErrorChain, LoggingError, reader::LogRecord};
+
+const HEADER: u8 = 0;
+const STRING: u8 = 1;
+const RECORD: u8 = 2;
+const MAGIC: &[u8] = b"FLBIN1";
+/// Entries bigger than this are treated as corrupt.
+const ENTRY_SIZE_MAX: usize = 1 << 28;
+const FIELD_CAUSE: &str = "cause";
+const FIELD_BACKTRACE: &str = "backtrace";
+
+/// Check if `data` is the start of a binary log file.
+pub(crate) fn is_binary(data: &[u8]) -> bool {
+    data.len() > MAGIC.len() + 1
+        && data[0] as usize == MAGIC.len() + 1
+        && data[1] == HEADER
+        && data[2..].starts_with(MAGIC)
+}
+
+fn push_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn push_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    push_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Writes binary records with a string table per log file.
+pub(crate) struct BinaryEncoder {
+    strings: HashMap<String, u64>,
+    time: i64,
+    started: bool,
+    entry: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl BinaryEncoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            strings: HashMap::new(),
+            time: 0,
+            started: false,
+            entry: Vec::with_capacity(256),
+            output: Vec::with_capacity(256),
+        }
+    }
+
+    /// Next record starts a new log file.
+    pub(crate) fn new_file(&mut self) {
+        self.strings.clear();
+        self.time = 0;
+        self.started = false;
+    }
+
+    fn push_entry(&mut self, typ: u8) {
+        push_varint(&mut self.output, self.entry.len() as u64 + 1);
+        self.output.push(typ);
+        self.output.extend_from_slice(&self.entry);
+        self.entry.clear();
+    }
+
+    fn intern(&mut self, value: &str) -> u64 {
+        if let Some(id) = self.strings.get(value) {
+            return *id;
+        }
+        let id = self.strings.len() as u64;
+        self.strings.insert(value.to_string(), id);
+        self.entry.extend_from_slice(value.as_bytes());
+        self.push_entry(STRING);
+        id
+    }
+
+    fn intern_opt(&mut self, value: Option<&str>) -> u64 {
+        value.map_or(0, |value| self.intern(value) + 1)
+    }
+
+    /// Entries of `record` including new strings.
+    pub(crate) fn encode(&mut self, record: &LogRecord) -> &[u8] {
+        self.output.clear();
+        if !self.started {
+            self.entry.extend_from_slice(MAGIC);
+            self.push_entry(HEADER);
+            self.started = true;
+        }
+        let domain = self.intern(&record.domain);
+        let hostname = self.intern_opt(record.hostname.as_deref());
+        let pname = self.intern_opt((!record.pname.is_empty()).then_some(&record.pname));
+        let tname = self.intern_opt(record.tname.as_deref());
+        let mut fields = Vec::new();
+        if let Some(ref error) = record.error {
+            for cause in error.causes.iter() {
+                fields.push((self.intern(FIELD_CAUSE), cause));
+            }
+            if let Some(ref backtrace) = error.backtrace {
+                fields.push((self.intern(FIELD_BACKTRACE), backtrace));
+            }
+        }
+        let time = record.date.timestamp_micros();
+        let entry = &mut self.entry;
+        push_varint(entry, zigzag(time.wrapping_sub(self.time)));
+        self.time = time;
+        entry.push(record.level);
+        push_varint(entry, domain);
+        push_varint(entry, hostname);
+        push_varint(entry, pname);
+        push_varint(entry, record.pid as u64);
+        push_varint(entry, tname);
+        push_varint(entry, record.tid as u64);
+        push_bytes(entry, record.message.as_bytes());
+        push_varint(entry, fields.len() as u64);
+        for (key, value) in fields {
+            push_varint(entry, key);
+            push_bytes(entry, value.as_bytes());
+        }
+        self.push_entry(RECORD);
+        &self.output
+    }
+}
+
+fn invalid(text: &str) -> LoggingError {
+    LoggingError::InvalidFile(format!("Invalid binary log entry: {text}"))
+}
+
+/// Reads varints, bytes and strings of an entry.
+struct EntryReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> EntryReader<'a> {
+    fn varint(&mut self) -> Result<u64, LoggingError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.data.split_first().ok_or_else(|| invalid("varint"))?;
+            self.data = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint"))
+    }
+
+    fn byte(&mut self) -> Result<u8, LoggingError> {
+        let (&byte, rest) = self.data.split_first().ok_or_else(|| invalid("level"))?;
+        self.data = rest;
+        Ok(byte)
+    }
+
+    fn string(&mut self) -> Result<String, LoggingError> {
+        let size = self.varint()? as usize;
+        if size > self.data.len() {
+            return Err(invalid("string size"));
+        }
+        let (bytes, rest) = self.data.split_at(size);
+        self.data = rest;
+        String::from_utf8(bytes.to_vec()).map_err(|e| LoggingError::Utf8Error(e.to_string()))
+    }
+}
+
+/// Reads binary records and keeps the string table of the current log file.
+pub(crate) struct BinaryDecoder {
+    strings: Vec<String>,
+    time: i64,
+    started: bool,
+}
+
+impl BinaryDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            strings: Vec::new(),
+            time: 0,
+            started: false,
+        }
+    }
+
+    fn string(&self, id: u64) -> Result<&String, LoggingError> {
+        self.strings
+            .get(id as usize)
+            .ok_or_else(|| invalid("unknown string ID"))
+    }
+
+    fn string_opt(&self, id: u64) -> Result<Option<String>, LoggingError> {
+        match id {
+            0 => Ok(None),
+            id => self.string(id - 1).cloned().map(Some),
+        }
+    }
+
+    fn read_varint<R: BufRead + ?Sized>(reader: &mut R) -> Result<Option<u64>, LoggingError> {
+        let mut value = 0u64;
+        let mut byte = [0u8];
+        for (i, shift) in (0..64).step_by(7).enumerate() {
+            if let Err(err) = reader.read_exact(&mut byte) {
+                return match err.kind() {
+                    ErrorKind::UnexpectedEof if i == 0 => Ok(None),
+                    ErrorKind::UnexpectedEof => Err(invalid("truncated size")),
+                    _ => Err(err.into()),
+                };
+            }
+            value |= ((byte[0] & 0x7f) as u64) << shift;
+            if byte[0] & 0x80 == 0 {
+                return Ok(Some(value));
+            }
+        }
+        Err(invalid("size"))
+    }
+
+    /// Read next record from `reader`. Returns `None` at the end of the log file.
+    pub(crate) fn decode<R: BufRead + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<Option<LogRecord>, LoggingError> {
+        loop {
+            let Some(size) = Self::read_varint(reader)? else {
+                return Ok(None);
+            };
+            let size = size as usize;
+            if size == 0 || size > ENTRY_SIZE_MAX {
+                return Err(invalid("size"));
+            }
+            let mut entry = vec![0u8; size];
+            reader.read_exact(&mut entry).map_err(|e| match e.kind() {
+                ErrorKind::UnexpectedEof => invalid("truncated entry"),
+                _ => e.into(),
+            })?;
+            let mut data = EntryReader { data: &entry[1..] };
+            match entry[0] {
+                HEADER if data.data == MAGIC => {
+                    self.strings.clear();
+                    self.time = 0;
+                    self.started = true;
+                }
+                _ if !self.started => {
+                    return Err(LoggingError::InvalidFile(
+                        "Not a binary log file".to_string(),
+                    ));
+                }
+                STRING => self.strings.push(
+                    String::from_utf8(data.data.to_vec())
+                        .map_err(|e| LoggingError::Utf8Error(e.to_string()))?,
+                ),
+                RECORD => {
+                    self.time = self.time.wrapping_add(unzigzag(data.varint()?));
+                    let level = data.byte()?;
+                    let domain = self.string(data.varint()?)?.clone();
+                    let hostname = self.string_opt(data.varint()?)?;
+                    let pname = self.string_opt(data.varint()?)?.unwrap_or_default();
+                    let pid = data.varint()? as u32;
+                    let tname = self.string_opt(data.varint()?)?;
+                    let tid = data.varint()? as u32;
+                    let message = data.string()?;
+                    let mut error: Option<ErrorChain> = None;
+                    for _ in 0..data.varint()? {
+                        let key = self.string(data.varint()?)?;
+                        let value = data.string()?;
+                        let error = error.get_or_insert_default();
+                        match key.as_str() {
+                            FIELD_CAUSE => error.causes.push(value),
+                            FIELD_BACKTRACE => error.backtrace = Some(value),
+                            _ => {}
+                        }
+                    }
+                    let date = DateTime::from_timestamp_micros(self.time)
+                        .ok_or_else(|| invalid("time"))?
+                        .with_timezone(&Local);
+                    return Ok(Some(LogRecord {
+                        level,
+                        domain,
+                        message,
+                        hostname,
+                        pname,
+                        pid,
+                        tname,
+                        tid,
+                        date,
+                        error,
+                    }));
+                }
+                // Unknown entries of newer versions are skipped.
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tempfile::TempDir;
+
+    use crate::{
+        CaptureWriterConfig, CompressionMethodEnum, DEBUG, ExtConfig, FileWriterConfig, LevelSyms,
+        Logging, MessageStructEnum, reader::BinaryReader,
+    };
+
+    #[test]
+    fn binary() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("binary.log");
+        let mut expected = Vec::new();
+        // The second run appends to the log file of the first one.
+        for run in 0..2 {
+            let capture = CaptureWriterConfig::builder().build().unwrap();
+            let handle = capture.handle();
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("binary")
+                .writer(
+                    FileWriterConfig::builder(&log_file)
+                        .structured(MessageStructEnum::Binary)
+                        .size(1000)
+                        .backlog(100)
+                        .compression(CompressionMethodEnum::Deflate)
+                        .build()
+                        .unwrap(),
+                )
+                .writer(capture)
+                .build()
+                .unwrap();
+            logging.set_ext_config(&ExtConfig::new(
+                MessageStructEnum::String,
+                false,
+                true,
+                true,
+                false,
+                true,
+            ));
+            for i in 0..50 {
+                logging.info(format!("Binary message {run}.{i}")).unwrap();
+            }
+            logging
+                .exception_err(&io::Error::other("Binary error"))
+                .unwrap();
+            handle.wait_for(51, 5.0).unwrap();
+            logging.shutdown(false).unwrap();
+            expected.extend(handle.records().into_iter().map(|r| r.formatted));
+        }
+        let mut paths: Vec<_> = (1..)
+            .map(|i| temp_dir.path().join(format!("binary.log.{i}.gz")))
+            .take_while(|path| path.exists())
+            .collect();
+        assert!(paths.len() > 2);
+        paths.reverse();
+        paths.push(log_file);
+        let mut records = Vec::new();
+        for path in paths {
+            for record in BinaryReader::open(path).unwrap() {
+                records.push(record.unwrap());
+            }
+        }
+        let formatted: Vec<_> = records
+            .iter()
+            .map(|r| r.format(&MessageStructEnum::String, &LevelSyms::Sym, &None))
+            .collect();
+        assert_eq!(formatted, expected);
+        let record = &records[50];
+        assert!(record.error.is_some());
+        assert_eq!(record.pid, std::process::id());
+        let json = record.format(&MessageStructEnum::Json, &LevelSyms::Str, &None);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["domain"], "binary");
+        let xml = records[0].format(&MessageStructEnum::Xml, &LevelSyms::Str, &None);
+        assert!(xml.ends_with("<message>Binary message 0.0</message></log>"));
+        temp_dir.close().unwrap();
+    }
+}
@@ -12,7 +12,10 @@ use flume::{Receiver, SendError, Sender, bounded};
 use parking_lot::RwLock;
 use regex::Regex;
 
-use crate::{LoggingError, NOTSET};
+use crate::{
+    LevelSyms, LoggingError, MessageStructEnum, NOTSET,
+    def::{check_filters, check_time_format},
+};
 
 pub trait CallbackFnT: Fn(u8, String, String) -> Result<(), LoggingError> {}
 
@@ -40,6 +43,12 @@ pub struct CallbackWriterConfig {
     pub(crate) level: u8,
     pub(crate) domain_filter: Option<String>,
     pub(crate) message_filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) structured: Option<MessageStructEnum>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) level2sym: Option<LevelSyms>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) time_format: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
     pub(crate) callback: Option<Arc<RwLock<CallbackFn>>>,
     pub(crate) debug: u8,
@@ -58,10 +67,18 @@ impl CallbackWriterConfig {
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             callback: callback.map(|f| Arc::new(RwLock::new(f))),
             debug: 0,
         }
     }
+
+    /// Create builder for callback writer configuration.
+    pub fn builder() -> CallbackWriterConfigBuilder {
+        CallbackWriterConfigBuilder::default()
+    }
 }
 
 impl Default for CallbackWriterConfig {
@@ -71,6 +88,9 @@ impl Default for CallbackWriterConfig {
             level: NOTSET,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             callback: None,
             debug: 0,
         }
@@ -83,6 +103,74 @@ impl fmt::Display for CallbackWriterConfig {
     }
 }
 
+/// Builder for [`CallbackWriterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct CallbackWriterConfigBuilder {
+    config: CallbackWriterConfig,
+}
+
+impl CallbackWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    pub fn callback(&mut self, callback: CallbackFn) -> &mut Self {
+        self.config.callback = Some(Arc::new(RwLock::new(callback)));
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Validate settings and create callback writer configuration.
+    pub fn build(&self) -> Result<CallbackWriterConfig, LoggingError> {
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        if self.config.callback.is_none() {
+            return Err(LoggingError::InvalidValue(
+                "Callback writer requires a callback function".to_string(),
+            ));
+        }
+        Ok(self.config.clone())
+    }
+}
+
 fn callback_writer_thread(
     config: Arc<RwLock<CallbackWriterConfig>>,
     rx: Receiver<CallbackTypeEnum>,
@@ -0,0 +1,573 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use parking_lot::{Condvar, Mutex, RwLock};
+use regex::Regex;
+
+use crate::{
+    ErrorChain, LevelSyms, LoggingError, MessageStructEnum, NOTSET,
+    def::{check_filters, check_time_format},
+};
+
+/// Log message received by a capture writer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedRecord {
+    pub level: u8,
+    pub domain: String,
+    /// Message as passed to the logging call
+    pub message: String,
+    /// Message as formatted for the writers
+    pub formatted: String,
+    /// Thread name. Only set if enabled in [`crate::ExtConfig`].
+    pub tname: Option<String>,
+    /// Thread id. Only set if enabled in [`crate::ExtConfig`].
+    pub tid: u32,
+    pub date: DateTime<Local>,
+    /// Causes and backtrace. Only set for messages logged with `exception_err`.
+    pub error: Option<ErrorChain>,
+    /// Structured fields as in JSON messages: `host`, `pname`, `pid`, `tname` and `tid`.
+    /// Only fields which are set are included.
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+struct CaptureState {
+    records: Mutex<Vec<CapturedRecord>>,
+    cond: Condvar,
+}
+
+/// Handle to the log messages received by a capture writer. Cloning the handle
+/// gives access to the same records.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureHandle {
+    state: Arc<CaptureState>,
+}
+
+impl CaptureHandle {
+    fn push(&self, record: CapturedRecord, capacity: usize) {
+        let mut records = self.state.records.lock();
+        if capacity > 0 && records.len() >= capacity {
+            let excess = records.len() + 1 - capacity;
+            records.drain(..excess);
+        }
+        records.push(record);
+        self.state.cond.notify_all();
+    }
+
+    /// Copy of all captured log messages.
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        self.state.records.lock().clone()
+    }
+
+    /// Remove and return all captured log messages.
+    pub fn drain(&self) -> Vec<CapturedRecord> {
+        std::mem::take(&mut *self.state.records.lock())
+    }
+
+    /// Remove all captured log messages.
+    pub fn clear(&self) {
+        self.state.records.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.records.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.records.lock().is_empty()
+    }
+
+    /// Captured log messages with given level, domain and message matching regex `pattern`.
+    /// `None` matches any level or domain.
+    pub fn find(
+        &self,
+        level: Option<u8>,
+        domain: Option<&str>,
+        pattern: &str,
+    ) -> Result<Vec<CapturedRecord>, LoggingError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| LoggingError::InvalidValue(format!("Invalid pattern {pattern:?}: {e}")))?;
+        Ok(self
+            .state
+            .records
+            .lock()
+            .iter()
+            .filter(|r| level.is_none_or(|l| l == r.level))
+            .filter(|r| domain.is_none_or(|d| d == r.domain))
+            .filter(|r| regex.is_match(&r.message))
+            .cloned()
+            .collect())
+    }
+
+    /// Check whether a matching log message has been captured. See [`CaptureHandle::find`].
+    pub fn contains(&self, level: Option<u8>, domain: Option<&str>, pattern: &str) -> bool {
+        self.find(level, domain, pattern)
+            .is_ok_and(|records| !records.is_empty())
+    }
+
+    /// Panic if no matching log message has been captured. See [`CaptureHandle::find`].
+    #[track_caller]
+    pub fn assert_logged(&self, level: Option<u8>, domain: Option<&str>, pattern: &str) {
+        if !self.contains(level, domain, pattern) {
+            panic!(
+                "No log message with level={level:?} domain={domain:?} matching {pattern:?} captured:\n{}",
+                self.dump()
+            );
+        }
+    }
+
+    /// Panic if a matching log message has been captured. See [`CaptureHandle::find`].
+    #[track_caller]
+    pub fn assert_not_logged(&self, level: Option<u8>, domain: Option<&str>, pattern: &str) {
+        if self.contains(level, domain, pattern) {
+            panic!(
+                "Unexpected log message with level={level:?} domain={domain:?} matching {pattern:?} captured:\n{}",
+                self.dump()
+            );
+        }
+    }
+
+    /// Wait until at least `count` log messages have been captured.
+    /// Log messages are delivered by the logging thread, so this is needed before checking them.
+    pub fn wait_for(&self, count: usize, timeout: f64) -> Result<(), LoggingError> {
+        let deadline = Instant::now() + Duration::from_secs_f64(timeout);
+        let mut records = self.state.records.lock();
+        while records.len() < count {
+            if self
+                .state
+                .cond
+                .wait_until(&mut records, deadline)
+                .timed_out()
+            {
+                return Err(LoggingError::RecvError(format!(
+                    "Timeout while waiting for {count} log messages. Captured {}.",
+                    records.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn dump(&self) -> String {
+        self.state
+            .records
+            .lock()
+            .iter()
+            .map(|r| format!("  {} {}: {}", r.level, r.domain, r.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureWriterConfig {
+    /// Only capture log messages if enabled is true
+    pub enabled: bool,
+    /// Log level for filtering log messages
+    pub level: u8,
+    /// Optional filter log messages by domain
+    pub domain_filter: Option<String>,
+    /// Optional filter log messages by their contents
+    pub message_filter: Option<String>,
+    /// Optional message structure. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<MessageStructEnum>,
+    /// Optional level naming. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level2sym: Option<LevelSyms>,
+    /// Optional strftime format of the timestamp. Default is "%Y.%m.%d %H:%M:%S".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+    /// Maximum number of captured log messages. Oldest are removed first. 0 means no limit.
+    pub capacity: usize,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub(crate) handle: CaptureHandle,
+    /// Debug level. Only for developers.
+    pub debug: u8,
+}
+
+impl CaptureWriterConfig {
+    pub fn new(level: u8) -> Self {
+        Self {
+            level,
+            ..Default::default()
+        }
+    }
+
+    /// Create builder for capture writer configuration.
+    pub fn builder() -> CaptureWriterConfigBuilder {
+        CaptureWriterConfigBuilder::default()
+    }
+
+    /// Handle to the log messages captured by writers created from this configuration.
+    pub fn handle(&self) -> CaptureHandle {
+        self.handle.clone()
+    }
+}
+
+impl Default for CaptureWriterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            level: NOTSET,
+            domain_filter: None,
+            message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
+            capacity: 0,
+            handle: CaptureHandle::default(),
+            debug: 0,
+        }
+    }
+}
+
+impl fmt::Display for CaptureWriterConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Builder for [`CaptureWriterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct CaptureWriterConfigBuilder {
+    config: CaptureWriterConfig,
+}
+
+impl CaptureWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    /// Maximum number of captured log messages. 0 means no limit.
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.config.capacity = capacity;
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Validate settings and create capture writer configuration.
+    pub fn build(&self) -> Result<CaptureWriterConfig, LoggingError> {
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        Ok(self.config.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct CaptureWriter {
+    pub(crate) config: Arc<RwLock<CaptureWriterConfig>>,
+    handle: CaptureHandle,
+    pub(crate) debug: u8,
+}
+
+impl CaptureWriter {
+    pub fn new(config: CaptureWriterConfig) -> Self {
+        let handle = config.handle.clone();
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            handle,
+            debug: 0,
+        }
+    }
+
+    pub fn shutdown(&mut self) -> Result<(), LoggingError> {
+        Ok(())
+    }
+
+    pub fn sync(&self, _timeout: f64) -> Result<(), LoggingError> {
+        Ok(())
+    }
+
+    pub fn enable(&self) {
+        self.config.write().enabled = true;
+    }
+
+    pub fn disable(&self) {
+        self.config.write().enabled = false;
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.config.write().enabled = enabled;
+    }
+
+    pub fn set_level(&self, level: u8) {
+        self.config.write().level = level;
+    }
+
+    pub fn set_domain_filter(&self, domain_filter: Option<String>) -> Result<(), regex::Error> {
+        if let Some(ref message) = domain_filter {
+            Regex::new(message)?;
+        }
+        self.config.write().domain_filter = domain_filter;
+        Ok(())
+    }
+
+    pub fn set_message_filter(&self, message_filter: Option<String>) -> Result<(), regex::Error> {
+        if let Some(ref message) = message_filter {
+            Regex::new(message)?;
+        }
+        self.config.write().message_filter = message_filter;
+        Ok(())
+    }
+
+    /// Handle to the captured log messages.
+    pub fn handle(&self) -> CaptureHandle {
+        self.handle.clone()
+    }
+
+    pub fn send(&self, record: CapturedRecord) -> Result<(), LoggingError> {
+        let config = self.config.read();
+        if !config.enabled {
+            return Ok(());
+        }
+        if let Some(ref domain_filter) = config.domain_filter
+            && !Regex::new(domain_filter).unwrap().is_match(&record.domain)
+        {
+            return Ok(());
+        }
+        if let Some(ref message_filter) = config.message_filter
+            && !Regex::new(message_filter)
+                .unwrap()
+                .is_match(&record.message)
+        {
+            return Ok(());
+        }
+        self.handle.push(record, config.capacity);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fmt, io};
+
+    use crate::{
+        CaptureWriterConfig, DEBUG, ERROR, EXCEPTION, ExtConfig, INFO, Logging, MessageStructEnum,
+        NOTSET, WARNING, root,
+    };
+
+    #[derive(Debug)]
+    struct ConfigError(io::Error);
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Failed to load config")
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn capture() {
+        let config = CaptureWriterConfig::builder()
+            .level(DEBUG)
+            .capacity(3)
+            .build()
+            .unwrap();
+        let handle = config.handle();
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("capture")
+            .writer(config)
+            .build()
+            .unwrap();
+        logging.trace("Trace Message").unwrap();
+        logging.debug("Debug Message").unwrap();
+        logging.info("Info Message").unwrap();
+        logging.warning("Warning Message 42").unwrap();
+        logging.error("Error Message").unwrap();
+        handle.wait_for(3, 5.0).unwrap();
+        assert!(handle.wait_for(4, 0.1).is_err());
+        handle.assert_logged(Some(WARNING), Some("capture"), r"Message \d+$");
+        handle.assert_logged(None, None, "^Error");
+        handle.assert_not_logged(None, None, "Debug");
+        handle.assert_not_logged(Some(INFO), Some("other"), "Info");
+        let records = handle.drain();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].level, ERROR);
+        assert!(records[2].formatted.ends_with("capture: E Error Message"));
+        assert!(handle.is_empty());
+        logging.info("Info Message").unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        handle.clear();
+        assert_eq!(handle.len(), 0);
+        logging.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn fields() {
+        let config = CaptureWriterConfig::builder().build().unwrap();
+        let handle = config.handle();
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("fields")
+            .ext_config(ExtConfig::new(
+                MessageStructEnum::String,
+                false,
+                true,
+                true,
+                true,
+                true,
+            ))
+            .writer(config)
+            .build()
+            .unwrap();
+        logging.info("Info Message").unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        let record = handle.records().pop().unwrap();
+        assert!(!record.fields.contains_key("host"));
+        assert_eq!(record.fields["pid"], std::process::id().to_string());
+        assert!(record.fields.contains_key("pname"));
+        assert!(record.fields.contains_key("tname"));
+        assert!(record.fields["tid"].parse::<u32>().unwrap() > 0);
+        logging.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn escaping() {
+        let mut handles = Vec::new();
+        let mut builder = Logging::builder();
+        builder.level(NOTSET).domain("a<b>&\"c\"");
+        for structured in [MessageStructEnum::Json, MessageStructEnum::Xml] {
+            let config = CaptureWriterConfig::builder()
+                .structured(structured)
+                .time_format("\"%Y\" \\ <%m>")
+                .build()
+                .unwrap();
+            handles.push(config.handle());
+            builder.writer(config);
+        }
+        let mut logging = builder.build().unwrap();
+        let message = "Quote \" backslash \\ tab \t <tag> & more\nnext line\u{1}";
+        logging.info(message).unwrap();
+        for handle in handles.iter() {
+            handle.wait_for(1, 5.0).unwrap();
+        }
+        let record = handles[0].records().pop().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&record.formatted).unwrap();
+        assert_eq!(value["domain"], "a<b>&\"c\"");
+        assert_eq!(value["message"], message);
+        assert!(value["date"].as_str().unwrap().starts_with('"'));
+        assert!(record.formatted.contains(r#"\nnext line\u0001""#));
+        let record = handles[1].records().pop().unwrap();
+        assert!(
+            record
+                .formatted
+                .contains("<domain>a&lt;b&gt;&amp;\"c\"</domain>")
+        );
+        assert!(record.formatted.contains("\" \\ &lt;"));
+        assert!(
+            record
+                .formatted
+                .contains("<message>Quote \" backslash \\ tab \t &lt;tag&gt; &amp; more\n")
+        );
+        logging.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn root_capture() {
+        let (wid, handle) = root::add_capture_writer(INFO).unwrap();
+        root::debug("Root Debug Message").unwrap();
+        root::info("Root Info Message").unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        handle.assert_logged(Some(INFO), Some("root"), "^Root Info Message$");
+        handle.assert_not_logged(None, None, "^Root Debug");
+        assert!(root::remove_writer(wid).is_some());
+    }
+
+    #[test]
+    fn exception_err() {
+        let mut handles = Vec::new();
+        let mut builder = Logging::builder();
+        builder.level(NOTSET).domain("error");
+        for structured in [
+            MessageStructEnum::String,
+            MessageStructEnum::Json,
+            MessageStructEnum::Xml,
+        ] {
+            let config = CaptureWriterConfig::builder()
+                .structured(structured)
+                .build()
+                .unwrap();
+            handles.push(config.handle());
+            builder.writer(config);
+        }
+        let mut logging = builder.build().unwrap();
+        let err = ConfigError(io::Error::new(
+            io::ErrorKind::NotFound,
+            "\"app.conf\" <missing>",
+        ));
+        logging.exception_err(&err).unwrap();
+        for handle in handles.iter() {
+            handle.wait_for(1, 5.0).unwrap();
+        }
+        let record = handles[0].records().pop().unwrap();
+        assert_eq!(record.level, EXCEPTION);
+        assert_eq!(record.message, "Failed to load config");
+        let error = record.error.unwrap();
+        assert_eq!(error.causes, vec!["\"app.conf\" <missing>".to_string()]);
+        let lines: Vec<_> = record.formatted.lines().collect();
+        assert!(lines[0].ends_with("error: ! Failed to load config"));
+        assert_eq!(lines[1], "  caused by: \"app.conf\" <missing>");
+        let record = handles[1].records().pop().unwrap();
+        assert!(
+            record.formatted.contains(
+                r#""message":"Failed to load config","causes":["\"app.conf\" <missing>"]"#
+            )
+        );
+        let record = handles[2].records().pop().unwrap();
+        assert!(record.formatted.contains(
+            "<message>Failed to load config</message><causes><cause>\"app.conf\" &lt;missing&gt;</cause></causes>"
+        ));
+        logging.shutdown(false).unwrap();
+    }
+}
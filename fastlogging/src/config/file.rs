@@ -3,14 +3,17 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::str;
 
-const CONFIG_FILE_SIZE_MAX: u64 = 4096;
+const CONFIG_FILE_SIZE_MAX: u64 = 1024 * 1024;
+const INCLUDE_DEPTH_MAX: usize = 8;
 
 use crate::LoggingError;
 use crate::WriterConfigEnum;
 use crate::WriterEnum;
 use crate::WriterTypeEnum;
 use crate::callback::CallbackWriter;
+use crate::capture::CaptureWriter;
 use crate::level2string;
+use crate::recorder::RecorderWriter;
 use crate::{
     ClientWriter, ConsoleWriter, FileWriter, LevelSyms, LoggingServer, MessageStructEnum, NOTSET,
     SyslogWriter,
@@ -19,15 +22,27 @@ use crate::{
 use super::LoggingInstance;
 
 #[repr(C)]
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum FileMerge {
-    Replace,      // Replace complete configuration by config file contents
-    Merge,        // Add only new writers
+    Replace, // Replace complete configuration by config file contents
+    Merge,   // Add only new writers
+    #[default]
     MergeReplace, // Add new writers and replace existing writers
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncludeConfig {
+    /// Path to config file fragment. Relative paths are resolved against the including file.
+    pub path: PathBuf,
+    /// How to merge the fragment into the including configuration.
+    #[serde(default)]
+    pub merge: FileMerge,
+}
+
 #[repr(C)]
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FileConfig {
     pub(crate) level: u8,
     pub(crate) domain: String,
@@ -39,6 +54,8 @@ pub struct FileConfig {
     pub(crate) structured: MessageStructEnum,
     pub(crate) level2sym: LevelSyms,
     pub(crate) configs: Vec<WriterConfigEnum>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) include: Vec<IncludeConfig>,
 }
 
 impl Default for FileConfig {
@@ -54,8 +71,190 @@ impl Default for FileConfig {
             structured: MessageStructEnum::String,
             level2sym: LevelSyms::Sym,
             configs: Vec::new(),
+            include: Vec::new(),
+        }
+    }
+}
+
+impl FileConfig {
+    /// Merge config file fragment `fragment` into this configuration.
+    /// With `Replace` the fragment replaces this configuration completely. With `Merge` and
+    /// `MergeReplace` the global settings of the fragment are only used where this
+    /// configuration keeps the defaults. `Merge` only adds writers of the fragment which
+    /// are missing, `MergeReplace` also replaces existing writers by those of the fragment.
+    fn merge_fragment(&mut self, fragment: FileConfig, merge: &FileMerge) {
+        if *merge == FileMerge::Replace {
+            *self = fragment;
+            return;
+        }
+        if self.level == NOTSET {
+            self.level = fragment.level;
+        }
+        if self.domain.is_empty() {
+            self.domain = fragment.domain;
+        }
+        if self.hostname.is_none() {
+            self.hostname = fragment.hostname;
+        }
+        if self.pname.is_empty() {
+            self.pname = fragment.pname;
+        }
+        if self.pid == 0 {
+            self.pid = fragment.pid;
+        }
+        self.tname |= fragment.tname;
+        self.tid |= fragment.tid;
+        if self.structured == MessageStructEnum::String {
+            self.structured = fragment.structured;
+        }
+        if self.level2sym == LevelSyms::Sym {
+            self.level2sym = fragment.level2sym;
+        }
+        for config in fragment.configs {
+            let typ = config.typ();
+            match self.configs.iter().position(|c| c.typ() == typ) {
+                Some(index) => {
+                    if *merge == FileMerge::MergeReplace {
+                        self.configs[index] = config;
+                    }
+                }
+                None => self.configs.push(config),
+            }
+        }
+    }
+}
+
+/// Expand environment variable references `${VAR}` and `${VAR:-default}` in string value `data`.
+/// The default value is used if the variable is not set or empty. `$${` is replaced by a literal `${`.
+fn expand_env_vars(data: &str) -> Result<String, LoggingError> {
+    let mut result = String::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(tail) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("${") {
+            let end = tail.find('}').ok_or_else(|| {
+                LoggingError::ConfigError(
+                    "Unterminated environment variable reference in config file".to_string(),
+                )
+            })?;
+            let (name, default) = match tail[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&tail[..end], None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(LoggingError::ConfigError(format!(
+                    "Invalid environment variable name {name:?} in config file"
+                )));
+            }
+            match (std::env::var(name), default) {
+                (Ok(value), None) => result.push_str(&value),
+                (Ok(value), Some(_)) if !value.is_empty() => result.push_str(&value),
+                (_, Some(default)) => result.push_str(default),
+                (Err(_), None) => {
+                    return Err(LoggingError::ConfigError(format!(
+                        "Environment variable {name} referenced in config file is not set"
+                    )));
+                }
+            }
+            rest = &tail[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Expand environment variables in all string values of `value`.
+#[cfg(feature = "config_json")]
+fn expand_json(value: &mut serde_json::Value) -> Result<(), LoggingError> {
+    match value {
+        serde_json::Value::String(data) => *data = expand_env_vars(data)?,
+        serde_json::Value::Array(values) => {
+            for value in values.iter_mut() {
+                expand_json(value)?;
+            }
         }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                expand_json(value)?;
+            }
+        }
+        _ => {}
     }
+    Ok(())
+}
+
+/// Expand environment variables in all string values of `value`.
+#[cfg(feature = "config_yaml")]
+fn expand_yaml(value: &mut serde_yaml::Value) -> Result<(), LoggingError> {
+    match value {
+        serde_yaml::Value::String(data) => *data = expand_env_vars(data)?,
+        serde_yaml::Value::Sequence(values) => {
+            for value in values.iter_mut() {
+                expand_yaml(value)?;
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for value in map.values_mut() {
+                expand_yaml(value)?;
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => expand_yaml(&mut tagged.value)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand environment variables in text and attribute values of XML document `data`.
+/// Element names and comments are kept as they are.
+#[cfg(feature = "config_xml")]
+fn expand_xml(data: &str) -> Result<String, LoggingError> {
+    use quick_xml::events::{BytesCData, BytesStart, BytesText, Event};
+
+    fn xml_error<E: fmt::Display>(err: E) -> LoggingError {
+        LoggingError::ConfigError(format!("Invalid XML config file: {err}"))
+    }
+
+    fn expand_attributes(start: &BytesStart) -> Result<BytesStart<'static>, LoggingError> {
+        let name = String::from_utf8_lossy(start.name().as_ref()).to_string();
+        let mut expanded = BytesStart::new(name);
+        for attr in start.attributes() {
+            let attr = attr.map_err(xml_error)?;
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = attr
+                .normalized_value(quick_xml::XmlVersion::default())
+                .map_err(xml_error)?;
+            let value = expand_env_vars(&value)?;
+            expanded.push_attribute((key.as_str(), value.as_str()));
+        }
+        Ok(expanded)
+    }
+
+    let mut reader = quick_xml::Reader::from_str(data);
+    let mut writer = quick_xml::Writer::new(Vec::new());
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Eof => break,
+            Event::Start(start) => writer.write_event(Event::Start(expand_attributes(&start)?))?,
+            Event::Empty(start) => writer.write_event(Event::Empty(expand_attributes(&start)?))?,
+            Event::Text(text) => {
+                let value = expand_env_vars(&text.decode().map_err(xml_error)?)?;
+                writer.write_event(Event::Text(BytesText::new(&value)))?;
+            }
+            Event::CData(text) => {
+                let value = expand_env_vars(&text.decode().map_err(xml_error)?)?;
+                writer.write_event(Event::CData(BytesCData::new(value)))?;
+            }
+            event => writer.write_event(event)?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).map_err(xml_error)
 }
 
 #[repr(C)]
@@ -92,6 +291,16 @@ pub fn default_config_file() -> (PathBuf, Vec<u8>) {
     (PathBuf::new(), Vec::new())
 }
 
+/// Read config file `path` including its fragments and check all writer configurations.
+/// Returns the writer configurations.
+pub fn check_config_file(path: &Path) -> Result<Vec<WriterConfigEnum>, LoggingError> {
+    let config = ConfigFile::read(path, 0)?;
+    for writer_config in config.configs.iter() {
+        writer_config.check()?;
+    }
+    Ok(config.configs)
+}
+
 impl ConfigFile {
     pub fn new() -> Self {
         Self {
@@ -101,6 +310,13 @@ impl ConfigFile {
     }
 
     pub fn load(&mut self, path: &Path) -> Result<(), LoggingError> {
+        self.config = ConfigFile::read(path, 0)?;
+        self.path = path.to_path_buf();
+        Ok(())
+    }
+
+    /// Read config file `path`, expand environment variables and merge included fragments.
+    fn read(path: &Path, depth: usize) -> Result<FileConfig, LoggingError> {
         // Initialize settings with default settings from optional config file.
         let (path, lextension) = if let Some(extension) = path.extension() {
             (
@@ -139,7 +355,7 @@ impl ConfigFile {
                 )));
             }
         };
-        self.config = match if lextension == b"json" {
+        let mut config = match if lextension == b"json" {
             ConfigFile::from_json(&path, &data)
         } else if lextension == b"xml" {
             ConfigFile::from_xml(&path, &data)
@@ -158,8 +374,20 @@ impl ConfigFile {
                 )));
             }
         };
-        self.path = path;
-        Ok(())
+        for include in std::mem::take(&mut config.include) {
+            if depth >= INCLUDE_DEPTH_MAX {
+                return Err(LoggingError::ConfigError(format!(
+                    "Config file {path:?} exceeds maximum include depth {INCLUDE_DEPTH_MAX}"
+                )));
+            }
+            let include_path = match path.parent() {
+                Some(parent) if include.path.is_relative() => parent.join(&include.path),
+                _ => include.path,
+            };
+            let fragment = ConfigFile::read(&include_path, depth + 1)?;
+            config.merge_fragment(fragment, &include.merge);
+        }
+        Ok(config)
     }
 
     pub fn save(&mut self, path: Option<&Path>) -> Result<(), LoggingError> {
@@ -209,16 +437,8 @@ impl ConfigFile {
             instance.tid = self.config.tid;
             instance.structured = self.config.structured.clone();
             instance.level2sym = self.config.level2sym.clone();
-            for (_wid, writer) in instance.writers.drain() {
-                match writer {
-                    WriterEnum::Root => {}
-                    WriterEnum::Console(mut console_writer) => console_writer.shutdown()?,
-                    WriterEnum::File(mut file_writer) => file_writer.shutdown()?,
-                    WriterEnum::Client(mut client_writer) => client_writer.shutdown()?,
-                    WriterEnum::Server(mut logging_server) => logging_server.shutdown()?,
-                    WriterEnum::Callback(mut callback_writer) => callback_writer.shutdown()?,
-                    WriterEnum::Syslog(mut syslog_writer) => syslog_writer.shutdown()?,
-                }
+            for (_wid, mut writer) in instance.writers.drain() {
+                writer.shutdown()?;
             }
         } else {
             if self.config.level != NOTSET {
@@ -254,9 +474,7 @@ impl ConfigFile {
                     }
                 }
                 WriterConfigEnum::File(file_config) => {
-                    let configs = instance.get_filtered_writer_configs(WriterTypeEnum::File(
-                        file_config.path.to_str().unwrap().to_string(),
-                    ));
+                    let configs = instance.get_filtered_writer_configs(config.typ());
                     if merge == FileMerge::MergeReplace {
                         instance.remove_writers(Some(configs.into_keys().collect::<Vec<_>>()));
                     } else if merge == FileMerge::Merge && configs.is_empty() {
@@ -267,9 +485,7 @@ impl ConfigFile {
                     }
                 }
                 WriterConfigEnum::Client(client_config) => {
-                    let configs = instance.get_filtered_writer_configs(WriterTypeEnum::Client(
-                        client_config.get_address_port(),
-                    ));
+                    let configs = instance.get_filtered_writer_configs(config.typ());
                     if merge == FileMerge::MergeReplace {
                         instance.remove_writers(Some(configs.into_keys().collect::<Vec<_>>()));
                     } else if merge == FileMerge::Merge && configs.is_empty() {
@@ -280,9 +496,7 @@ impl ConfigFile {
                     }
                 }
                 WriterConfigEnum::Server(server_config) => {
-                    let configs = instance.get_filtered_writer_configs(WriterTypeEnum::Server(
-                        server_config.get_address_port(),
-                    ));
+                    let configs = instance.get_filtered_writer_configs(config.typ());
                     if merge == FileMerge::MergeReplace {
                         instance.remove_writers(Some(configs.into_keys().collect::<Vec<_>>()));
                     } else if merge == FileMerge::Merge && configs.is_empty() {
@@ -315,6 +529,27 @@ impl ConfigFile {
                         )?)));
                     }
                 }
+                WriterConfigEnum::Recorder(recorder_config) => {
+                    let configs = instance.get_filtered_writer_configs(WriterTypeEnum::Recorder);
+                    if merge == FileMerge::MergeReplace {
+                        instance.remove_writers(Some(configs.into_keys().collect::<Vec<_>>()));
+                    } else if merge == FileMerge::Merge && configs.is_empty() {
+                        instance.add_writer(WriterEnum::Recorder(Box::new(RecorderWriter::new(
+                            recorder_config.clone(),
+                            instance.server_tx.clone(),
+                        )?)));
+                    }
+                }
+                WriterConfigEnum::Capture(capture_config) => {
+                    let configs = instance.get_filtered_writer_configs(WriterTypeEnum::Capture);
+                    if merge == FileMerge::MergeReplace {
+                        instance.remove_writers(Some(configs.into_keys().collect::<Vec<_>>()));
+                    } else if merge == FileMerge::Merge && configs.is_empty() {
+                        instance.add_writer(WriterEnum::Capture(Box::new(CaptureWriter::new(
+                            capture_config.clone(),
+                        ))));
+                    }
+                }
             }
         }
         Ok(())
@@ -337,15 +572,26 @@ impl ConfigFile {
                     .get_writer_configs()
                     .into_values()
                     .collect::<Vec<_>>(),
+                include: Vec::new(),
             },
         }
     }
 
+    /// Parse JSON config file `data` and expand environment variables in string values.
     pub fn from_json(path: &Path, data: &str) -> Result<FileConfig, LoggingError> {
         #[cfg(feature = "config_json")]
-        let file_data = serde_json::from_str(data).map_err(|e| {
-            LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
-        });
+        let file_data = serde_json::from_str(data)
+            .map_err(|e| {
+                LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
+            })
+            .and_then(|mut value| {
+                expand_json(&mut value)?;
+                serde_json::from_value(value).map_err(|e| {
+                    LoggingError::InvalidValue(format!(
+                        "Failed to read config file {path:?}: {e:?}"
+                    ))
+                })
+            });
         #[cfg(not(feature = "config_json"))]
         let file_data = Err(LoggingError::InvalidValue(
             "Support for JSON type config files is not enabled".to_string(),
@@ -365,10 +611,13 @@ impl ConfigFile {
         data
     }
 
+    /// Parse XML config file `data` and expand environment variables in values.
     pub fn from_xml(path: &Path, data: &str) -> Result<FileConfig, LoggingError> {
         #[cfg(feature = "config_xml")]
-        let file_data = quick_xml::de::from_str(data).map_err(|e| {
-            LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
+        let file_data = expand_xml(data).and_then(|data| {
+            quick_xml::de::from_str(&data).map_err(|e| {
+                LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
+            })
         });
         #[cfg(not(feature = "config_xml"))]
         let file_data = Err(LoggingError::InvalidValue(
@@ -389,11 +638,21 @@ impl ConfigFile {
         data
     }
 
+    /// Parse YAML config file `data` and expand environment variables in string values.
     pub fn from_yaml(path: &Path, data: &str) -> Result<FileConfig, LoggingError> {
         #[cfg(feature = "config_yaml")]
-        let file_data = serde_yaml::from_str(data).map_err(|e| {
-            LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
-        });
+        let file_data = serde_yaml::from_str(data)
+            .map_err(|e| {
+                LoggingError::InvalidValue(format!("Failed to read config file {path:?}: {e:?}"))
+            })
+            .and_then(|mut value| {
+                expand_yaml(&mut value)?;
+                serde_yaml::from_value(value).map_err(|e| {
+                    LoggingError::InvalidValue(format!(
+                        "Failed to read config file {path:?}: {e:?}"
+                    ))
+                })
+            });
         #[cfg(not(feature = "config_yaml"))]
         let file_data = Err(LoggingError::InvalidValue(
             "Support for YAML type config files is not enabled".to_string(),
@@ -442,3 +701,185 @@ impl fmt::Display for ConfigFile {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Mutex};
+
+    use crate::{DEBUG, LevelSyms, MessageStructEnum, WARNING, WriterConfigEnum, WriterTypeEnum};
+
+    use super::{ConfigFile, FileMerge, expand_env_vars};
+
+    /// Serializes tests which modify environment variables.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn env_vars() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        unsafe {
+            std::env::set_var("FASTLOGGING_TEST_ENV_VARS_DIR", "C:\\logs \"x\"");
+            std::env::set_var("FASTLOGGING_TEST_ENV_VARS_EMPTY", "");
+        }
+        assert_eq!(
+            expand_env_vars("${FASTLOGGING_TEST_ENV_VARS_DIR}/a.log").unwrap(),
+            "C:\\logs \"x\"/a.log"
+        );
+        assert_eq!(
+            expand_env_vars("${FASTLOGGING_TEST_ENV_VARS_EMPTY:-/tmp}").unwrap(),
+            "/tmp"
+        );
+        assert_eq!(
+            expand_env_vars("${FASTLOGGING_TEST_ENV_VARS_UNSET:-}x").unwrap(),
+            "x"
+        );
+        assert_eq!(expand_env_vars("$${HOME} $5").unwrap(), "${HOME} $5");
+        assert!(expand_env_vars("${FASTLOGGING_TEST_ENV_VARS_UNSET}").is_err());
+        assert!(expand_env_vars("${FASTLOGGING_TEST_ENV_VARS_DIR").is_err());
+    }
+
+    #[test]
+    fn env_vars_in_values() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let value = "a: b # \"c\" \\ <d> & 'e'\nf";
+        unsafe {
+            std::env::set_var("FASTLOGGING_TEST_VALUES_DOMAIN", value);
+        }
+        // Unset variables in keys and comments are not expanded.
+        let config = ConfigFile::from_json(
+            Path::new("test.json"),
+            r#"{"level": 10, "domain": "${FASTLOGGING_TEST_VALUES_DOMAIN}",
+            "${FASTLOGGING_TEST_VALUES_UNSET}": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(config.level, DEBUG);
+        assert_eq!(config.domain, value);
+        let config = ConfigFile::from_yaml(
+            Path::new("test.yaml"),
+            "# ${FASTLOGGING_TEST_VALUES_UNSET}\n\
+             level: 10\n\
+             domain: ${FASTLOGGING_TEST_VALUES_DOMAIN}\n",
+        )
+        .unwrap();
+        assert_eq!(config.level, DEBUG);
+        assert_eq!(config.domain, value);
+        let config = ConfigFile::from_xml(
+            Path::new("test.xml"),
+            "<FileConfig><!-- ${FASTLOGGING_TEST_VALUES_UNSET} -->\
+             <level>10</level><domain>${FASTLOGGING_TEST_VALUES_DOMAIN}</domain></FileConfig>",
+        )
+        .unwrap();
+        assert_eq!(config.level, DEBUG);
+        assert_eq!(config.domain, value);
+    }
+
+    #[test]
+    fn include() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        unsafe {
+            std::env::set_var("FASTLOGGING_TEST_INCLUDE_DOMAIN", "main");
+        }
+        fs::create_dir(temp_dir.path().join("conf.d")).unwrap();
+        fs::write(
+            temp_dir.path().join("conf.d/console.json"),
+            r#"{"level": 30, "domain": "console", "hostname": "fragment",
+            "configs": [{"Console": {"enabled": true, "level": 30,
+            "domain_filter": null, "message_filter": null, "colors": false,
+            "target": "StdErr", "debug": 0}}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("conf.d/nested.json"),
+            r#"{"include": [{"path": "console.json", "merge": "Merge"}]}"#,
+        )
+        .unwrap();
+        let path = temp_dir.path().join("main.json");
+        fs::write(
+            &path,
+            r#"{"level": 10, "domain": "${FASTLOGGING_TEST_INCLUDE_DOMAIN}", "configs": [
+            {"Console": {"enabled": true, "level": 20, "domain_filter": null,
+            "message_filter": null, "colors": true, "target": "StdOut", "debug": 0}}],
+            "include": [{"path": "conf.d/nested.json"}]}"#,
+        )
+        .unwrap();
+        let mut config_file = ConfigFile::new();
+        config_file.load(&path).unwrap();
+        let config = &config_file.config;
+        // Settings of the including file take precedence, fragments only fill in.
+        assert_eq!(config.domain, "main");
+        assert_eq!(config.level, DEBUG);
+        assert_eq!(config.hostname.as_deref(), Some("fragment"));
+        assert!(config.include.is_empty());
+        assert_eq!(config.configs.len(), 1);
+        assert_eq!(config.configs[0].typ(), WriterTypeEnum::Console);
+        match &config.configs[0] {
+            WriterConfigEnum::Console(console_config) => {
+                assert_eq!(console_config.level, WARNING);
+                assert!(!console_config.colors);
+            }
+            config => panic!("Unexpected writer config {config:?}"),
+        }
+    }
+
+    #[test]
+    fn include_merge() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("fragment.json"),
+            r#"{"level": 30, "domain": "fragment", "pname": "app", "tname": true, "tid": true,
+            "structured": "Json", "level2sym": "Str",
+            "configs": [{"Console": {"enabled": true, "level": 30,
+            "domain_filter": null, "message_filter": null, "colors": false,
+            "target": "StdErr", "debug": 0}}]}"#,
+        )
+        .unwrap();
+        for merge in [
+            FileMerge::Replace,
+            FileMerge::Merge,
+            FileMerge::MergeReplace,
+        ] {
+            let path = temp_dir.path().join("main.json");
+            fs::write(
+                &path,
+                format!(
+                    r#"{{"level": 10, "domain": "main", "structured": "Xml", "configs": [
+                    {{"Console": {{"enabled": true, "level": 20, "domain_filter": null,
+                    "message_filter": null, "colors": true, "target": "StdOut", "debug": 0}}}}],
+                    "include": [{{"path": "fragment.json", "merge": "{merge:?}"}}]}}"#
+                ),
+            )
+            .unwrap();
+            let mut config_file = ConfigFile::new();
+            config_file.load(&path).unwrap();
+            let config = &config_file.config;
+            // Settings which the including file keeps at their defaults are always filled in.
+            assert_eq!(config.pname, "app", "{merge:?}");
+            assert!(config.tname && config.tid, "{merge:?}");
+            assert_eq!(config.level2sym, LevelSyms::Str, "{merge:?}");
+            let (level, domain, structured, colors) = match merge {
+                FileMerge::Replace => (WARNING, "fragment", MessageStructEnum::Json, false),
+                FileMerge::Merge => (DEBUG, "main", MessageStructEnum::Xml, true),
+                FileMerge::MergeReplace => (DEBUG, "main", MessageStructEnum::Xml, false),
+            };
+            assert_eq!(config.level, level, "{merge:?}");
+            assert_eq!(config.domain, domain, "{merge:?}");
+            assert_eq!(config.structured, structured, "{merge:?}");
+            assert_eq!(config.configs.len(), 1, "{merge:?}");
+            match &config.configs[0] {
+                WriterConfigEnum::Console(console_config) => {
+                    assert_eq!(console_config.colors, colors, "{merge:?}")
+                }
+                config => panic!("Unexpected writer config {config:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn include_recursion() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("loop.json");
+        fs::write(&path, r#"{"include": [{"path": "loop.json"}]}"#).unwrap();
+        let mut config_file = ConfigFile::new();
+        assert!(config_file.load(&path).is_err());
+    }
+}
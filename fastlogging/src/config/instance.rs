@@ -11,10 +11,10 @@ use gethostname::gethostname;
 
 use crate::LoggingError;
 use crate::LoggingTypeEnum;
-use crate::ServerConfig;
 use crate::WriterConfigEnum;
 use crate::WriterEnum;
 use crate::WriterTypeEnum;
+use crate::{ClientStats, ServerConfig};
 use crate::{LevelSyms, MessageStructEnum};
 
 use super::ExtConfig;
@@ -178,6 +178,12 @@ impl LoggingInstance {
                     wtype == WriterTypeEnum::Callback
                 }
                 WriterConfigEnum::Syslog(_syslog_writer_config) => wtype == WriterTypeEnum::Syslog,
+                WriterConfigEnum::Recorder(_recorder_writer_config) => {
+                    wtype == WriterTypeEnum::Recorder
+                }
+                WriterConfigEnum::Capture(_capture_writer_config) => {
+                    wtype == WriterTypeEnum::Capture
+                }
             })
             .collect()
     }
@@ -215,6 +221,18 @@ impl LoggingInstance {
         }
     }
 
+    pub fn get_server_client_stats(&self, wid: usize) -> Result<Vec<ClientStats>, LoggingError> {
+        match self.writers.get(&wid) {
+            Some(WriterEnum::Server(server)) => Ok(server.client_stats()),
+            Some(writer) => Err(LoggingError::InvalidValue(format!(
+                "Writer wid={wid} has invalid type {writer:?}"
+            ))),
+            None => Err(LoggingError::InvalidValue(format!(
+                "Writer {wid} does not exist"
+            ))),
+        }
+    }
+
     pub fn get_server_configs(&self) -> HashMap<usize, ServerConfig> {
         self.writers
             .iter()
@@ -1,6 +1,6 @@
 mod common;
 pub use common::ExtConfig;
 mod file;
-pub use file::{default_config_file, ConfigFile, FileMerge};
+pub use file::{check_config_file, default_config_file, ConfigFile, FileMerge};
 mod instance;
 pub use instance::{LoggingConfig, LoggingInstance};
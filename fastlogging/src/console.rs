@@ -1,6 +1,6 @@
 use std::{
-    fmt,
-    io::Write,
+    env, fmt,
+    io::{self, IsTerminal, Write},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -12,16 +12,27 @@ use std::{
 use flume::{Receiver, SendError, Sender, bounded};
 use parking_lot::RwLock;
 use regex::Regex;
-use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use termcolor::{Buffer, BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
+#[cfg(target_family = "unix")]
+use crate::unix::terminal_width;
+#[cfg(target_family = "windows")]
+use crate::windows::terminal_width;
 use crate::{
-    CRITICAL, DEBUG, ERROR, EXCEPTION, INFO, LoggingError, NOTSET, SUCCESS, TRACE, WARNING,
+    CRITICAL, DEBUG, ERROR, EXCEPTION, INFO, LevelSyms, LoggingError, MessageStructEnum, NOTSET,
+    PrettyLayout, SUCCESS, TRACE, WARNING,
+    def::{check_filters, check_time_format},
+    logging::DEFAULT_TIME_FORMAT,
+    pretty::write_pretty,
+    reader::LogRecord,
 };
 
 #[derive(Debug)]
 pub enum ConsoleTypeEnum {
-    Message((u8, String, String)), // level, domain, message
-    Sync,                          // timeout
+    Message((u8, String, String)),       // level, domain, message
+    Record((Box<LogRecord>, LevelSyms)), // record for pretty layout, level naming
+    Sync,                                // timeout
     Stop,
 }
 
@@ -33,6 +44,166 @@ pub enum ConsoleTargetEnum {
     StdErr,
     /// Write log messages to stdout and stderr
     Both,
+    /// Write log messages below `split_level` to stdout and all others to stderr
+    Split,
+}
+
+fn default_split_level() -> u8 {
+    WARNING
+}
+
+/// When to write colored log messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorMode {
+    /// Colors if the stream is a terminal. `NO_COLOR` disables and `CLICOLOR_FORCE`
+    /// enforces colors.
+    #[default]
+    Auto,
+    /// Always write escape codes, even into pipes and files
+    Always,
+    /// Never write escape codes
+    Never,
+}
+
+impl ColorMode {
+    /// Resolve color mode for stdout or stderr (`stderr` is true).
+    pub fn enabled(&self, stderr: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                    false
+                } else if env::var_os("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    true
+                } else if stderr {
+                    io::stderr().is_terminal()
+                } else {
+                    io::stdout().is_terminal()
+                }
+            }
+        }
+    }
+}
+
+/// Name of `color` as parsed by [`Color::from_str`](std::str::FromStr).
+fn color2string(color: &Color) -> String {
+    match color {
+        Color::Black => "black".to_string(),
+        Color::Blue => "blue".to_string(),
+        Color::Green => "green".to_string(),
+        Color::Red => "red".to_string(),
+        Color::Cyan => "cyan".to_string(),
+        Color::Magenta => "magenta".to_string(),
+        Color::Yellow => "yellow".to_string(),
+        Color::Ansi256(value) => value.to_string(),
+        Color::Rgb(r, g, b) => format!("{r},{g},{b}"),
+        _ => "white".to_string(),
+    }
+}
+
+/// Colors are stored as names ("red"), ANSI 256 color numbers ("208") or RGB ("255,128,0").
+mod color_serde {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.as_ref().map(color2string).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|color| color.parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Text style of a part of a log message.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColorStyle {
+    /// Foreground color
+    #[serde(default, with = "color_serde", skip_serializing_if = "Option::is_none")]
+    pub fg: Option<Color>,
+    /// Background color
+    #[serde(default, with = "color_serde", skip_serializing_if = "Option::is_none")]
+    pub bg: Option<Color>,
+    /// Bold text
+    #[serde(default)]
+    pub bold: bool,
+}
+
+impl ColorStyle {
+    pub fn new(fg: Option<Color>, bg: Option<Color>, bold: bool) -> Self {
+        Self { fg, bg, bold }
+    }
+
+    fn fg(color: Color) -> Self {
+        Self::new(Some(color), None, false)
+    }
+
+    pub(crate) fn spec(&self) -> ColorSpec {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(self.fg).set_bg(self.bg).set_bold(self.bold);
+        spec
+    }
+}
+
+/// Styles of log messages per level. Timestamps and domains of string messages are
+/// highlighted if their style is set. The timestamp style also applies to hostname, process
+/// and thread, if the log messages contain them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorTheme {
+    pub trace: ColorStyle,
+    pub debug: ColorStyle,
+    pub info: ColorStyle,
+    pub success: ColorStyle,
+    pub warning: ColorStyle,
+    pub error: ColorStyle,
+    pub critical: ColorStyle,
+    pub exception: ColorStyle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<ColorStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<ColorStyle>,
+}
+
+impl ColorTheme {
+    /// Style of log messages with `level`.
+    pub fn style(&self, level: u8) -> &ColorStyle {
+        match level {
+            EXCEPTION.. => &self.exception,
+            CRITICAL.. => &self.critical,
+            ERROR.. => &self.error,
+            WARNING.. => &self.warning,
+            SUCCESS.. => &self.success,
+            INFO.. => &self.info,
+            DEBUG.. => &self.debug,
+            _ => &self.trace,
+        }
+    }
+}
+
+impl Default for ColorTheme {
+    /// Foreground colors of [`level2color`].
+    fn default() -> Self {
+        Self {
+            trace: ColorStyle::fg(level2color(TRACE)),
+            debug: ColorStyle::fg(level2color(DEBUG)),
+            info: ColorStyle::fg(level2color(INFO)),
+            success: ColorStyle::fg(level2color(SUCCESS)),
+            warning: ColorStyle::fg(level2color(WARNING)),
+            error: ColorStyle::fg(level2color(ERROR)),
+            critical: ColorStyle::fg(level2color(CRITICAL)),
+            exception: ColorStyle::fg(level2color(EXCEPTION)),
+            timestamp: None,
+            domain: None,
+        }
+    }
 }
 
 #[repr(C)]
@@ -46,10 +217,31 @@ pub struct ConsoleWriterConfig {
     pub domain_filter: Option<String>,
     /// Optional filter log messages by their contents
     pub message_filter: Option<String>,
+    /// Optional message structure. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<MessageStructEnum>,
+    /// Optional level naming. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level2sym: Option<LevelSyms>,
+    /// Optional strftime format of the timestamp. Default is "%Y.%m.%d %H:%M:%S".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
     /// Colored output if true
     pub colors: bool,
+    /// When to write colors if `colors` is true
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    /// Optional color theme. Default are the colors of [`level2color`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ColorTheme>,
     /// Select log message destination (stdout, stderr)
     pub target: ConsoleTargetEnum,
+    /// Lowest level written to stderr by target `Split`. Default is WARNING.
+    #[serde(default = "default_split_level")]
+    pub split_level: u8,
+    /// Optional multi-line layout for local development instead of single lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pretty: Option<PrettyLayout>,
     /// Debug level. Only for developers.
     pub debug: u8,
 }
@@ -61,11 +253,23 @@ impl ConsoleWriterConfig {
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             colors,
+            color_mode: ColorMode::Auto,
+            theme: None,
             target: ConsoleTargetEnum::StdOut,
+            split_level: WARNING,
+            pretty: None,
             debug: 0,
         }
     }
+
+    /// Create builder for console writer configuration.
+    pub fn builder() -> ConsoleWriterConfigBuilder {
+        ConsoleWriterConfigBuilder::default()
+    }
 }
 
 impl Default for ConsoleWriterConfig {
@@ -75,8 +279,15 @@ impl Default for ConsoleWriterConfig {
             level: NOTSET,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             colors: false,
+            color_mode: ColorMode::Auto,
+            theme: None,
             target: ConsoleTargetEnum::StdOut,
+            split_level: WARNING,
+            pretty: None,
             debug: 0,
         }
     }
@@ -88,80 +299,310 @@ impl fmt::Display for ConsoleWriterConfig {
     }
 }
 
-fn console_writer_thread(
+/// Builder for [`ConsoleWriterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleWriterConfigBuilder {
+    config: ConsoleWriterConfig,
+}
+
+impl ConsoleWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    pub fn colors(&mut self, colors: bool) -> &mut Self {
+        self.config.colors = colors;
+        self
+    }
+
+    /// Select when to write colors. Default is [`ColorMode::Auto`].
+    pub fn color_mode(&mut self, color_mode: ColorMode) -> &mut Self {
+        self.config.color_mode = color_mode;
+        self
+    }
+
+    /// Set color styles of levels, timestamps and domains.
+    pub fn theme(&mut self, theme: ColorTheme) -> &mut Self {
+        self.config.theme = Some(theme);
+        self
+    }
+
+    pub fn target(&mut self, target: ConsoleTargetEnum) -> &mut Self {
+        self.config.target = target;
+        self
+    }
+
+    /// Write log messages with `level` and above to stderr, all others to stdout.
+    pub fn split(&mut self, level: u8) -> &mut Self {
+        self.config.target = ConsoleTargetEnum::Split;
+        self.config.split_level = level;
+        self
+    }
+
+    /// Write log messages in multi-line `layout`.
+    pub fn pretty(&mut self, layout: PrettyLayout) -> &mut Self {
+        self.config.pretty = Some(layout);
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Validate settings and create console writer configuration.
+    pub fn build(&self) -> Result<ConsoleWriterConfig, LoggingError> {
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        Ok(self.config.clone())
+    }
+}
+
+/// Terminal color of log messages with `level`.
+pub fn level2color(level: u8) -> Color {
+    match level {
+        TRACE => Color::White,
+        DEBUG => Color::Blue,
+        INFO => Color::Green,
+        SUCCESS => Color::Cyan,
+        WARNING => Color::Yellow,
+        ERROR => Color::Magenta,
+        CRITICAL => Color::Red,
+        EXCEPTION => Color::Red,
+        _ => Color::White,
+    }
+}
+
+fn write_styled(buffer: &mut Buffer, style: &ColorStyle, text: &str) -> io::Result<()> {
+    buffer.set_color(&style.spec())?;
+    buffer.write_all(text.as_bytes())?;
+    buffer.reset()
+}
+
+/// Write `message` with the styles of `theme`. Timestamp and domain are only highlighted
+/// if the message contains " {domain}: ", like string messages do.
+fn write_colored(
+    buffer: &mut Buffer,
+    theme: &ColorTheme,
+    level: u8,
+    domain: &str,
+    message: &str,
+) -> io::Result<()> {
+    let style = theme.style(level);
+    let domain_pos = if theme.timestamp.is_some() || theme.domain.is_some() {
+        message.find(&format!(" {domain}: "))
+    } else {
+        None
+    };
+    if let Some(pos) = domain_pos {
+        write_styled(
+            buffer,
+            theme.timestamp.as_ref().unwrap_or(style),
+            &message[..pos],
+        )?;
+        buffer.write_all(b" ")?;
+        write_styled(buffer, theme.domain.as_ref().unwrap_or(style), domain)?;
+        write_styled(buffer, style, &message[pos + 1 + domain.len()..])?;
+    } else {
+        write_styled(buffer, style, message)?;
+    }
+    writeln!(buffer)
+}
+
+/// Output streams of the console writer. `stderr` selects the stream.
+trait ConsoleStreams: Send + 'static {
+    fn buffer(&self, stderr: bool) -> Buffer;
+
+    fn print(&mut self, stderr: bool, buffer: &Buffer) -> io::Result<()>;
+
+    fn flush(&mut self, stderr: bool) -> io::Result<()>;
+
+    /// Terminal width for wrapping lines of the pretty layout.
+    fn width(&self, stderr: bool) -> Option<usize>;
+}
+
+struct StdStreams {
+    stdout: BufferWriter,
+    stderr: BufferWriter,
+}
+
+impl StdStreams {
+    fn new() -> Self {
+        Self {
+            stdout: BufferWriter::stdout(ColorChoice::Always),
+            stderr: BufferWriter::stderr(ColorChoice::Always),
+        }
+    }
+}
+
+impl ConsoleStreams for StdStreams {
+    fn buffer(&self, stderr: bool) -> Buffer {
+        if stderr {
+            self.stderr.buffer()
+        } else {
+            self.stdout.buffer()
+        }
+    }
+
+    fn print(&mut self, stderr: bool, buffer: &Buffer) -> io::Result<()> {
+        if stderr {
+            self.stderr.print(buffer)
+        } else {
+            self.stdout.print(buffer)
+        }
+    }
+
+    fn flush(&mut self, stderr: bool) -> io::Result<()> {
+        if stderr {
+            io::stderr().flush()
+        } else {
+            io::stdout().flush()
+        }
+    }
+
+    fn width(&self, stderr: bool) -> Option<usize> {
+        terminal_width(stderr).or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+    }
+}
+
+fn console_writer_thread<S: ConsoleStreams>(
     config: Arc<RwLock<ConsoleWriterConfig>>,
     rx: Receiver<ConsoleTypeEnum>,
     sync_tx: Sender<u8>,
     stop: Arc<AtomicBool>,
+    mut streams: S,
 ) -> Result<(), LoggingError> {
-    let stdout_bufwtr = BufferWriter::stdout(ColorChoice::Always);
-    let mut stdout_buffer = stdout_bufwtr.buffer();
-    let stderr_bufwtr = BufferWriter::stderr(ColorChoice::Always);
-    let mut stderr_buffer = stderr_bufwtr.buffer();
+    let mut buffers = [streams.buffer(false), streams.buffer(true)];
+    // Environment and terminals are checked once for ColorMode::Auto
+    let auto_colors = [
+        ColorMode::Auto.enabled(false),
+        ColorMode::Auto.enabled(true),
+    ];
+    let default_theme = ColorTheme::default();
+    let default_layout = PrettyLayout::default();
+    // Stream of the last message. It is flushed before writing to the other stream, to
+    // keep the order of log messages.
+    let mut last_stderr = None;
     loop {
         if stop.load(Ordering::Relaxed) {
             break;
         }
-        match rx.recv()? {
-            ConsoleTypeEnum::Message((level, domain, message)) => {
-                let config_read = config.read();
-                if !config_read.enabled {
-                    continue;
-                }
-                if let Some(ref domain_filter) = config_read.domain_filter {
-                    let re = Regex::new(domain_filter).unwrap();
-                    if !re.is_match(&domain) {
-                        continue;
-                    }
-                }
-                if let Some(ref message_filter) = config_read.message_filter {
-                    let re = Regex::new(message_filter).unwrap();
-                    if !re.is_match(&domain) {
-                        continue;
-                    }
-                }
-                if config_read.colors {
-                    let (bufwtr, buffer) = if config_read.target == ConsoleTargetEnum::StdOut {
-                        (&stdout_bufwtr, &mut stdout_buffer)
-                    } else if config_read.target == ConsoleTargetEnum::StdErr {
-                        (&stderr_bufwtr, &mut stderr_buffer)
-                    } else if level < ERROR {
-                        (&stdout_bufwtr, &mut stdout_buffer)
-                    } else {
-                        (&stderr_bufwtr, &mut stderr_buffer)
-                    };
-                    buffer.clear();
-                    buffer.set_color(ColorSpec::new().set_fg(Some(match level {
-                        TRACE => Color::White,
-                        DEBUG => Color::Blue,
-                        INFO => Color::Green,
-                        SUCCESS => Color::Cyan,
-                        WARNING => Color::Yellow,
-                        ERROR => Color::Magenta,
-                        CRITICAL => Color::Red,
-                        EXCEPTION => Color::Red,
-                        _ => Color::White,
-                    })))?;
-                    writeln!(buffer, "{message}")?;
-                    buffer.reset()?;
-                    bufwtr.print(buffer)?;
-                } else if config_read.target == ConsoleTargetEnum::StdOut {
-                    println!("{message}");
-                } else if config_read.target == ConsoleTargetEnum::StdErr {
-                    eprintln!("{message}");
-                } else if level < ERROR {
-                    println!("{message}");
-                } else {
-                    eprintln!("{message}");
-                }
-            }
+        let message = match rx.recv()? {
             ConsoleTypeEnum::Sync => {
+                if let Some(last_stderr) = last_stderr {
+                    streams.flush(last_stderr)?;
+                }
                 sync_tx.send(1)?;
+                continue;
             }
             ConsoleTypeEnum::Stop => {
                 break;
             }
+            message => message,
+        };
+        let (level, domain) = match message {
+            ConsoleTypeEnum::Message((level, ref domain, _)) => (level, domain),
+            ConsoleTypeEnum::Record((ref record, _)) => (record.level, &record.domain),
+            ConsoleTypeEnum::Sync | ConsoleTypeEnum::Stop => continue,
+        };
+        let config_read = config.read();
+        if !config_read.enabled {
+            continue;
+        }
+        if let Some(ref domain_filter) = config_read.domain_filter {
+            let re = Regex::new(domain_filter).unwrap();
+            if !re.is_match(domain) {
+                continue;
+            }
+        }
+        if let Some(ref message_filter) = config_read.message_filter {
+            let re = Regex::new(message_filter).unwrap();
+            if !re.is_match(domain) {
+                continue;
+            }
+        }
+        let stderr = match config_read.target {
+            ConsoleTargetEnum::StdOut => false,
+            ConsoleTargetEnum::StdErr => true,
+            ConsoleTargetEnum::Both => level >= ERROR,
+            ConsoleTargetEnum::Split => level >= config_read.split_level,
+        };
+        let colors = config_read.colors
+            && match config_read.color_mode {
+                ColorMode::Auto => auto_colors[stderr as usize],
+                ColorMode::Always => true,
+                ColorMode::Never => false,
+            };
+        let theme = colors.then(|| config_read.theme.as_ref().unwrap_or(&default_theme));
+        let buffer = &mut buffers[stderr as usize];
+        buffer.clear();
+        match (&message, theme) {
+            (ConsoleTypeEnum::Record((record, level2sym)), theme) => {
+                let layout = config_read.pretty.as_ref().unwrap_or(&default_layout);
+                let width = if layout.wrap {
+                    layout.width.or_else(|| streams.width(stderr))
+                } else {
+                    None
+                };
+                let time_format = config_read
+                    .time_format
+                    .as_deref()
+                    .unwrap_or(DEFAULT_TIME_FORMAT);
+                write_pretty(buffer, layout, record, level2sym, time_format, theme, width)?;
+            }
+            (ConsoleTypeEnum::Message((_, _, message)), Some(theme)) => {
+                write_colored(buffer, theme, level, domain, message)?;
+            }
+            (ConsoleTypeEnum::Message((_, _, message)), None) => {
+                writeln!(buffer, "{message}")?;
+            }
+            _ => {}
         }
+        drop(config_read);
+        if let Some(last_stderr) = last_stderr
+            && last_stderr != stderr
+        {
+            streams.flush(last_stderr)?;
+        }
+        streams.print(stderr, buffer)?;
+        last_stderr = Some(stderr);
+    }
+    if let Some(last_stderr) = last_stderr {
+        streams.flush(last_stderr)?;
     }
     Ok(())
 }
@@ -177,6 +618,14 @@ pub struct ConsoleWriter {
 
 impl ConsoleWriter {
     pub fn new(config: ConsoleWriterConfig, stop: Arc<AtomicBool>) -> Result<Self, LoggingError> {
+        Self::with_streams(config, stop, StdStreams::new())
+    }
+
+    fn with_streams<S: ConsoleStreams>(
+        config: ConsoleWriterConfig,
+        stop: Arc<AtomicBool>,
+        streams: S,
+    ) -> Result<Self, LoggingError> {
         let config = Arc::new(RwLock::new(config));
         let (tx, rx) = bounded(1000);
         let (sync_tx, sync_rx) = bounded(1);
@@ -188,7 +637,9 @@ impl ConsoleWriter {
                 thread::Builder::new()
                     .name("ConsoleWriter".to_string())
                     .spawn(move || {
-                        if let Err(err) = console_writer_thread(config.clone(), rx, sync_tx, stop) {
+                        if let Err(err) =
+                            console_writer_thread(config.clone(), rx, sync_tx, stop, streams)
+                        {
                             eprintln!("console_writer_thread failed: {err:?}");
                         }
                     })?,
@@ -273,10 +724,26 @@ impl ConsoleWriter {
         self.config.write().colors = colors;
     }
 
+    pub fn set_color_mode(&self, color_mode: ColorMode) {
+        self.config.write().color_mode = color_mode;
+    }
+
+    pub fn set_theme(&self, theme: Option<ColorTheme>) {
+        self.config.write().theme = theme;
+    }
+
     pub fn set_target(&self, target: ConsoleTargetEnum) {
         self.config.write().target = target;
     }
 
+    pub fn set_split_level(&self, split_level: u8) {
+        self.config.write().split_level = split_level;
+    }
+
+    pub fn set_pretty(&self, pretty: Option<PrettyLayout>) {
+        self.config.write().pretty = pretty;
+    }
+
     #[inline]
     pub fn send(
         &self,
@@ -287,11 +754,116 @@ impl ConsoleWriter {
         self.tx
             .send(ConsoleTypeEnum::Message((level, domain, message)))
     }
+
+    #[inline]
+    pub(crate) fn send_record(
+        &self,
+        record: LogRecord,
+        level2sym: LevelSyms,
+    ) -> Result<(), SendError<ConsoleTypeEnum>> {
+        self.tx
+            .send(ConsoleTypeEnum::Record((Box::new(record), level2sym)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{ConsoleWriterConfig, DEBUG, Logging, NOTSET};
+    use std::{
+        io,
+        sync::{Arc, atomic::AtomicBool},
+    };
+
+    use parking_lot::Mutex;
+    use termcolor::{Buffer, Color};
+
+    use super::{ColorMode, ColorStyle, ColorTheme, ConsoleStreams, write_colored};
+    use crate::{
+        ConsoleTargetEnum, ConsoleWriter, ConsoleWriterConfig, DEBUG, ERROR, INFO, Logging, NOTSET,
+        WARNING,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Print(bool, String),
+        Flush(bool),
+    }
+
+    /// Captures stdout and stderr in one list, to check the order of writes and flushes.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<Event>>>);
+
+    impl ConsoleStreams for Captured {
+        fn buffer(&self, _stderr: bool) -> Buffer {
+            Buffer::no_color()
+        }
+
+        fn print(&mut self, stderr: bool, buffer: &Buffer) -> io::Result<()> {
+            let text = String::from_utf8_lossy(buffer.as_slice()).to_string();
+            self.0.lock().push(Event::Print(stderr, text));
+            Ok(())
+        }
+
+        fn flush(&mut self, stderr: bool) -> io::Result<()> {
+            self.0.lock().push(Event::Flush(stderr));
+            Ok(())
+        }
+
+        fn width(&self, _stderr: bool) -> Option<usize> {
+            None
+        }
+    }
+
+    #[test]
+    fn split() {
+        let config = ConsoleWriterConfig::builder()
+            .split(WARNING)
+            .build()
+            .unwrap();
+        let captured = Captured::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut writer = ConsoleWriter::with_streams(config, stop, captured.clone()).unwrap();
+        for (level, message) in [
+            (DEBUG, "Debug"),
+            (INFO, "Info"),
+            (WARNING, "Warning"),
+            (ERROR, "Error"),
+            (INFO, "Info 2"),
+        ] {
+            writer
+                .send(level, "root".to_string(), message.to_string())
+                .unwrap();
+        }
+        writer.sync(5.0).unwrap();
+        writer.set_split_level(ERROR);
+        writer
+            .send(WARNING, "root".to_string(), "Warning 2".to_string())
+            .unwrap();
+        writer.shutdown().unwrap();
+        use Event::*;
+        assert_eq!(
+            *captured.0.lock(),
+            vec![
+                Print(false, "Debug\n".to_string()),
+                Print(false, "Info\n".to_string()),
+                Flush(false),
+                Print(true, "Warning\n".to_string()),
+                Print(true, "Error\n".to_string()),
+                Flush(true),
+                Print(false, "Info 2\n".to_string()),
+                Flush(false),
+                Print(false, "Warning 2\n".to_string()),
+                Flush(false),
+            ]
+        );
+        // Existing config files without split level
+        let config: ConsoleWriterConfig = serde_json::from_str(
+            r#"{"enabled": true, "level": 10, "domain_filter": null, "message_filter": null,
+            "colors": false, "target": "Split", "debug": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(config.target, ConsoleTargetEnum::Split);
+        assert_eq!(config.split_level, WARNING);
+    }
 
     #[test]
     fn console() {
@@ -312,4 +884,59 @@ mod tests {
         logging.fatal("Fatal Message".to_string()).unwrap();
         logging.shutdown(false).unwrap();
     }
+
+    #[test]
+    fn colors() {
+        assert!(ColorMode::Always.enabled(false));
+        assert!(!ColorMode::Never.enabled(true));
+        let config: ConsoleWriterConfig = serde_json::from_str(
+            r#"{"enabled": true, "level": 10, "domain_filter": null, "message_filter": null,
+            "colors": true, "target": "StdOut", "debug": 0, "theme": {
+            "warning": {"fg": "208", "bold": true}, "error": {"fg": "white", "bg": "255,0,0"},
+            "domain": {"fg": "cyan"}}}"#,
+        )
+        .unwrap();
+        // Existing config files without color_mode get automatic colors.
+        assert_eq!(config.color_mode, ColorMode::Auto);
+        let theme = config.theme.unwrap();
+        assert_eq!(
+            theme.style(WARNING),
+            &ColorStyle::new(Some(Color::Ansi256(208)), None, true)
+        );
+        assert_eq!(
+            theme.style(ERROR),
+            &ColorStyle::new(Some(Color::White), Some(Color::Rgb(255, 0, 0)), false)
+        );
+        assert_eq!(theme.style(DEBUG), ColorTheme::default().style(DEBUG));
+        let json = serde_json::to_string(&theme).unwrap();
+        assert_eq!(serde_json::from_str::<ColorTheme>(&json).unwrap(), theme);
+        assert!(serde_json::from_str::<ColorStyle>(r#"{"fg": "purple"}"#).is_err());
+        let mut buffer = Buffer::ansi();
+        write_colored(
+            &mut buffer,
+            &theme,
+            ERROR,
+            "app",
+            "2026.10.18 12:00:00 app: E Failed",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer.into_inner()).unwrap(),
+            "\x1b[0m\x1b[37m\x1b[48;2;255;0;0m2026.10.18 12:00:00\x1b[0m \x1b[0m\x1b[36mapp\x1b[0m\
+             \x1b[0m\x1b[37m\x1b[48;2;255;0;0m: E Failed\x1b[0m\n"
+        );
+        let mut buffer = Buffer::ansi();
+        write_colored(
+            &mut buffer,
+            &ColorTheme::default(),
+            ERROR,
+            "app",
+            "app: E Failed",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer.into_inner()).unwrap(),
+            "\x1b[0m\x1b[35mapp: E Failed\x1b[0m\n"
+        );
+    }
 }
@@ -1,9 +1,18 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
 use std::fmt;
 
+use flume::Sender;
+
 use crate::{
     CallbackWriter, ClientWriter, ClientWriterConfig, ConsoleWriter, ConsoleWriterConfig,
     FileWriter, FileWriterConfig, LoggingError, LoggingServer, ServerConfig, SyslogWriter,
-    SyslogWriterConfig, callback::CallbackWriterConfig, config::LoggingInstance,
+    SyslogWriterConfig,
+    callback::CallbackWriterConfig,
+    capture::{CaptureWriter, CaptureWriterConfig},
+    config::LoggingInstance,
+    net::def::check_max_message_size,
+    recorder::{RecorderWriter, RecorderWriterConfig},
 };
 
 // Log-Levels
@@ -77,7 +86,48 @@ pub fn level2string(levelsym: &LevelSyms, level: u8) -> &'static str {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Convert level string, short string or symbol into log level.
+pub fn str2level(text: &str) -> Option<u8> {
+    [
+        NOTSET, TRACE, DEBUG, INFO, SUCCESS, WARNING, ERROR, FATAL, EXCEPTION, NOLOG,
+    ]
+    .into_iter()
+    .find(|level| {
+        [level2str, level2short, level2sym]
+            .iter()
+            .any(|f| f(*level) == text)
+    })
+}
+
+/// Check that optional domain and message filters are valid regular expressions.
+pub(crate) fn check_filters(
+    domain_filter: &Option<String>,
+    message_filter: &Option<String>,
+) -> Result<(), LoggingError> {
+    for (name, filter) in [("domain", domain_filter), ("message", message_filter)] {
+        if let Some(filter) = filter {
+            regex::Regex::new(filter).map_err(|e| {
+                LoggingError::InvalidValue(format!("Invalid {name} filter {filter:?}: {e}"))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that optional timestamp format is a valid strftime format string.
+pub(crate) fn check_time_format(time_format: &Option<String>) -> Result<(), LoggingError> {
+    if let Some(time_format) = time_format
+        && chrono::format::StrftimeItems::new(time_format)
+            .any(|item| matches!(item, chrono::format::Item::Error))
+    {
+        return Err(LoggingError::InvalidValue(format!(
+            "Invalid time format {time_format:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LevelSyms {
     /// Use 1 character symbol (!, F, E, W, ...)
     Sym,
@@ -144,6 +194,21 @@ pub enum WriterTypeEnum {
     Servers,
     Callback,
     Syslog,
+    Recorder,
+    Capture,
+}
+
+impl WriterTypeEnum {
+    /// Whether writers of type `typ` are selected. `Files`, `Clients` and `Servers` select
+    /// all writers of their kind.
+    pub fn selects(&self, typ: &WriterTypeEnum) -> bool {
+        match (self, typ) {
+            (WriterTypeEnum::Files, WriterTypeEnum::File(_))
+            | (WriterTypeEnum::Clients, WriterTypeEnum::Client(_))
+            | (WriterTypeEnum::Servers, WriterTypeEnum::Server(_)) => true,
+            _ => self == typ,
+        }
+    }
 }
 
 impl fmt::Display for WriterTypeEnum {
@@ -152,6 +217,8 @@ impl fmt::Display for WriterTypeEnum {
     }
 }
 
+// Configs are only moved when writers are added or reconfigured.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WriterConfigEnum {
     Root(RootConfig),
@@ -161,6 +228,8 @@ pub enum WriterConfigEnum {
     Server(ServerConfig),
     Callback(CallbackWriterConfig),
     Syslog(SyslogWriterConfig),
+    Recorder(RecorderWriterConfig),
+    Capture(CaptureWriterConfig),
 }
 
 impl WriterConfigEnum {
@@ -200,6 +269,85 @@ impl WriterConfigEnum {
             WriterEnum::Syslog(syslog_writer) => {
                 WriterConfigEnum::Syslog(syslog_writer.config.read().clone())
             }
+
+            WriterEnum::Recorder(recorder_writer) => {
+                WriterConfigEnum::Recorder(recorder_writer.config.read().clone())
+            }
+            WriterEnum::Capture(capture_writer) => {
+                WriterConfigEnum::Capture(capture_writer.config.read().clone())
+            }
+        }
+    }
+
+    /// Check writer configuration for invalid settings.
+    pub fn check(&self) -> Result<(), LoggingError> {
+        match self {
+            WriterConfigEnum::Root(_root_config) => Ok(()),
+            WriterConfigEnum::Console(console_config) => {
+                check_filters(
+                    &console_config.domain_filter,
+                    &console_config.message_filter,
+                )?;
+                check_time_format(&console_config.time_format)
+            }
+            WriterConfigEnum::File(file_config) => {
+                check_filters(&file_config.domain_filter, &file_config.message_filter)?;
+                check_time_format(&file_config.time_format)?;
+                file_config.check_backlog()?;
+                file_config.check_features()
+            }
+            WriterConfigEnum::Client(client_config) => {
+                check_filters(&client_config.domain_filter, &client_config.message_filter)?;
+                check_time_format(&client_config.time_format)?;
+                check_max_message_size(client_config.max_message_size)
+            }
+            WriterConfigEnum::Server(_server_config) => Ok(()),
+            WriterConfigEnum::Callback(callback_config) => {
+                check_filters(
+                    &callback_config.domain_filter,
+                    &callback_config.message_filter,
+                )?;
+                check_time_format(&callback_config.time_format)
+            }
+            WriterConfigEnum::Syslog(syslog_config) => {
+                check_filters(&syslog_config.domain_filter, &syslog_config.message_filter)?;
+                check_time_format(&syslog_config.time_format)
+            }
+            WriterConfigEnum::Recorder(recorder_config) => {
+                check_filters(
+                    &recorder_config.domain_filter,
+                    &recorder_config.message_filter,
+                )?;
+                check_time_format(&recorder_config.time_format)?;
+                recorder_config.check_limits()
+            }
+            WriterConfigEnum::Capture(capture_config) => {
+                check_filters(
+                    &capture_config.domain_filter,
+                    &capture_config.message_filter,
+                )?;
+                check_time_format(&capture_config.time_format)
+            }
+        }
+    }
+
+    pub fn typ(&self) -> WriterTypeEnum {
+        match self {
+            WriterConfigEnum::Root(_root_config) => WriterTypeEnum::Root,
+            WriterConfigEnum::Console(_console_config) => WriterTypeEnum::Console,
+            WriterConfigEnum::File(file_config) => {
+                WriterTypeEnum::File(file_config.path.to_string_lossy().to_string())
+            }
+            WriterConfigEnum::Client(client_config) => {
+                WriterTypeEnum::Client(client_config.get_address())
+            }
+            WriterConfigEnum::Server(server_config) => {
+                WriterTypeEnum::Server(server_config.get_address())
+            }
+            WriterConfigEnum::Callback(_callback_config) => WriterTypeEnum::Callback,
+            WriterConfigEnum::Syslog(_syslog_config) => WriterTypeEnum::Syslog,
+            WriterConfigEnum::Recorder(_recorder_config) => WriterTypeEnum::Recorder,
+            WriterConfigEnum::Capture(_capture_config) => WriterTypeEnum::Capture,
         }
     }
 }
@@ -246,6 +394,18 @@ impl From<SyslogWriterConfig> for WriterConfigEnum {
     }
 }
 
+impl From<RecorderWriterConfig> for WriterConfigEnum {
+    fn from(config: RecorderWriterConfig) -> Self {
+        Self::Recorder(config)
+    }
+}
+
+impl From<CaptureWriterConfig> for WriterConfigEnum {
+    fn from(config: CaptureWriterConfig) -> Self {
+        Self::Capture(config)
+    }
+}
+
 #[derive(Debug)]
 pub enum WriterEnum {
     Root,
@@ -255,6 +415,8 @@ pub enum WriterEnum {
     Server(Box<LoggingServer>),
     Callback(Box<CallbackWriter>),
     Syslog(Box<SyslogWriter>),
+    Recorder(Box<RecorderWriter>),
+    Capture(Box<CaptureWriter>),
 }
 
 impl WriterEnum {
@@ -301,6 +463,30 @@ impl WriterEnum {
             WriterConfigEnum::Syslog(syslog_writer_config) => Ok(WriterEnum::Syslog(Box::new(
                 SyslogWriter::new(syslog_writer_config.clone(), instance.stop.clone())?,
             ))),
+            WriterConfigEnum::Recorder(recorder_writer_config) => {
+                Ok(WriterEnum::Recorder(Box::new(RecorderWriter::new(
+                    recorder_writer_config.clone(),
+                    instance.server_tx.clone(),
+                )?)))
+            }
+            WriterConfigEnum::Capture(capture_writer_config) => Ok(WriterEnum::Capture(Box::new(
+                CaptureWriter::new(capture_writer_config.clone()),
+            ))),
+        }
+    }
+
+    /// Stop writer thread after writing all pending messages.
+    pub fn shutdown(&mut self) -> Result<(), LoggingError> {
+        match self {
+            WriterEnum::Root => Ok(()),
+            WriterEnum::Console(console_writer) => console_writer.shutdown(),
+            WriterEnum::File(file_writer) => file_writer.shutdown(),
+            WriterEnum::Client(client_writer) => client_writer.shutdown(),
+            WriterEnum::Server(logging_server) => logging_server.shutdown(),
+            WriterEnum::Callback(callback_writer) => callback_writer.shutdown(),
+            WriterEnum::Syslog(syslog_writer) => syslog_writer.shutdown(),
+            WriterEnum::Recorder(recorder_writer) => recorder_writer.shutdown(),
+            WriterEnum::Capture(capture_writer) => capture_writer.shutdown(),
         }
     }
 
@@ -325,6 +511,12 @@ impl WriterEnum {
             WriterEnum::Syslog(syslog_writer) => {
                 WriterConfigEnum::Syslog(syslog_writer.config.read().clone())
             }
+            WriterEnum::Recorder(recorder_writer) => {
+                WriterConfigEnum::Recorder(recorder_writer.config.read().clone())
+            }
+            WriterEnum::Capture(capture_writer) => {
+                WriterConfigEnum::Capture(capture_writer.config.read().clone())
+            }
         }
     }
 
@@ -333,7 +525,7 @@ impl WriterEnum {
             WriterEnum::Root => WriterTypeEnum::Root,
             WriterEnum::Console(_console_writer) => WriterTypeEnum::Console,
             WriterEnum::File(file_writer) => {
-                WriterTypeEnum::File(file_writer.config.read().path.to_str().unwrap().to_string())
+                WriterTypeEnum::File(file_writer.config.read().path.to_string_lossy().to_string())
             }
             WriterEnum::Client(client_writer) => {
                 WriterTypeEnum::Client(client_writer.config.read().get_address())
@@ -343,6 +535,8 @@ impl WriterEnum {
             }
             WriterEnum::Callback(_callback_writer) => WriterTypeEnum::Callback,
             WriterEnum::Syslog(_syslog_writer) => WriterTypeEnum::Syslog,
+            WriterEnum::Recorder(_recorder_writer) => WriterTypeEnum::Recorder,
+            WriterEnum::Capture(_capture_writer) => WriterTypeEnum::Capture,
         }
     }
 
@@ -365,6 +559,12 @@ impl WriterEnum {
             WriterEnum::Syslog(syslog_writer) => {
                 syslog_writer.sync(timeout)?;
             }
+            WriterEnum::Recorder(recorder_writer) => {
+                recorder_writer.sync(timeout)?;
+            }
+            WriterEnum::Capture(capture_writer) => {
+                capture_writer.sync(timeout)?;
+            }
         }
         Ok(())
     }
@@ -375,17 +575,47 @@ pub enum LoggingTypeEnum {
     Message((u8, String, String)),                 // level, domain, message
     MessageRemote((u8, String, String)),           // level, domain, message
     MessageExt((u8, String, String, u32, String)), // level, domain, message, tname, tid
+    MessageErr((u8, String, String, u32, String, Box<ErrorChain>)), // MessageExt + error chain
     Sync((Vec<WriterTypeEnum>, f64)),              // list of logging types, timeout
+    SyncReply((Vec<WriterTypeEnum>, f64, Sender<u8>)), // Sync with own reply channel
+    Dump,                                          // dump recorder writers
     Stop,
 }
 
+/// Causes and backtrace of an error logged with `exception_err`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorChain {
+    /// Messages of the errors returned by `source()`, outermost first.
+    pub causes: Vec<String>,
+    /// Backtrace captured when the error was logged. Only available if enabled with
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+    pub backtrace: Option<String>,
+}
+
+impl ErrorChain {
+    pub fn new(err: &dyn Error) -> Self {
+        let mut causes = Vec::new();
+        let mut source = err.source();
+        while let Some(err) = source {
+            causes.push(err.to_string());
+            source = err.source();
+        }
+        let backtrace = Backtrace::capture();
+        Self {
+            causes,
+            backtrace: (backtrace.status() == BacktraceStatus::Captured)
+                .then(|| backtrace.to_string()),
+        }
+    }
+}
+
 impl fmt::Display for LoggingTypeEnum {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{self:?}")
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MessageStructEnum {
     /// Log messages without structure information (default).
     String,
@@ -393,6 +623,11 @@ pub enum MessageStructEnum {
     Json,
     /// Log messages as Xml structure.
     Xml,
+    /// Log messages as logfmt key=value pairs.
+    Logfmt,
+    /// Length-prefixed binary records written by file writers. Read them with
+    /// [`crate::reader::BinaryReader`]. Other writers log strings.
+    Binary,
 }
 
 impl fmt::Display for MessageStructEnum {
@@ -2,7 +2,6 @@ use std::io;
 
 #[allow(unused_imports)]
 use serde::{Deserialize, Serialize};
-use zip::result::ZipError;
 
 use crate::{
     ClientTypeEnum, EncryptionMethod, LoggingTypeEnum, SyslogTypeEnum, callback::CallbackTypeEnum,
@@ -92,12 +91,6 @@ impl From<io::Error> for LoggingError {
     }
 }
 
-impl From<ZipError> for LoggingError {
-    fn from(error: ZipError) -> Self {
-        LoggingError::ArchiveError(error.to_string())
-    }
-}
-
 #[cfg(target_family = "unix")]
 impl From<syslog::Error> for LoggingError {
     fn from(error: syslog::Error) -> Self {
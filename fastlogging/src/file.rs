@@ -1,7 +1,7 @@
 use std::{
     fmt,
-    fs::{File, OpenOptions, rename},
-    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    fs::{File, Metadata, OpenOptions, copy, metadata, read_dir, remove_file, rename},
+    io::{BufReader, BufWriter, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     sync::{
         Arc,
@@ -11,25 +11,76 @@ use std::{
     time::{Duration, SystemTime},
 };
 
+use flate2::write::GzEncoder;
 use flume::{Receiver, RecvTimeoutError, Sender, bounded};
+use lzma_rust2::{XzOptions, XzWriter};
 use parking_lot::RwLock;
 use regex::Regex;
-use zip::{ZipWriter, write::SimpleFileOptions};
 
-use crate::{LoggingError, level2str};
+#[cfg(target_family = "unix")]
+use crate::unix::free_space;
+#[cfg(target_family = "windows")]
+use crate::windows::free_space;
+use crate::{
+    EncryptionMethod, LevelSyms, LoggingError, MessageStructEnum, NOTSET, RotationSchedule,
+    RotationTimezone,
+    audit::{AuditChain, AuditWriter, SigningKey, signing_key},
+    binary::BinaryEncoder,
+    def::{check_filters, check_time_format},
+    file_encryption::{FileCipher, file_key},
+    level2str,
+    path_template::{PathTemplate, template_writer_thread_worker},
+    reader::LogRecord,
+};
 
 const BACKLOG_MAX: usize = 1000;
 const QUEUE_CAPACITY: usize = 10000;
 const DEFAULT_DELAY: u64 = 3600;
+/// Buffered messages are appended to a shared log file at the latest at this size.
+const SHARED_BUFFER_SIZE: usize = 65536;
 
 #[derive(Debug, Clone)]
 pub enum FileTypeEnum {
     Message((u8, String, String)), // level, domain,message
-    Sync,                          // timeout
+    Record(Box<LogRecord>),
+    Sync, // timeout
     Rotate,
+    Reopen,
+    Fsync,
     Stop,
 }
 
+/// Log message or binary record received by a file writer.
+enum Payload {
+    Text(String),
+    Binary(Box<LogRecord>),
+}
+
+impl Payload {
+    fn into_text(self) -> String {
+        match self {
+            Payload::Text(message) => message,
+            Payload::Binary(record) => {
+                record.format(&MessageStructEnum::String, &LevelSyms::Str, &None)
+            }
+        }
+    }
+}
+
+/// When buffered log messages are written to the log file.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum FlushPolicy {
+    /// When the buffer is full, at `sync`, rotation and shutdown.
+    #[default]
+    Buffered,
+    /// After every message.
+    Always,
+    /// At the latest after this time since the oldest unwritten message.
+    Interval(Duration),
+    /// After this number of messages.
+    Records(usize),
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum CompressionMethodEnum {
     Store,
@@ -38,17 +89,22 @@ pub enum CompressionMethodEnum {
     Lzma,
 }
 
-impl From<CompressionMethodEnum> for zip::CompressionMethod {
-    fn from(val: CompressionMethodEnum) -> Self {
-        match val {
-            CompressionMethodEnum::Store => zip::CompressionMethod::Stored,
-            CompressionMethodEnum::Deflate => zip::CompressionMethod::Deflated,
-            CompressionMethodEnum::Zstd => zip::CompressionMethod::Zstd,
-            CompressionMethodEnum::Lzma => zip::CompressionMethod::Lzma,
+impl CompressionMethodEnum {
+    /// File extension of backup files, which is added after the backup file name.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CompressionMethodEnum::Store => "",
+            CompressionMethodEnum::Deflate => ".gz",
+            CompressionMethodEnum::Zstd => ".zst",
+            CompressionMethodEnum::Lzma => ".xz",
         }
     }
 }
 
+/// All extensions used for backup files. Used to find backups of other compression methods.
+/// ".zip" is used by backups of older versions.
+pub(crate) const BACKUP_EXTENSIONS: [&str; 5] = ["", ".gz", ".zst", ".xz", ".zip"];
+
 impl From<i32> for CompressionMethodEnum {
     fn from(val: i32) -> Self {
         match val {
@@ -72,6 +128,15 @@ pub struct FileWriterConfig {
     pub domain_filter: Option<String>,
     /// Optional filter log messages by their contents
     pub message_filter: Option<String>,
+    /// Optional message structure. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<MessageStructEnum>,
+    /// Optional level naming. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level2sym: Option<LevelSyms>,
+    /// Optional strftime format of the timestamp. Default is "%Y.%m.%d %H:%M:%S".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
     /// Path to log file
     pub path: PathBuf,
     /// Maximum size of log file. 0 means no size limit.
@@ -82,8 +147,103 @@ pub struct FileWriterConfig {
     timeout: Option<Duration>,
     /// Time when to backup log file.
     time: Option<SystemTime>,
+    /// Optional recurring rotation schedule, e.g. "@daily" or "0 */6 * * *".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schedule: Option<RotationSchedule>,
+    /// Timezone in which the rotation schedule is evaluated.
+    #[serde(default)]
+    pub(crate) timezone: RotationTimezone,
     /// Compression method for backup files.
     compression: CompressionMethodEnum,
+    /// Optional file name pattern of backup files. Either numbered, e.g. "app.{index}.log",
+    /// or a strftime format, e.g. "app.%Y-%m-%d_%H%M%S.log". Default is "<file name>.{index}".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backup_pattern: Option<String>,
+    /// Maximum total size of all backup files in bytes. 0 means no limit.
+    #[serde(default)]
+    retention_size: u64,
+    /// Maximum age of backup files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retention_age: Option<Duration>,
+    /// Minimum free disk space in bytes. Backup files are removed to keep it. 0 means no limit.
+    #[serde(default)]
+    min_free_space: u64,
+    /// Optional interval for checking whether the log file was moved, deleted or truncated
+    /// by another program, e.g. logrotate. The log file is reopened if required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    check_interval: Option<Duration>,
+    /// Rotate by copying the log file into the backup file and truncating it, instead of
+    /// renaming it. Other programs keep their open handles of the log file.
+    #[serde(default)]
+    copy_truncate: bool,
+    /// Share log file with other processes writing to the same path. Writes and rotation
+    /// are serialized by an advisory lock on the lock file "<path>.lock".
+    #[serde(default)]
+    shared: bool,
+    /// When buffered log messages are written to the log file.
+    #[serde(default)]
+    flush: FlushPolicy,
+    /// Optional log level from which on every message is written to disk with fdatasync.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fsync_level: Option<u8>,
+    /// Maximum number of open log files if `path` is a template. Default is 16.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_open_files: Option<usize>,
+    /// Encrypt log records with AES-256-GCM. Read them with [`crate::reader::LogReader`].
+    /// The key is not serialized.
+    #[serde(default, serialize_with = "redact_serde::encryption")]
+    encryption: EncryptionMethod,
+    /// Hash chain of tamper-evident audit logs. See [`crate::audit`]. The HMAC key is not
+    /// serialized.
+    #[serde(default, serialize_with = "redact_serde::audit")]
+    audit: Option<AuditChain>,
+    /// Ed25519 signing key in PKCS#8 format and number of records between checkpoints.
+    /// The signing key is not serialized.
+    #[serde(default, serialize_with = "redact_serde::audit_checkpoints")]
+    audit_checkpoints: Option<(SigningKey, usize)>,
+}
+
+/// Serialize secrets of file writer configurations with empty keys. Configurations loaded
+/// from the output fail validation until the keys are set again.
+mod redact_serde {
+    use serde::{Serialize, Serializer};
+
+    use super::*;
+
+    pub fn encryption<S: Serializer>(
+        encryption: &EncryptionMethod,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match encryption {
+            EncryptionMethod::NONE => EncryptionMethod::NONE,
+            EncryptionMethod::AuthKey(_) => EncryptionMethod::AuthKey(Vec::new()),
+            EncryptionMethod::AES(_) => EncryptionMethod::AES(Vec::new()),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn audit<S: Serializer>(
+        audit: &Option<AuditChain>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        audit
+            .as_ref()
+            .map(|chain| match chain {
+                AuditChain::Sha256 => AuditChain::Sha256,
+                AuditChain::Hmac(_) => AuditChain::Hmac(Vec::new()),
+            })
+            .serialize(serializer)
+    }
+
+    pub fn audit_checkpoints<S: Serializer>(
+        checkpoints: &Option<(SigningKey, usize)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        checkpoints
+            .as_ref()
+            .map(|(_, interval)| (SigningKey(Vec::new()), *interval))
+            .serialize(serializer)
+    }
 }
 
 impl FileWriterConfig {
@@ -96,29 +256,156 @@ impl FileWriterConfig {
         time: Option<SystemTime>,
         compression: Option<CompressionMethodEnum>,
     ) -> Result<Self, LoggingError> {
-        if size > 0 || timeout.is_some() || time.is_some() {
-            if backlog == 0 {
-                return Err(LoggingError::InvalidValue(
-                    "For rotating file logger backlog depth has to be set!".to_string(),
-                ));
-            } else if backlog > BACKLOG_MAX {
-                return Err(LoggingError::InvalidValue(format!(
-                    "For rotating file logger backlog depth {backlog} too big! Maximum value is {BACKLOG_MAX}."
-                )));
-            }
-        }
-        Ok(Self {
+        let config = Self {
             enabled: true,
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             path,
             size,
             backlog,
             timeout,
             time,
+            schedule: None,
+            timezone: RotationTimezone::Local,
             compression: compression.unwrap_or(CompressionMethodEnum::Store),
-        })
+            backup_pattern: None,
+            retention_size: 0,
+            retention_age: None,
+            min_free_space: 0,
+            check_interval: None,
+            copy_truncate: false,
+            shared: false,
+            flush: FlushPolicy::Buffered,
+            fsync_level: None,
+            max_open_files: None,
+            encryption: EncryptionMethod::NONE,
+            audit: None,
+            audit_checkpoints: None,
+        };
+        config.check_backlog()?;
+        Ok(config)
+    }
+
+    /// Create builder for file writer configuration writing to `path`.
+    pub fn builder<P: Into<PathBuf>>(path: P) -> FileWriterConfigBuilder {
+        FileWriterConfigBuilder {
+            config: Self {
+                enabled: true,
+                level: NOTSET,
+                domain_filter: None,
+                message_filter: None,
+                structured: None,
+                level2sym: None,
+                time_format: None,
+                path: path.into(),
+                size: 0,
+                backlog: 0,
+                timeout: None,
+                time: None,
+                schedule: None,
+                timezone: RotationTimezone::Local,
+                compression: CompressionMethodEnum::Store,
+                backup_pattern: None,
+                retention_size: 0,
+                retention_age: None,
+                min_free_space: 0,
+                check_interval: None,
+                copy_truncate: false,
+                shared: false,
+                flush: FlushPolicy::Buffered,
+                fsync_level: None,
+                max_open_files: None,
+                encryption: EncryptionMethod::NONE,
+                audit: None,
+                audit_checkpoints: None,
+            },
+        }
+    }
+
+    pub(crate) fn check_backlog(&self) -> Result<(), LoggingError> {
+        self.timezone.check()?;
+        check_backup_pattern(&self.backup_pattern)?;
+        if self.size > 0
+            || self.timeout.is_some()
+            || self.time.is_some()
+            || self.schedule.is_some()
+            || self.retention().is_set()
+        {
+            if self.backlog == 0 {
+                return Err(LoggingError::InvalidValue(
+                    "For rotating file logger backlog depth has to be set!".to_string(),
+                ));
+            } else if self.backlog > BACKLOG_MAX {
+                return Err(LoggingError::InvalidValue(format!(
+                    "For rotating file logger backlog depth {} too big! Maximum value is {BACKLOG_MAX}.",
+                    self.backlog
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that sharing, encryption, audit chain and binary records are not combined.
+    pub(crate) fn check_features(&self) -> Result<(), LoggingError> {
+        if file_key(&self.encryption)?.is_some() && self.shared {
+            return Err(LoggingError::InvalidValue(
+                "Shared log files can not be encrypted".to_string(),
+            ));
+        }
+        if let Some(AuditChain::Hmac(ref key)) = self.audit
+            && key.is_empty()
+        {
+            return Err(LoggingError::InvalidValue(
+                "Audit HMAC key must not be empty".to_string(),
+            ));
+        }
+        if let Some((ref key, interval)) = self.audit_checkpoints {
+            if self.audit.is_none() {
+                return Err(LoggingError::InvalidValue(
+                    "Audit checkpoints require an audit chain".to_string(),
+                ));
+            }
+            if interval == 0 {
+                return Err(LoggingError::InvalidValue(
+                    "Audit checkpoint interval has to be greater than 0".to_string(),
+                ));
+            }
+            signing_key(&key.0)?;
+        }
+        if self.structured == Some(MessageStructEnum::Binary) && !self.supports_binary() {
+            return Err(LoggingError::InvalidValue(
+                "Binary log files can not be shared, encrypted or audit logs".to_string(),
+            ));
+        }
+        if self.audit.is_some() && self.shared {
+            return Err(LoggingError::InvalidValue(
+                "Shared log files can not be audit logs".to_string(),
+            ));
+        }
+        if self.max_open_files == Some(0) {
+            return Err(LoggingError::InvalidValue(
+                "Maximum number of open files has to be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Binary records can be written, i.e. the log file is not shared, encrypted or an
+    /// audit log.
+    pub(crate) fn supports_binary(&self) -> bool {
+        !self.shared && self.audit.is_none() && matches!(self.encryption, EncryptionMethod::NONE)
+    }
+
+    fn retention(&self) -> Retention {
+        Retention {
+            size: self.retention_size,
+            age: self.retention_age,
+            min_free_space: self.min_free_space,
+        }
     }
 }
 
@@ -128,85 +415,764 @@ impl fmt::Display for FileWriterConfig {
     }
 }
 
+/// Builder for [`FileWriterConfig`].
+#[derive(Debug, Clone)]
+pub struct FileWriterConfigBuilder {
+    config: FileWriterConfig,
+}
+
+impl FileWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    /// Rotate log file when it exceeds `size` bytes. 0 means no size limit.
+    pub fn size(&mut self, size: usize) -> &mut Self {
+        self.config.size = size;
+        self
+    }
+
+    /// Maximum number of backup files. Required for rotating file writers.
+    pub fn backlog(&mut self, backlog: usize) -> &mut Self {
+        self.config.backlog = backlog;
+        self
+    }
+
+    /// Rotate log file when it is older than `timeout`.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Rotate log file at `time`.
+    pub fn time(&mut self, time: SystemTime) -> &mut Self {
+        self.config.time = Some(time);
+        self
+    }
+
+    /// Rotate log file according to recurring `schedule`.
+    pub fn schedule(&mut self, schedule: RotationSchedule) -> &mut Self {
+        self.config.schedule = Some(schedule);
+        self
+    }
+
+    /// Timezone in which the rotation schedule is evaluated. Default is local time.
+    pub fn timezone(&mut self, timezone: RotationTimezone) -> &mut Self {
+        self.config.timezone = timezone;
+        self
+    }
+
+    pub fn compression(&mut self, compression: CompressionMethodEnum) -> &mut Self {
+        self.config.compression = compression;
+        self
+    }
+
+    /// Remove oldest backup files when all backup files together exceed `size` bytes.
+    pub fn retention_size(&mut self, size: u64) -> &mut Self {
+        self.config.retention_size = size;
+        self
+    }
+
+    /// Remove backup files older than `age`.
+    pub fn retention_age(&mut self, age: Duration) -> &mut Self {
+        self.config.retention_age = Some(age);
+        self
+    }
+
+    /// Remove oldest backup files while free disk space is below `size` bytes.
+    pub fn min_free_space(&mut self, size: u64) -> &mut Self {
+        self.config.min_free_space = size;
+        self
+    }
+
+    /// Check every `interval` whether the log file was moved, deleted or truncated by
+    /// another program and reopen it if required.
+    pub fn check_interval(&mut self, interval: Duration) -> &mut Self {
+        self.config.check_interval = Some(interval);
+        self
+    }
+
+    /// Rotate by copying and truncating the log file instead of renaming it.
+    pub fn copy_truncate(&mut self, copy_truncate: bool) -> &mut Self {
+        self.config.copy_truncate = copy_truncate;
+        self
+    }
+
+    /// Share log file with other processes, e.g. forked or spawned children, which
+    /// write to and rotate the same path.
+    pub fn shared(&mut self, shared: bool) -> &mut Self {
+        self.config.shared = shared;
+        self
+    }
+
+    /// When buffered log messages are written to the log file.
+    pub fn flush(&mut self, flush: FlushPolicy) -> &mut Self {
+        self.config.flush = flush;
+        self
+    }
+
+    /// Write messages with `level` or higher to disk with fdatasync, e.g. `ERROR`.
+    pub fn fsync_level(&mut self, level: u8) -> &mut Self {
+        self.config.fsync_level = Some(level);
+        self
+    }
+
+    /// Maximum number of log files kept open if the path is a template like
+    /// "logs/{domain}/{date:%Y-%m-%d}.log". The least recently used file is closed first.
+    pub fn max_open_files(&mut self, max_open_files: usize) -> &mut Self {
+        self.config.max_open_files = Some(max_open_files);
+        self
+    }
+
+    /// Encrypt log records with `EncryptionMethod::AES(key)` and a 32 byte `key`. Every
+    /// log file gets its own random data key, which is stored encrypted with `key`.
+    pub fn encryption(&mut self, encryption: EncryptionMethod) -> &mut Self {
+        self.config.encryption = encryption;
+        self
+    }
+
+    /// Chain every log record to the previous one with a SHA-256 or HMAC hash. The chain
+    /// continues across rotation and restarts. Verify it with [`crate::audit::AuditVerifier`].
+    pub fn audit(&mut self, chain: AuditChain) -> &mut Self {
+        self.config.audit = Some(chain);
+        self
+    }
+
+    /// Sign the audit chain head after every `interval` records with the Ed25519 key
+    /// `signing_key` in PKCS#8 format.
+    pub fn audit_checkpoints(&mut self, signing_key: Vec<u8>, interval: usize) -> &mut Self {
+        self.config.audit_checkpoints = Some((SigningKey(signing_key), interval));
+        self
+    }
+
+    /// File name pattern of backup files. `{index}` is replaced by the backup number
+    /// (1 is the newest), otherwise the pattern is a strftime format of the rotation time
+    /// in the rotation timezone.
+    pub fn backup_pattern<S: Into<String>>(&mut self, backup_pattern: S) -> &mut Self {
+        self.config.backup_pattern = Some(backup_pattern.into());
+        self
+    }
+
+    /// Validate settings and create file writer configuration.
+    pub fn build(&self) -> Result<FileWriterConfig, LoggingError> {
+        if self.config.path.as_os_str().is_empty() {
+            return Err(LoggingError::InvalidValue(
+                "File writer requires a path".to_string(),
+            ));
+        }
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        self.config.check_backlog()?;
+        PathTemplate::parse(&self.config.path)?;
+        self.config.check_features()?;
+        Ok(self.config.clone())
+    }
+}
+
+pub(crate) fn check_backup_pattern(backup_pattern: &Option<String>) -> Result<(), LoggingError> {
+    let Some(pattern) = backup_pattern else {
+        return Ok(());
+    };
+    if pattern.contains(['/', '\\']) {
+        return Err(LoggingError::InvalidValue(format!(
+            "Backup pattern {pattern:?} has to be a file name"
+        )));
+    }
+    match (pattern.contains("{index}"), pattern.contains('%')) {
+        (true, true) => Err(LoggingError::InvalidValue(format!(
+            "Backup pattern {pattern:?} can not contain {{index}} and a time format"
+        ))),
+        (false, false) => Err(LoggingError::InvalidValue(format!(
+            "Backup pattern {pattern:?} requires {{index}} or a time format"
+        ))),
+        (true, false) => Ok(()),
+        (false, true) => check_time_format(backup_pattern),
+    }
+}
+
+/// Path of numbered backup `index` without compression extension.
+fn numbered_backup_path(path: &Path, pattern: Option<&str>, index: usize) -> PathBuf {
+    match pattern {
+        Some(pattern) => path.with_file_name(pattern.replace("{index}", &index.to_string())),
+        None => {
+            let mut name = path.file_name().unwrap_or_default().to_os_string();
+            name.push(format!(".{index}"));
+            path.with_file_name(name)
+        }
+    }
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+/// Regex matching the backup file names of log file `path`.
+fn backup_regex(path: &Path, pattern: Option<&str>) -> Regex {
+    let mut re = String::from("^");
+    match pattern {
+        None => {
+            re.push_str(&regex::escape(
+                &path.file_name().unwrap_or_default().to_string_lossy(),
+            ));
+            re.push_str(r"\.\d+");
+        }
+        Some(pattern) if pattern.contains("{index}") => {
+            let parts: Vec<_> = pattern.split("{index}").map(regex::escape).collect();
+            re.push_str(&parts.join(r"\d+"));
+        }
+        Some(pattern) => {
+            let mut chars = pattern.chars();
+            while let Some(c) = chars.next() {
+                if c == '%' {
+                    // Skip optional padding flags and width of the specifier
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() || c == '%' {
+                            break;
+                        }
+                    }
+                    re.push_str(".+?");
+                } else {
+                    re.push_str(&regex::escape(&c.to_string()));
+                }
+            }
+            // Counter of several rotations within the resolution of the pattern
+            re.push_str(r"(\.\d+)?");
+        }
+    }
+    let extensions: Vec<_> = BACKUP_EXTENSIONS
+        .iter()
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| regex::escape(ext))
+        .collect();
+    re.push_str(&format!("({})?$", extensions.join("|")));
+    Regex::new(&re).unwrap()
+}
+
+/// Directory of log file `path`.
+fn log_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Backup files of log file `path` with modification time and size, oldest first.
+pub(crate) fn list_backups(
+    path: &Path,
+    pattern: Option<&str>,
+) -> Result<Vec<(SystemTime, u64, PathBuf)>, LoggingError> {
+    let re = backup_regex(path, pattern);
+    let mut backups = Vec::new();
+    for entry in read_dir(log_dir(path))? {
+        let entry = entry?;
+        if entry.path() != path && re.is_match(&entry.file_name().to_string_lossy()) {
+            let metadata = entry.metadata()?;
+            backups.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+    // Numbered backups with equal modification times: Higher index is older.
+    backups.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.2.cmp(&a.2)));
+    Ok(backups)
+}
+
+/// Move numbered backups one index up and remove the oldest one. Returns path of new backup.
+fn shift_numbered_backups(
+    path: &Path,
+    backlog: usize,
+    pattern: Option<&str>,
+) -> Result<PathBuf, LoggingError> {
+    for ext in BACKUP_EXTENSIONS {
+        let oldest = with_extension(&numbered_backup_path(path, pattern, backlog), ext);
+        if oldest.exists() {
+            remove_file(oldest)?;
+        }
+    }
+    for index in (1..backlog).rev() {
+        for ext in BACKUP_EXTENSIONS {
+            let backup_path = with_extension(&numbered_backup_path(path, pattern, index), ext);
+            if backup_path.exists() {
+                let new_path = with_extension(&numbered_backup_path(path, pattern, index + 1), ext);
+                rename(backup_path, new_path)?;
+            }
+        }
+    }
+    Ok(numbered_backup_path(path, pattern, 1))
+}
+
+/// Remove the oldest dated backups so that `backlog` - 1 remain. Returns path of new backup.
+fn prune_dated_backups(
+    path: &Path,
+    backlog: usize,
+    pattern: &str,
+    timezone: RotationTimezone,
+    time: SystemTime,
+) -> Result<PathBuf, LoggingError> {
+    let backups = list_backups(path, Some(pattern))?;
+    let count = backups.len().saturating_sub(backlog - 1);
+    for (_, _, backup_path) in backups.into_iter().take(count) {
+        remove_file(backup_path)?;
+    }
+    // Several rotations within the resolution of the pattern get a counter.
+    let backup_path = path.with_file_name(timezone.format(time, pattern));
+    let mut counter = 0;
+    let mut new_path = backup_path.clone();
+    while BACKUP_EXTENSIONS
+        .iter()
+        .any(|ext| with_extension(&new_path, ext).exists())
+    {
+        counter += 1;
+        new_path = with_extension(&backup_path, &format!(".{counter}"));
+    }
+    Ok(new_path)
+}
+
+/// Move log file at `path` into a backup file and return the path of the uncompressed backup.
 fn rotate_do(
     path: &Path,
     backlog: usize,
-    compression: CompressionMethodEnum,
-) -> Result<(), LoggingError> {
-    for num in 1..backlog {
-        let mut backlog_path_old = path.to_path_buf();
-        backlog_path_old.set_extension(format!(".log.{}", backlog - num - 1));
-        if backlog_path_old.exists() {
-            let mut backlog_path_new = path.to_path_buf();
-            backlog_path_new.set_extension(format!(".log.{}", backlog - num));
-            rename(backlog_path_old, backlog_path_new)?;
+    copy_truncate: bool,
+    pattern: Option<&str>,
+    timezone: RotationTimezone,
+    time: SystemTime,
+) -> Result<PathBuf, LoggingError> {
+    let backup_path = match pattern {
+        Some(pattern) if !pattern.contains("{index}") => {
+            prune_dated_backups(path, backlog, pattern, timezone, time)?
         }
+        pattern => shift_numbered_backups(path, backlog, pattern)?,
+    };
+    if copy_truncate {
+        copy(path, &backup_path)?;
+        OpenOptions::new().write(true).open(path)?.set_len(0)?;
+    } else {
+        rename(path, &backup_path)?;
+    }
+    Ok(backup_path)
+}
+
+/// Device and inode of a file. Moved or replaced files get another id.
+pub(crate) type FileId = Option<(u64, u64)>;
+
+#[cfg(target_family = "unix")]
+pub(crate) fn file_id(metadata: &Metadata) -> FileId {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(target_family = "unix"))]
+pub(crate) fn file_id(_metadata: &Metadata) -> FileId {
+    None
+}
+
+/// Open log file for appending. Returns file, its size and its id.
+fn open_log_file(path: &Path) -> Result<(BufWriter<File>, usize, FileId), LoggingError> {
+    // Append mode writes at the end, even if another program truncated the file.
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.seek(SeekFrom::End(0))? as usize;
+    let id = file_id(&file.metadata()?);
+    Ok((BufWriter::new(file), size, id))
+}
+
+/// Open lock file of a shared log file at `path`.
+fn open_lock_file(path: &Path) -> Result<File, LoggingError> {
+    Ok(OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_extension(path, ".lock"))?)
+}
+
+/// Exclusive advisory lock of a shared log file. Released when dropped.
+struct SharedLock<'a>(&'a File);
+
+impl<'a> SharedLock<'a> {
+    fn new(lock_file: &'a File) -> Result<Self, LoggingError> {
+        lock_file.lock()?;
+        Ok(Self(lock_file))
+    }
+}
+
+impl Drop for SharedLock<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
     }
-    let mut backlog_path = path.to_path_buf();
+}
+
+/// Append `pending` messages to a shared log file under its lock. If another process has
+/// rotated the log file it is reopened first. Updates `size` to the size of the log file
+/// and returns the lock for a following rotation and whether the log file was reopened.
+fn shared_append<'a>(
+    lock_file: &'a File,
+    path: &Path,
+    file: &mut BufWriter<File>,
+    size: &mut usize,
+    file_id_open: &mut FileId,
+    pending: &mut Vec<u8>,
+) -> Result<(SharedLock<'a>, bool), LoggingError> {
+    let lock = SharedLock::new(lock_file)?;
+    let reopen = match metadata(path) {
+        Ok(metadata) => file_id(&metadata) != *file_id_open || (metadata.len() as usize) < *size,
+        Err(_) => true,
+    };
+    if reopen {
+        (*file, _, *file_id_open) = open_log_file(path)?;
+    }
+    file.write_all(pending)?;
+    file.flush()?;
+    pending.clear();
+    *size = file.get_ref().metadata()?.len() as usize;
+    Ok((lock, reopen))
+}
+
+/// Compress backup file at `path` into a single stream file and remove the uncompressed file.
+/// Returns the path of the compressed file.
+fn compress_do(path: &Path, compression: CompressionMethodEnum) -> Result<PathBuf, LoggingError> {
+    let compressed_path = with_extension(path, compression.extension());
     if compression == CompressionMethodEnum::Store {
-        backlog_path.set_extension(".log.1");
-    } else {
-        backlog_path.set_extension(".log.1.gz");
-    }
-    // Compress current log file
-    let file = File::open(path)?;
-    let mut buffer = Vec::new();
-    std::io::copy(&mut file.take(u64::MAX), &mut buffer)?;
-    let zip_file = File::create(&backlog_path)?;
-    let mut zip = ZipWriter::new(zip_file);
-    let filename = path.file_name().unwrap().to_str().unwrap();
-    let options = SimpleFileOptions::default()
-        .compression_method(compression.into())
-        .unix_permissions(0o755);
-    zip.start_file(filename, options)?;
-    zip.write_all(&buffer)?;
-    zip.finish()?;
-    Ok(())
+        return Ok(compressed_path);
+    }
+    // Incomplete files are never visible under the final name.
+    let tmp_path = with_extension(&compressed_path, ".tmp");
+    let mut reader = BufReader::new(File::open(path)?);
+    let writer = BufWriter::new(File::create(&tmp_path)?);
+    let writer = match compression {
+        CompressionMethodEnum::Store => unreachable!(),
+        CompressionMethodEnum::Deflate => {
+            let mut encoder = GzEncoder::new(writer, flate2::Compression::default());
+            std::io::copy(&mut reader, &mut encoder)?;
+            encoder.finish()?
+        }
+        CompressionMethodEnum::Zstd => {
+            let mut encoder = zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+            std::io::copy(&mut reader, &mut encoder)?;
+            encoder.finish()?
+        }
+        CompressionMethodEnum::Lzma => {
+            let mut encoder = XzWriter::new(writer, XzOptions::with_preset(6))?;
+            std::io::copy(&mut reader, &mut encoder)?;
+            encoder.finish()?
+        }
+    };
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    rename(&tmp_path, &compressed_path)?;
+    remove_file(path)?;
+    Ok(compressed_path)
+}
+
+/// Retention policy for backup files.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Retention {
+    size: u64,
+    age: Option<Duration>,
+    min_free_space: u64,
+}
+
+impl Retention {
+    fn is_set(&self) -> bool {
+        self.size > 0 || self.age.is_some() || self.min_free_space > 0
+    }
+}
+
+/// Remove backups of log file `path`, oldest first, until `retention` is met.
+/// Returns the removed backups.
+fn retain_do(
+    path: &Path,
+    pattern: Option<&str>,
+    retention: &Retention,
+) -> Result<Vec<PathBuf>, LoggingError> {
+    let mut backups = list_backups(path, pattern)?.into_iter().peekable();
+    let mut total_size: u64 = backups.clone().map(|(_, size, _)| size).sum();
+    let expired = retention
+        .age
+        .and_then(|age| SystemTime::now().checked_sub(age));
+    let mut removed = Vec::new();
+    while let Some((time, size, _)) = backups.peek() {
+        let remove = expired.is_some_and(|expired| *time < expired)
+            || (retention.size > 0 && total_size > retention.size)
+            || (retention.min_free_space > 0
+                && free_space(log_dir(path))? < retention.min_free_space);
+        if !remove {
+            break;
+        }
+        total_size -= size;
+        let (_, _, backup_path) = backups.next().unwrap();
+        remove_file(&backup_path)?;
+        removed.push(backup_path);
+    }
+    Ok(removed)
+}
+
+/// Compress new backup and apply retention policy.
+fn backup_do(
+    path: &Path,
+    backup_path: Option<PathBuf>,
+    compression: CompressionMethodEnum,
+    pattern: Option<&str>,
+    retention: &Retention,
+) {
+    if let Some(backup_path) = backup_path
+        && let Err(err) = compress_do(&backup_path, compression)
+    {
+        eprintln!("Failed to compress log file: {backup_path:?}\n  {err:?}");
+    }
+    if retention.is_set()
+        && let Err(err) = retain_do(path, pattern, retention)
+    {
+        eprintln!("Failed to remove old log files: {path:?}\n  {err:?}");
+    }
+}
+
+/// Compress new backup and apply retention policy in a background thread,
+/// so that logging continues meanwhile.
+fn backup_thread(
+    path: &Path,
+    backup_path: Option<PathBuf>,
+    compression: CompressionMethodEnum,
+    pattern: Option<String>,
+    retention: Retention,
+) -> Option<JoinHandle<()>> {
+    let backup_path = backup_path.filter(|_| compression != CompressionMethodEnum::Store);
+    if backup_path.is_none() && !retention.is_set() {
+        return None;
+    }
+    let path = path.to_path_buf();
+    let result = thread::Builder::new()
+        .name("FileBackup".to_string())
+        .spawn(move || {
+            backup_do(
+                &path,
+                backup_path,
+                compression,
+                pattern.as_deref(),
+                &retention,
+            )
+        });
+    match result {
+        Ok(thr) => Some(thr),
+        Err(err) => {
+            eprintln!("Failed to start backup thread: {err:?}");
+            None
+        }
+    }
 }
 
 fn file_writer_thread_worker(
     config: Arc<RwLock<FileWriterConfig>>,
+    path: PathBuf,
     rx: Receiver<FileTypeEnum>,
     stop: Arc<AtomicBool>,
     sync_tx: Sender<u8>,
 ) -> Result<(), LoggingError> {
-    let path = config.read().path.clone();
     let mut create_time = SystemTime::now();
-    let mut file = BufWriter::new(OpenOptions::new().create(true).append(true).open(&path)?);
-    let mut size = file.seek(SeekFrom::End(0))? as usize;
+    let (mut file, mut size, mut file_id_open) = open_log_file(&path)?;
+    // Every opened encrypted log file gets a new data key and every opened audit log
+    // a new seed before its first record.
+    let file_key = file_key(&config.read().encryption)?;
+    let mut cipher = None;
+    let mut audit = {
+        let c = config.read();
+        match c.audit {
+            Some(ref chain) => {
+                let mut audit = AuditWriter::new(chain, &c.audit_checkpoints)?;
+                audit.resume(&path, c.backup_pattern.as_deref(), &c.encryption);
+                Some(audit)
+            }
+            None => None,
+        }
+    };
+    let mut new_file = true;
+    // Encoder of binary log files
+    let mut binary: Option<BinaryEncoder> = None;
+    // Lock file coordinating writes and rotation of processes sharing the log file.
+    let lock_file = if config.read().shared {
+        Some(open_lock_file(&path)?)
+    } else {
+        None
+    };
+    let mut pending = Vec::new();
+    // Messages written since the last flush and the time of the next flush for FlushPolicy.
+    let mut unflushed = 0;
+    let mut flush_deadline: Option<SystemTime> = None;
+    // Message received when a time based rotation was due. It is handled after the rotation.
+    let mut held: Option<FileTypeEnum> = None;
+    let mut next_check = create_time;
+    // A non-empty log file belongs to the schedule period of its last modification.
+    // If this period is already over, the file is rotated at startup.
+    let mut schedule_base = if size > 0 {
+        file.get_ref().metadata()?.modified().unwrap_or(create_time)
+    } else {
+        create_time
+    };
+    let mut next_schedule: Option<(RotationSchedule, RotationTimezone, Option<SystemTime>)> = None;
+    let (backup_pattern, retention) = {
+        let c = config.read();
+        (c.backup_pattern.clone(), c.retention())
+    };
+    // Apply retention policy to backups of earlier runs.
+    let mut backup_thr = backup_thread(
+        &path,
+        None,
+        CompressionMethodEnum::Store,
+        backup_pattern,
+        retention,
+    );
     let newline = vec![b'\n'];
     let default_delay = Duration::from_secs(DEFAULT_DELAY);
     loop {
         if stop.load(Ordering::Relaxed) {
             break;
         }
-        let (max_size, backlog, timeout, time, compression) = {
+        let (max_size, backlog, timeout, time, scheduled, compression, check_interval) = {
             let c = config.read();
-            (c.size, c.backlog, c.timeout, c.time, c.compression)
+            let scheduled = match (&c.schedule, &next_schedule) {
+                (None, _) => None,
+                (Some(schedule), Some((cached, timezone, next)))
+                    if schedule == cached && c.timezone == *timezone =>
+                {
+                    *next
+                }
+                (Some(schedule), _) => {
+                    let next = schedule.next_after(c.timezone, schedule_base);
+                    next_schedule = Some((schedule.clone(), c.timezone, next));
+                    next
+                }
+            };
+            (
+                c.size,
+                c.backlog,
+                c.timeout,
+                c.time,
+                scheduled,
+                c.compression,
+                c.check_interval,
+            )
+        };
+        if let Some(interval) = check_interval
+            && SystemTime::now() >= next_check
+        {
+            next_check = SystemTime::now() + interval;
+            file.flush()?;
+            match metadata(&path) {
+                Ok(metadata) if file_id(&metadata) == file_id_open => {
+                    // Truncated by another program, e.g. logrotate with copytruncate.
+                    if (metadata.len() as usize) < size {
+                        size = metadata.len() as usize;
+                        new_file = true;
+                    }
+                }
+                // Moved or deleted by another program
+                _ => {
+                    (file, size, file_id_open) = open_log_file(&path)?;
+                    new_file = true;
+                }
+            }
+        }
+        // Earliest time based rotation. An absolute time before the creation of the
+        // current log file has already been handled.
+        let trigger = [
+            timeout.and_then(|timeout| create_time.checked_add(timeout)),
+            time.filter(|time| *time > create_time),
+            scheduled,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        let now = SystemTime::now();
+        let to = match [trigger, check_interval.map(|_| next_check), flush_deadline]
+            .into_iter()
+            .flatten()
+            .min()
+        {
+            Some(wakeup) => wakeup.duration_since(now).unwrap_or(Duration::ZERO),
+            None => default_delay,
+        };
+        let mut received = match held.take() {
+            Some(message) => Ok(message),
+            None => rx.recv_timeout(to.min(default_delay)),
         };
-        let mut deadline = create_time
-            .checked_add(timeout.unwrap_or(default_delay))
-            .unwrap_or_else(|| SystemTime::now().checked_add(default_delay).unwrap());
-        if let Some(time) = time
-            && time < deadline
+        // Messages which keep arriving do not delay a due rotation.
+        if backlog > 0
+            && trigger.is_some_and(|trigger| trigger <= SystemTime::now())
+            && let Ok(message) = received
         {
-            deadline = time;
+            held = Some(message);
+            received = Err(RecvTimeoutError::Timeout);
         }
-        let to = deadline.duration_since(SystemTime::now()).unwrap();
-        let message = match rx.recv_timeout(to) {
+        let message = match received {
             Ok(m) => m,
             Err(err) => {
                 if err == RecvTimeoutError::Disconnected {
                     break;
                 }
-                if timeout.is_none() && time.is_none() {
+                if flush_deadline.is_some_and(|deadline| deadline <= SystemTime::now()) {
+                    file.flush()?;
+                    unflushed = 0;
+                    flush_deadline = None;
+                }
+                if trigger.is_none_or(|trigger| trigger > SystemTime::now()) {
+                    continue;
+                }
+                if size == 0 && lock_file.is_none() {
+                    // Nothing to rotate. Start next period.
+                    create_time = SystemTime::now();
+                    schedule_base = create_time;
+                    next_schedule = None;
                     continue;
                 }
                 FileTypeEnum::Rotate
             }
         };
-        let rotate = match message {
-            FileTypeEnum::Message((_level, domain, message)) => {
+        let (mut flush_now, mut fsync_now) = (false, false);
+        let mut rotate = match message {
+            message @ (FileTypeEnum::Message(_) | FileTypeEnum::Record(_)) => {
+                let (level, domain, payload) = match message {
+                    FileTypeEnum::Message((level, domain, message)) => {
+                        (level, domain, Payload::Text(message))
+                    }
+                    FileTypeEnum::Record(record) => {
+                        (record.level, record.domain.clone(), Payload::Binary(record))
+                    }
+                    _ => unreachable!(),
+                };
                 let config_read = config.read();
                 if !config_read.enabled {
                     continue;
@@ -223,13 +1189,107 @@ fn file_writer_thread_worker(
                         continue;
                     }
                 }
-                file.write_all(message.as_bytes())?;
-                let _ = file.write(&newline)?;
-                size += message.len();
+                if lock_file.is_some() {
+                    pending.extend_from_slice(payload.into_text().as_bytes());
+                    pending.extend_from_slice(&newline);
+                } else {
+                    if new_file {
+                        new_file = false;
+                        cipher = None;
+                        if let Some(ref file_key) = file_key {
+                            let (new_cipher, key_frame) = FileCipher::new(file_key)?;
+                            file.write_all(&key_frame)?;
+                            size += key_frame.len();
+                            cipher = Some(new_cipher);
+                        }
+                        if let Some(ref mut audit) = audit {
+                            audit.new_file();
+                        }
+                        if let Some(ref mut encoder) = binary {
+                            encoder.new_file();
+                        }
+                    }
+                    // Once a binary record was received, all messages are written as records.
+                    let message = match payload {
+                        Payload::Binary(record) => Err(record),
+                        Payload::Text(message) if binary.is_some() => {
+                            Err(Box::new(LogRecord::from_message(level, domain, message)))
+                        }
+                        Payload::Text(message) => Ok(message),
+                    };
+                    let records = match (message, audit.as_mut()) {
+                        (Err(record), _) => {
+                            let data = binary
+                                .get_or_insert_with(BinaryEncoder::new)
+                                .encode(&record);
+                            file.write_all(data)?;
+                            size += data.len();
+                            Vec::new()
+                        }
+                        (Ok(message), Some(audit)) => audit.records(&message)?,
+                        (Ok(message), None) => vec![message],
+                    };
+                    for record in records {
+                        if let Some(ref mut cipher) = cipher {
+                            let frame = cipher.seal(record.as_bytes())?;
+                            file.write_all(&frame)?;
+                            size += frame.len();
+                        } else {
+                            file.write_all(record.as_bytes())?;
+                            let _ = file.write(&newline)?;
+                            size += record.len();
+                        }
+                    }
+                }
+                unflushed += 1;
+                flush_now = match config_read.flush {
+                    FlushPolicy::Buffered => false,
+                    FlushPolicy::Always => true,
+                    FlushPolicy::Interval(interval) => {
+                        let now = SystemTime::now();
+                        *flush_deadline.get_or_insert(now + interval) <= now
+                    }
+                    FlushPolicy::Records(records) => unflushed >= records,
+                };
+                fsync_now = config_read
+                    .fsync_level
+                    .is_some_and(|fsync_level| level >= fsync_level);
                 false
             }
             FileTypeEnum::Rotate => true,
-            FileTypeEnum::Sync => {
+            FileTypeEnum::Reopen => {
+                if let Some(ref lock_file) = lock_file {
+                    shared_append(
+                        lock_file,
+                        &path,
+                        &mut file,
+                        &mut size,
+                        &mut file_id_open,
+                        &mut pending,
+                    )?;
+                }
+                file.flush()?;
+                (file, size, file_id_open) = open_log_file(&path)?;
+                new_file = true;
+                false
+            }
+            FileTypeEnum::Sync | FileTypeEnum::Fsync => {
+                if let Some(ref lock_file) = lock_file {
+                    shared_append(
+                        lock_file,
+                        &path,
+                        &mut file,
+                        &mut size,
+                        &mut file_id_open,
+                        &mut pending,
+                    )?;
+                }
+                file.flush()?;
+                unflushed = 0;
+                flush_deadline = None;
+                if matches!(message, FileTypeEnum::Fsync) {
+                    file.get_ref().sync_data()?;
+                }
                 sync_tx.send(1)?;
                 false
             }
@@ -237,29 +1297,137 @@ fn file_writer_thread_worker(
                 break;
             }
         };
+        if (flush_now || fsync_now) && lock_file.is_none() {
+            file.flush()?;
+            unflushed = 0;
+            flush_deadline = None;
+            if fsync_now {
+                file.get_ref().sync_data()?;
+            }
+        }
+        // Messages for a shared log file are appended in batches. Its size is known
+        // only while holding the lock, which is kept during rotation.
+        let _lock = match lock_file {
+            Some(ref lock_file) => {
+                if !rotate
+                    && !flush_now
+                    && !fsync_now
+                    && (pending.is_empty()
+                        || (pending.len() < SHARED_BUFFER_SIZE
+                            && (max_size == 0 || size + pending.len() <= max_size)
+                            && !rx.is_empty()))
+                {
+                    continue;
+                }
+                let (lock, reopened) = shared_append(
+                    lock_file,
+                    &path,
+                    &mut file,
+                    &mut size,
+                    &mut file_id_open,
+                    &mut pending,
+                )?;
+                unflushed = 0;
+                flush_deadline = None;
+                if fsync_now {
+                    file.get_ref().sync_data()?;
+                }
+                if reopened || (rotate && size == 0) {
+                    // Log file was rotated by another process or is empty. Start next period.
+                    rotate = false;
+                    create_time = SystemTime::now();
+                    schedule_base = create_time;
+                    next_schedule = None;
+                }
+                Some(lock)
+            }
+            None => None,
+        };
         if backlog > 0 && (rotate || (max_size > 0 && size > max_size)) {
             file.flush()?;
             drop(file);
+            // Previous backup has to be compressed before backups are moved.
+            if let Some(thr) = backup_thr.take() {
+                let _ = thr.join();
+            }
             // Rotate
-            if let Err(err) = rotate_do(&path, backlog, compression) {
-                eprintln!("Failed to rotate log files: {path:?}\n  {err:?}");
+            let (backup_pattern, timezone, retention, copy_truncate) = {
+                let c = config.read();
+                (
+                    c.backup_pattern.clone(),
+                    c.timezone,
+                    c.retention(),
+                    c.copy_truncate,
+                )
+            };
+            match rotate_do(
+                &path,
+                backlog,
+                copy_truncate,
+                backup_pattern.as_deref(),
+                timezone,
+                SystemTime::now(),
+            ) {
+                // Other processes sharing the log file shift and remove the backups
+                // as well. Compress and remove them while holding the lock.
+                Ok(backup_path) if lock_file.is_some() => backup_do(
+                    &path,
+                    Some(backup_path),
+                    compression,
+                    backup_pattern.as_deref(),
+                    &retention,
+                ),
+                Ok(backup_path) => {
+                    backup_thr = backup_thread(
+                        &path,
+                        Some(backup_path),
+                        compression,
+                        backup_pattern,
+                        retention,
+                    )
+                }
+                Err(err) => eprintln!("Failed to rotate log files: {path:?}\n  {err:?}"),
             }
             create_time = SystemTime::now();
-            file = BufWriter::new(OpenOptions::new().write(true).truncate(true).open(&path)?);
-            size = 0;
+            schedule_base = create_time;
+            next_schedule = None;
+            // If rotation failed, continue writing to the current log file.
+            (file, size, file_id_open) = open_log_file(&path)?;
+            new_file = true;
         }
     }
+    if let Some(ref lock_file) = lock_file {
+        shared_append(
+            lock_file,
+            &path,
+            &mut file,
+            &mut size,
+            &mut file_id_open,
+            &mut pending,
+        )?;
+    }
     file.flush()?;
+    if let Some(thr) = backup_thr.take() {
+        let _ = thr.join();
+    }
     Ok(())
 }
 
 fn file_writer_thread(
     config: Arc<RwLock<FileWriterConfig>>,
+    path: PathBuf,
+    template: Option<PathTemplate>,
     rx: Receiver<FileTypeEnum>,
     stop: Arc<AtomicBool>,
     sync_tx: Sender<u8>,
 ) -> Result<(), LoggingError> {
-    if let Err(err) = file_writer_thread_worker(config.clone(), rx, stop, sync_tx) {
+    let result = match template {
+        Some(template) => {
+            template_writer_thread_worker(config.clone(), template, rx, stop, sync_tx)
+        }
+        None => file_writer_thread_worker(config.clone(), path, rx, stop, sync_tx),
+    };
+    if let Err(err) = result {
         eprintln!("Logging file worker crashed with error: {err:?}");
         eprintln!("{:#?}", config.read());
         Err(err)
@@ -279,7 +1447,19 @@ pub struct FileWriter {
 
 impl FileWriter {
     pub fn new(config: FileWriterConfig, stop: Arc<AtomicBool>) -> Result<Self, LoggingError> {
-        let config = Arc::new(RwLock::new(config));
+        config.check_features()?;
+        let path = config.path.clone();
+        let template = PathTemplate::parse(&path)?;
+        Self::start(Arc::new(RwLock::new(config)), path, template, stop)
+    }
+
+    /// Start writer thread for log file `path`, or for the log files expanded from `template`.
+    pub(crate) fn start(
+        config: Arc<RwLock<FileWriterConfig>>,
+        path: PathBuf,
+        template: Option<PathTemplate>,
+        stop: Arc<AtomicBool>,
+    ) -> Result<Self, LoggingError> {
         let (tx, rx) = bounded(QUEUE_CAPACITY);
         let (sync_tx, sync_rx) = bounded(1);
         Ok(Self {
@@ -290,7 +1470,9 @@ impl FileWriter {
                 thread::Builder::new()
                     .name("FileWriter".to_string())
                     .spawn(move || {
-                        if let Err(err) = file_writer_thread(config, rx, stop, sync_tx) {
+                        if let Err(err) =
+                            file_writer_thread(config, path, template, rx, stop, sync_tx)
+                        {
                             eprintln!("file_writer_thread failed: {err:?}");
                         }
                     })?,
@@ -335,6 +1517,24 @@ impl FileWriter {
         Ok(())
     }
 
+    /// Write buffered log messages to disk with fdatasync. Wait for maximum time `timeout`
+    /// seconds.
+    pub fn fsync(&self, timeout: f64) -> Result<(), LoggingError> {
+        self.tx.send(FileTypeEnum::Fsync).map_err(|e| {
+            LoggingError::SendCmdError("FileWriter".to_string(), "FSYNC".to_string(), e.to_string())
+        })?;
+        self.sync_rx
+            .recv_timeout(Duration::from_secs_f64(timeout))
+            .map_err(|e| {
+                LoggingError::RecvAswError(
+                    "FileWriter".to_string(),
+                    "FSYNC".to_string(),
+                    e.to_string(),
+                )
+            })?;
+        Ok(())
+    }
+
     pub fn enable(&self) {
         self.config.write().enabled = true;
     }
@@ -380,6 +1580,35 @@ impl FileWriter {
         self.sync(5.0)
     }
 
+    /// Set recurring rotation `schedule` evaluated in `timezone`. `None` disables it.
+    pub fn set_schedule(
+        &self,
+        schedule: Option<RotationSchedule>,
+        timezone: RotationTimezone,
+    ) -> Result<(), LoggingError> {
+        timezone.check()?;
+        let mut config_write = self.config.write();
+        if schedule.is_some() && config_write.backlog == 0 {
+            return Err(LoggingError::InvalidValue(
+                "For rotating file logger backlog depth has to be set!".to_string(),
+            ));
+        }
+        config_write.schedule = schedule;
+        config_write.timezone = timezone;
+        drop(config_write);
+        self.sync(5.0)
+    }
+
+    /// Reopen log file, e.g. after it was moved by another program.
+    pub fn reopen(&self) -> Result<(), LoggingError> {
+        self.tx.send(FileTypeEnum::Reopen).map_err(|e| {
+            LoggingError::SendError(format!(
+                "Failed to reopen {:?}: {e:?}",
+                self.config.read().path
+            ))
+        })
+    }
+
     pub fn rotate(&self) -> Result<(), LoggingError> {
         self.tx.send(FileTypeEnum::Rotate).map_err(|e| {
             LoggingError::SendError(format!(
@@ -390,6 +1619,25 @@ impl FileWriter {
     }
 
     #[inline]
+    /// Send log message or binary record of a path template writer.
+    pub(crate) fn forward(&self, message: FileTypeEnum) -> Result<(), LoggingError> {
+        self.tx.send(message).map_err(|e| {
+            LoggingError::SendError(format!("FileWriter::forward: Failed to send message: {e}"))
+        })
+    }
+
+    pub(crate) fn send_record(&self, record: LogRecord) -> Result<(), LoggingError> {
+        let level = record.level;
+        self.tx
+            .send(FileTypeEnum::Record(Box::new(record)))
+            .map_err(|e| {
+                LoggingError::SendError(format!(
+                    "FileWriter::send_record: Failed to send {} record: {e}",
+                    level2str(level)
+                ))
+            })
+    }
+
     pub fn send(&self, level: u8, domain: String, message: String) -> Result<(), LoggingError> {
         self.tx
             .send(FileTypeEnum::Message((level, domain, message)))
@@ -406,7 +1654,21 @@ impl FileWriter {
 mod tests {
     use tempfile::TempDir;
 
-    use crate::{DEBUG, FileWriterConfig, Logging, NOTSET};
+    use std::{
+        fs::File,
+        io::Read,
+        path::Path,
+        thread,
+        time::{Duration, SystemTime},
+    };
+
+    use super::{Retention, compress_do, retain_do, rotate_do};
+    use crate::reader::LogReader;
+    use crate::{
+        CompressionMethodEnum, DEBUG, ERROR, EncryptionMethod, FileWriterConfig, FlushPolicy, INFO,
+        LevelSyms, Logging, MessageStructEnum, NOTSET, RotationSchedule, RotationTimezone,
+        WriterConfigEnum,
+    };
 
     #[test]
     fn file() {
@@ -434,4 +1696,761 @@ mod tests {
         let _log_text = std::fs::read_to_string(&log_file).unwrap();
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn builder() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("builder.log");
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .size(1024)
+                .build()
+                .is_err()
+        );
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .domain_filter("(")
+                .build()
+                .is_err()
+        );
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("builder")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .level(INFO)
+                    .domain_filter("^build")
+                    .size(1024)
+                    .backlog(3)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.debug("Debug Message".to_string()).unwrap();
+        logging.info("Info Message".to_string()).unwrap();
+        logging.shutdown(false).unwrap();
+        let log_text = std::fs::read_to_string(&log_file).unwrap();
+        assert!(!log_text.contains("Debug Message"));
+        assert!(log_text.contains("builder: I Info Message"));
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn schedule() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("schedule.log");
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .schedule(RotationSchedule::hourly())
+                .build()
+                .is_err()
+        );
+        // Log file written two days ago belongs to an earlier period and is rotated at startup.
+        std::fs::write(&log_file, "Old Message\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&log_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 86400))
+            .unwrap();
+        let config: WriterConfigEnum = serde_json::from_str(&format!(
+            r#"{{"File": {{"enabled": true, "level": 0, "domain_filter": null,
+            "message_filter": null, "path": {:?}, "size": 0, "backlog": 3, "timeout": null,
+            "time": null, "schedule": "@daily", "timezone": "Utc", "compression": "Store"}}}}"#,
+            log_file.to_str().unwrap()
+        ))
+        .unwrap();
+        match &config {
+            WriterConfigEnum::File(file_config) => {
+                assert_eq!(
+                    file_config.schedule,
+                    Some(RotationSchedule::cron("@daily").unwrap())
+                );
+                assert_eq!(file_config.timezone, RotationTimezone::Utc);
+            }
+            config => panic!("Unexpected writer config {config:?}"),
+        }
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("schedule")
+            .writer(config)
+            .build()
+            .unwrap();
+        logging.info("New Message".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        logging.shutdown(false).unwrap();
+        let log_text = std::fs::read_to_string(&log_file).unwrap();
+        assert!(!log_text.contains("Old Message"));
+        assert!(log_text.contains("schedule: I New Message"));
+        assert!(temp_dir.path().join("schedule.log.1").exists());
+        // Invalid schedules are rejected when loading the configuration.
+        assert!(
+            serde_json::from_str::<WriterConfigEnum>(
+                r#"{"File": {"enabled": true, "level": 0, "domain_filter": null,
+                "message_filter": null, "path": "x.log", "size": 0, "backlog": 3,
+                "timeout": null, "time": null, "schedule": "0 25 * * *", "compression": "Store"}}"#
+            )
+            .is_err()
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn rotation_under_load() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("load.log");
+        let boundary = SystemTime::now() + Duration::from_millis(300);
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("load")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .backlog(3)
+                    .time(boundary)
+                    .fsync_level(DEBUG)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        // Writing with fsync is slower than logging, so messages keep arriving across the
+        // boundary without a gap.
+        let mut first_after = None;
+        for i in 0.. {
+            if first_after.is_none() && SystemTime::now() > boundary {
+                first_after = Some(i);
+            }
+            if first_after.is_some_and(|first| i >= first + 1000) {
+                break;
+            }
+            logging.info(format!("Message {i}")).unwrap();
+        }
+        logging.shutdown(false).unwrap();
+        let first_after = first_after.unwrap();
+        let indices = |path: &Path| -> Vec<usize> {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| line.rsplit_once(' ').unwrap().1.parse().unwrap())
+                .collect()
+        };
+        let backup = indices(&temp_dir.path().join("load.log.1"));
+        let current = indices(&log_file);
+        // Messages logged after the boundary are written into the new log file.
+        assert!(!backup.is_empty());
+        assert!(backup.iter().all(|i| *i < first_after));
+        assert_eq!(current[0], backup.len());
+        assert_eq!(backup.len() + current.len(), first_after + 1000);
+        temp_dir.close().unwrap();
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn backup_names() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let dir = temp_dir.path();
+        let log_file = dir.join("app.log");
+        let utc = RotationTimezone::Utc;
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let rotate = |compression, pattern, content: &str| {
+            std::fs::write(&log_file, content).unwrap();
+            let backup_path = rotate_do(&log_file, 3, false, pattern, utc, time).unwrap();
+            compress_do(&backup_path, compression).unwrap()
+        };
+        // Default numbered names, oldest backup is removed.
+        for content in ["1", "2", "3", "4"] {
+            rotate(CompressionMethodEnum::Store, None, content);
+        }
+        assert_eq!(names(dir), ["app.log.1", "app.log.2", "app.log.3"]);
+        assert_eq!(std::fs::read_to_string(dir.join("app.log.1")).unwrap(), "4");
+        assert_eq!(std::fs::read_to_string(dir.join("app.log.3")).unwrap(), "2");
+        // Older backups with another compression are shifted too.
+        let backup = rotate(CompressionMethodEnum::Deflate, None, "5");
+        assert_eq!(backup, dir.join("app.log.1.gz"));
+        assert_eq!(names(dir), ["app.log.1.gz", "app.log.2", "app.log.3"]);
+        for path in names(dir) {
+            std::fs::remove_file(dir.join(path)).unwrap();
+        }
+        // Numbered pattern
+        rotate(CompressionMethodEnum::Store, Some("app.{index}.log"), "1");
+        rotate(CompressionMethodEnum::Store, Some("app.{index}.log"), "2");
+        assert_eq!(names(dir), ["app.1.log", "app.2.log"]);
+        for path in names(dir) {
+            std::fs::remove_file(dir.join(path)).unwrap();
+        }
+        // Dated pattern with counter for rotations within the same second.
+        let pattern = Some("app.%Y-%m-%d_%H%M%S.log");
+        let backup = rotate(CompressionMethodEnum::Store, pattern, "1");
+        assert_eq!(backup, dir.join("app.2023-11-14_221320.log"));
+        rotate(CompressionMethodEnum::Store, pattern, "2");
+        rotate(CompressionMethodEnum::Zstd, pattern, "3");
+        assert_eq!(
+            names(dir),
+            [
+                "app.2023-11-14_221320.log",
+                "app.2023-11-14_221320.log.1",
+                "app.2023-11-14_221320.log.2.zst",
+            ]
+        );
+        // Only backlog - 1 older backups are kept.
+        File::options()
+            .write(true)
+            .open(dir.join("app.2023-11-14_221320.log"))
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+        let time = time + Duration::from_secs(86400);
+        std::fs::write(&log_file, "4").unwrap();
+        rotate_do(&log_file, 3, false, pattern, utc, time).unwrap();
+        assert_eq!(
+            names(dir),
+            [
+                "app.2023-11-14_221320.log.1",
+                "app.2023-11-14_221320.log.2.zst",
+                "app.2023-11-15_221320.log",
+            ]
+        );
+        for pattern in [
+            "app.log",
+            "logs/app.%Y.log",
+            "app.%Y.{index}.log",
+            "app.%Q.log",
+        ] {
+            assert!(
+                FileWriterConfig::builder(&log_file)
+                    .backlog(3)
+                    .backup_pattern(pattern)
+                    .build()
+                    .is_err(),
+                "{pattern}"
+            );
+        }
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn retention() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let dir = temp_dir.path();
+        let log_file = dir.join("app.log");
+        std::fs::write(&log_file, "x".repeat(1000)).unwrap();
+        let now = SystemTime::now();
+        let create = |name: &str, size: usize, days: u64| {
+            let path = dir.join(name);
+            std::fs::write(&path, "x".repeat(size)).unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(days * 86400))
+                .unwrap();
+        };
+        for (name, days) in [
+            ("app.log.1.gz", 1),
+            ("app.log.2", 2),
+            ("app.log.3.xz", 3),
+            ("app.log.4", 4),
+        ] {
+            create(name, 100, days);
+        }
+        create("other.log.5", 100, 10);
+        create("app.log.1.gz.tmp", 100, 10);
+        // Oldest backups are removed first until the total size fits.
+        let retention = Retention {
+            size: 250,
+            ..Default::default()
+        };
+        let removed = retain_do(&log_file, None, &retention).unwrap();
+        assert_eq!(removed, [dir.join("app.log.4"), dir.join("app.log.3.xz")]);
+        let retention = Retention {
+            age: Some(Duration::from_secs(36 * 3600)),
+            ..Default::default()
+        };
+        let removed = retain_do(&log_file, None, &retention).unwrap();
+        assert_eq!(removed, [dir.join("app.log.2")]);
+        // Dated backups
+        let pattern = Some("app.%Y-%m-%d.log");
+        for (name, days) in [
+            ("app.2024-03-01.log.zst", 3),
+            ("app.2024-03-02.log", 2),
+            ("app.2024-03-02.log.1", 1),
+        ] {
+            create(name, 100, days);
+        }
+        let retention = Retention {
+            size: 100,
+            ..Default::default()
+        };
+        let removed = retain_do(&log_file, pattern, &retention).unwrap();
+        assert_eq!(
+            removed,
+            [
+                dir.join("app.2024-03-01.log.zst"),
+                dir.join("app.2024-03-02.log")
+            ]
+        );
+        // Impossible free space removes all backups, but never the log file.
+        let retention = Retention {
+            min_free_space: u64::MAX,
+            ..Default::default()
+        };
+        let removed = retain_do(&log_file, pattern, &retention).unwrap();
+        assert_eq!(removed, [dir.join("app.2024-03-02.log.1")]);
+        assert_eq!(
+            names(dir),
+            ["app.log", "app.log.1.gz", "app.log.1.gz.tmp", "other.log.5"]
+        );
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .retention_size(1 << 20)
+                .build()
+                .is_err()
+        );
+        temp_dir.close().unwrap();
+    }
+
+    fn wait_for_text(path: &Path, text: &str) -> bool {
+        for _ in 0..100 {
+            if std::fs::read_to_string(path)
+                .unwrap_or_default()
+                .contains(text)
+            {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    #[test]
+    fn durability() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        for (name, flush) in [
+            ("always", FlushPolicy::Always),
+            ("interval", FlushPolicy::Interval(Duration::from_millis(20))),
+            ("records", FlushPolicy::Records(2)),
+        ] {
+            let log_file = temp_dir.path().join(format!("{name}.log"));
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("durability")
+                .writer(
+                    FileWriterConfig::builder(&log_file)
+                        .flush(flush)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            logging.info("First Message".to_string()).unwrap();
+            if flush == FlushPolicy::Records(2) {
+                thread::sleep(Duration::from_millis(50));
+                assert_eq!(std::fs::read_to_string(&log_file).unwrap(), "");
+                logging.info("Second Message".to_string()).unwrap();
+            }
+            assert!(wait_for_text(&log_file, "First Message"), "{name}");
+            logging.shutdown(false).unwrap();
+        }
+        // Buffered messages are written by sync, fsync and messages from fsync level.
+        let log_file = temp_dir.path().join("buffered.log");
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("durability")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .fsync_level(ERROR)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.info("Info Message".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        assert!(wait_for_text(&log_file, "Info Message"));
+        logging.warning("Warning Message".to_string()).unwrap();
+        logging.fsync(5.0).unwrap();
+        assert!(wait_for_text(&log_file, "Warning Message"));
+        logging.info("Second Info Message".to_string()).unwrap();
+        logging.error("Error Message".to_string()).unwrap();
+        assert!(wait_for_text(&log_file, "Error Message"));
+        assert!(wait_for_text(&log_file, "Second Info Message"));
+        logging.shutdown(false).unwrap();
+        match serde_json::from_str::<WriterConfigEnum>(
+            r#"{"File": {"enabled": true, "level": 0, "domain_filter": null,
+            "message_filter": null, "path": "x.log", "size": 0, "backlog": 0, "timeout": null,
+            "time": null, "compression": "Store", "flush": {"Records": 10}, "fsync_level": 40}}"#,
+        )
+        .unwrap()
+        {
+            WriterConfigEnum::File(file_config) => {
+                assert_eq!(file_config.flush, FlushPolicy::Records(10));
+                assert_eq!(file_config.fsync_level, Some(ERROR));
+            }
+            config => panic!("Unexpected writer config {config:?}"),
+        }
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn external_rotation() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("external.log");
+        let moved_file = temp_dir.path().join("external.log.moved");
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("external")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .check_interval(Duration::from_millis(20))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.info("Before Move".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        // Moved log file is detected and a new one is created.
+        std::fs::rename(&log_file, &moved_file).unwrap();
+        for _ in 0..100 {
+            if log_file.exists() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        logging.info("After Move".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        // Truncated log file is continued at its end.
+        File::options()
+            .write(true)
+            .open(&log_file)
+            .unwrap()
+            .set_len(0)
+            .unwrap();
+        logging.info("After Truncate".to_string()).unwrap();
+        logging.reopen_files().unwrap();
+        logging.shutdown(false).unwrap();
+        let moved_text = std::fs::read_to_string(&moved_file).unwrap();
+        assert!(moved_text.contains("Before Move"));
+        assert!(!moved_text.contains("After Move"));
+        let log_text = std::fs::read_to_string(&log_file).unwrap();
+        assert!(log_text.ends_with(" external: I After Truncate\n"));
+        assert!(!log_text.contains("After Move"));
+        assert!(!log_text.contains('\0'));
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn copy_truncate() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("copy.log");
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("copy")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .backlog(2)
+                    .copy_truncate(true)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.info("First Message".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        // A reader keeps its handle of the log file.
+        let mut reader = File::open(&log_file).unwrap();
+        logging.rotate(None).unwrap();
+        logging.info("Second Message".to_string()).unwrap();
+        logging.shutdown(false).unwrap();
+        let backup_text = std::fs::read_to_string(temp_dir.path().join("copy.log.1")).unwrap();
+        assert!(backup_text.contains("First Message"));
+        let mut log_text = String::new();
+        reader.read_to_string(&mut log_text).unwrap();
+        assert!(!log_text.contains("First Message"));
+        assert!(log_text.contains("Second Message"));
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn shared() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("shared.log");
+        // Each logging instance stands for another process writing to the same file.
+        let threads: Vec<_> = ["A", "B"]
+            .into_iter()
+            .map(|name| {
+                let log_file = log_file.clone();
+                thread::spawn(move || {
+                    let mut logging = Logging::builder()
+                        .level(DEBUG)
+                        .domain("shared")
+                        .writer(
+                            FileWriterConfig::builder(&log_file)
+                                .size(2000)
+                                .backlog(100)
+                                .shared(true)
+                                .build()
+                                .unwrap(),
+                        )
+                        .build()
+                        .unwrap();
+                    for i in 0..300 {
+                        logging.info(format!("Message {name}{i}")).unwrap();
+                    }
+                    logging.shutdown(false).unwrap();
+                })
+            })
+            .collect();
+        for thr in threads {
+            thr.join().unwrap();
+        }
+        assert!(temp_dir.path().join("shared.log.lock").exists());
+        assert!(temp_dir.path().join("shared.log.1").exists());
+        let mut lines = Vec::new();
+        for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "lock") {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).unwrap();
+            lines.extend(text.lines().map(|line| line.to_string()));
+        }
+        for name in ["A", "B"] {
+            for i in 0..300 {
+                let message = format!("Message {name}{i}");
+                assert_eq!(
+                    lines.iter().filter(|line| line.ends_with(&message)).count(),
+                    1,
+                    "{message}"
+                );
+            }
+        }
+        assert_eq!(lines.len(), 600);
+        // Backups are compressed and removed under the lock.
+        let log_file = temp_dir.path().join("compressed.log");
+        let threads: Vec<_> = ["A", "B"]
+            .into_iter()
+            .map(|name| {
+                let log_file = log_file.clone();
+                thread::spawn(move || {
+                    let mut logging = Logging::builder()
+                        .level(DEBUG)
+                        .domain("shared")
+                        .writer(
+                            FileWriterConfig::builder(&log_file)
+                                .size(1000)
+                                .backlog(3)
+                                .compression(CompressionMethodEnum::Deflate)
+                                .shared(true)
+                                .build()
+                                .unwrap(),
+                        )
+                        .build()
+                        .unwrap();
+                    for i in 0..300 {
+                        logging.info(format!("Message {name}{i}")).unwrap();
+                    }
+                    logging.shutdown(false).unwrap();
+                })
+            })
+            .collect();
+        for thr in threads {
+            thr.join().unwrap();
+        }
+        let mut backups: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("compressed.log."))
+            .collect();
+        backups.sort();
+        assert_eq!(
+            backups,
+            [
+                "compressed.log.1.gz",
+                "compressed.log.2.gz",
+                "compressed.log.3.gz",
+                "compressed.log.lock"
+            ]
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn encryption() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("encrypted.log");
+        let key = EncryptionMethod::AES(b"0123456789abcdef0123456789abcdef".to_vec());
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .encryption(EncryptionMethod::AES(b"short".to_vec()))
+                .build()
+                .is_err()
+        );
+        assert!(
+            FileWriterConfig::builder(&log_file)
+                .encryption(key.clone())
+                .shared(true)
+                .build()
+                .is_err()
+        );
+        let config = FileWriterConfig::builder(&log_file)
+            .encryption(key.clone())
+            .build()
+            .unwrap();
+        assert!(format!("{config:?}").contains("AES(<redacted>)"));
+        let mut value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["encryption"], serde_json::json!({"AES": []}));
+        let loaded: FileWriterConfig = serde_json::from_value(value.clone()).unwrap();
+        assert!(WriterConfigEnum::File(loaded).check().is_err());
+        value["encryption"] = serde_json::to_value(&key).unwrap();
+        value["shared"] = serde_json::Value::Bool(true);
+        let loaded: FileWriterConfig = serde_json::from_value(value).unwrap();
+        assert!(WriterConfigEnum::File(loaded.clone()).check().is_err());
+        assert!(
+            Logging::builder()
+                .writer(WriterConfigEnum::File(loaded))
+                .build()
+                .is_err()
+        );
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("encrypted")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .size(1000)
+                    .backlog(2)
+                    .encryption(key.clone())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        for i in 0..20 {
+            logging.info(format!("Secret Message {i}")).unwrap();
+        }
+        logging.shutdown(false).unwrap();
+        let backup_file = temp_dir.path().join("encrypted.log.1");
+        let mut records = Vec::new();
+        // Every log file has its own data key.
+        for path in [&backup_file, &log_file] {
+            let data = std::fs::read(path).unwrap();
+            assert!(!String::from_utf8_lossy(&data).contains("Secret"));
+            for record in LogReader::open_encrypted(path, &key).unwrap() {
+                records.push(record.unwrap());
+            }
+        }
+        assert_eq!(records.len(), 20);
+        assert!(records[0].ends_with(" Secret Message 0"));
+        assert!(records[19].ends_with(" Secret Message 19"));
+        let wrong_key = EncryptionMethod::AES(b"abcdef0123456789abcdef0123456789".to_vec());
+        let mut reader = LogReader::open_encrypted(&log_file, &wrong_key).unwrap();
+        assert!(reader.next().unwrap().is_err());
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn compression() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        for (compression, ext) in [
+            (CompressionMethodEnum::Deflate, "gz"),
+            (CompressionMethodEnum::Zstd, "zst"),
+            (CompressionMethodEnum::Lzma, "xz"),
+        ] {
+            let log_file = temp_dir.path().join(format!("{ext}.log"));
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("compress")
+                .writer(
+                    FileWriterConfig::builder(&log_file)
+                        .size(10000)
+                        .backlog(2)
+                        .compression(compression)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            for i in 0..500 {
+                logging.info(format!("Info Message {i}")).unwrap();
+            }
+            // Shutdown waits for the compression thread.
+            logging.shutdown(false).unwrap();
+            let backup = temp_dir.path().join(format!("{ext}.log.1.{ext}"));
+            assert!(!temp_dir.path().join(format!("{ext}.log.1")).exists());
+            let file = File::open(&backup).unwrap();
+            let mut text = String::new();
+            match compression {
+                CompressionMethodEnum::Deflate => {
+                    flate2::read::GzDecoder::new(file).read_to_string(&mut text)
+                }
+                CompressionMethodEnum::Zstd => {
+                    zstd::Decoder::new(file).unwrap().read_to_string(&mut text)
+                }
+                _ => lzma_rust2::XzReader::new(file, false).read_to_string(&mut text),
+            }
+            .unwrap();
+            assert!(text.len() > 10000);
+            assert!(
+                text.lines()
+                    .all(|line| line.contains(" compress: I Info Message "))
+            );
+        }
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn per_writer_format() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let logfmt_file = temp_dir.path().join("logfmt.log");
+        let json_file = temp_dir.path().join("json.log");
+        let string_file = temp_dir.path().join("string.log");
+        assert!(
+            FileWriterConfig::builder(&logfmt_file)
+                .time_format("%Q")
+                .build()
+                .is_err()
+        );
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("fmt")
+            .writer(
+                FileWriterConfig::builder(&logfmt_file)
+                    .structured(MessageStructEnum::Logfmt)
+                    .level2sym(LevelSyms::Str)
+                    .time_format("%Y")
+                    .build()
+                    .unwrap(),
+            )
+            .writer(
+                FileWriterConfig::builder(&json_file)
+                    .structured(MessageStructEnum::Json)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(FileWriterConfig::builder(&string_file).build().unwrap())
+            .build()
+            .unwrap();
+        logging.info("Info \"quoted\" Message".to_string()).unwrap();
+        logging.shutdown(false).unwrap();
+        let year = chrono::Local::now().format("%Y").to_string();
+        let logfmt_text = std::fs::read_to_string(&logfmt_file).unwrap();
+        assert_eq!(
+            logfmt_text,
+            format!("time={year} domain=fmt level=INFO msg=\"Info \\\"quoted\\\" Message\"\n")
+        );
+        let json_text = std::fs::read_to_string(&json_file).unwrap();
+        assert!(json_text.contains("\"domain\":\"fmt\",\"level\":\"I\""));
+        let string_text = std::fs::read_to_string(&string_file).unwrap();
+        assert!(string_text.contains("fmt: I Info \"quoted\" Message"));
+        temp_dir.close().unwrap();
+    }
 }
@@ -0,0 +1,168 @@
+//! Encrypted log files consist of frames `[type: u8, size: u32 LE, payload]`.
+//!
+//! - Key frame: `"FLAES1"`, 12 byte nonce and a random 32 byte data key, which is encrypted
+//!   with the configured AES-256-GCM key. Every opened log file starts with a new data key.
+//! - Record frame: Log message encrypted with AES-256-GCM using the data key of the last key
+//!   frame and the record number since this key frame as nonce.
+
+use std::io::Error;
+
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::{EncryptionMethod, LoggingError};
+
+pub(crate) const KEY_FRAME: u8 = 1;
+pub(crate) const RECORD_FRAME: u8 = 2;
+pub(crate) const FRAME_HEADER_SIZE: usize = 5;
+const KEY_MAGIC: &[u8; 6] = b"FLAES1";
+const DATA_KEY_LEN: usize = 32;
+
+/// Check that log files can be encrypted with `encryption` and create the key.
+pub(crate) fn file_key(encryption: &EncryptionMethod) -> Result<Option<LessSafeKey>, LoggingError> {
+    match encryption {
+        EncryptionMethod::NONE => Ok(None),
+        EncryptionMethod::AES(key) => Ok(Some(LessSafeKey::new(
+            UnboundKey::new(&AES_256_GCM, key).map_err(|_| {
+                LoggingError::InvalidEncryption(
+                    "FileWriter".to_string(),
+                    encryption.clone(),
+                    "AES key has to be 32 bytes long".to_string(),
+                )
+            })?,
+        ))),
+        EncryptionMethod::AuthKey(_) => Err(LoggingError::InvalidEncryption(
+            "FileWriter".to_string(),
+            encryption.clone(),
+            "Log files can only be encrypted with AES".to_string(),
+        )),
+    }
+}
+
+fn record_nonce(record: u64) -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..8].copy_from_slice(&record.to_le_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn frame(typ: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+    frame.push(typ);
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Encrypts the log records of an opened log file with a new data key.
+pub(crate) struct FileCipher {
+    data_key: LessSafeKey,
+    record: u64,
+}
+
+impl FileCipher {
+    /// Create data key. Returns cipher and the key frame, which has to be written first.
+    pub(crate) fn new(file_key: &LessSafeKey) -> Result<(Self, Vec<u8>), LoggingError> {
+        let rng = SystemRandom::new();
+        let mut data_key = [0u8; DATA_KEY_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut data_key)
+            .and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| Error::other("Failed to create data key"))?;
+        let mut sealed_key = data_key.to_vec();
+        file_key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(KEY_MAGIC),
+                &mut sealed_key,
+            )
+            .map_err(|_| Error::other("Failed to encrypt data key"))?;
+        let payload = [&KEY_MAGIC[..], &nonce, &sealed_key].concat();
+        let data_key = LessSafeKey::new(
+            UnboundKey::new(&AES_256_GCM, &data_key)
+                .map_err(|_| Error::other("Failed to create data key"))?,
+        );
+        Ok((
+            Self {
+                data_key,
+                record: 0,
+            },
+            frame(KEY_FRAME, &payload),
+        ))
+    }
+
+    /// Encrypt `record` into a record frame.
+    pub(crate) fn seal(&mut self, record: &[u8]) -> Result<Vec<u8>, LoggingError> {
+        let mut data = record.to_vec();
+        self.data_key
+            .seal_in_place_append_tag(record_nonce(self.record), Aad::empty(), &mut data)
+            .map_err(|_| Error::other("Failed to encrypt log record"))?;
+        self.record += 1;
+        Ok(frame(RECORD_FRAME, &data))
+    }
+}
+
+/// Decrypts the frames of an encrypted log file.
+pub(crate) struct FileDecipher {
+    file_key: LessSafeKey,
+    data_key: Option<(LessSafeKey, u64)>,
+}
+
+impl FileDecipher {
+    pub(crate) fn new(file_key: LessSafeKey) -> Self {
+        Self {
+            file_key,
+            data_key: None,
+        }
+    }
+
+    /// Decrypt frame with `payload`. Returns the log record of record frames.
+    pub(crate) fn open(
+        &mut self,
+        typ: u8,
+        payload: &mut [u8],
+    ) -> Result<Option<Vec<u8>>, LoggingError> {
+        match typ {
+            KEY_FRAME => {
+                if payload.len() != KEY_MAGIC.len() + NONCE_LEN + DATA_KEY_LEN + 16
+                    || !payload.starts_with(KEY_MAGIC)
+                {
+                    return Err(LoggingError::InvalidFile("Invalid key frame".to_string()));
+                }
+                let (nonce, sealed_key) = payload[KEY_MAGIC.len()..].split_at_mut(NONCE_LEN);
+                let nonce = Nonce::try_assume_unique_for_key(nonce)
+                    .map_err(|_| LoggingError::InvalidFile("Invalid key frame".to_string()))?;
+                let data_key = self
+                    .file_key
+                    .open_in_place(nonce, Aad::from(KEY_MAGIC), sealed_key)
+                    .map_err(|_| {
+                        LoggingError::InvalidFile(
+                            "Failed to decrypt data key. Wrong key?".to_string(),
+                        )
+                    })?;
+                let data_key = UnboundKey::new(&AES_256_GCM, data_key)
+                    .map_err(|_| LoggingError::InvalidFile("Invalid data key".to_string()))?;
+                self.data_key = Some((LessSafeKey::new(data_key), 0));
+                Ok(None)
+            }
+            RECORD_FRAME => {
+                let Some((data_key, record)) = self.data_key.as_mut() else {
+                    return Err(LoggingError::InvalidFile(
+                        "Record frame without key frame".to_string(),
+                    ));
+                };
+                let data = data_key
+                    .open_in_place(record_nonce(*record), Aad::empty(), payload)
+                    .map_err(|_| {
+                        LoggingError::InvalidFile(format!("Failed to decrypt log record {record}"))
+                    })?;
+                *record += 1;
+                Ok(Some(data.to_vec()))
+            }
+            typ => Err(LoggingError::InvalidFile(format!(
+                "Invalid frame type {typ}"
+            ))),
+        }
+    }
+}
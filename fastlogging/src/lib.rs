@@ -6,28 +6,57 @@ mod def;
 pub use def::*;
 mod config;
 mod error;
-pub use config::{ExtConfig, LoggingConfig};
+pub use config::{ExtConfig, LoggingConfig, check_config_file};
 pub use error::LoggingError;
 mod file;
-pub use file::{CompressionMethodEnum, FileWriter, FileWriterConfig};
+pub use file::{
+    CompressionMethodEnum, FileWriter, FileWriterConfig, FileWriterConfigBuilder, FlushPolicy,
+};
+pub mod audit;
+mod binary;
+mod file_encryption;
 mod net;
+mod path_template;
+pub mod reader;
 pub use net::{
-    ClientTypeEnum, ClientWriter, ClientWriterConfig, EncryptionMethod, LoggingServer, ServerConfig,
+    ClientStats, ClientTypeEnum, ClientWriter, ClientWriterConfig, ClientWriterConfigBuilder,
+    DEFAULT_MAX_MESSAGE_SIZE, EncryptionMethod, LoggingServer, OversizeEnum, ServerConfig,
+    ServerConfigBuilder,
 };
 mod console;
-pub use console::{ConsoleWriter, ConsoleWriterConfig};
+pub use console::{
+    ColorMode, ColorStyle, ColorTheme, ConsoleTargetEnum, ConsoleWriter, ConsoleWriterConfig,
+    ConsoleWriterConfigBuilder, level2color,
+};
+pub use termcolor::Color;
+mod pretty;
+pub use pretty::PrettyLayout;
 mod callback;
-pub use callback::{CallbackWriter, CallbackWriterConfig};
+pub use callback::{CallbackWriter, CallbackWriterConfig, CallbackWriterConfigBuilder};
+mod capture;
+pub use capture::{
+    CaptureHandle, CaptureWriter, CaptureWriterConfig, CaptureWriterConfigBuilder, CapturedRecord,
+};
+mod recorder;
+pub use recorder::{RecorderWriter, RecorderWriterConfig, RecorderWriterConfigBuilder};
 mod logging;
+mod panic_hook;
+mod schedule;
+pub use panic_hook::{PANIC_SYNC_TIMEOUT, install_panic_hook};
+pub use schedule::{RotationSchedule, RotationTimezone};
 pub mod root;
-pub use logging::Logging;
+pub use logging::{Logging, LoggingBuilder};
 pub use root::ROOT_LOGGER;
 mod logger;
 pub use logger::Logger;
 #[cfg(target_family = "unix")]
+mod sighup;
+#[cfg(target_family = "unix")]
+pub use sighup::install_sighup_handler;
+#[cfg(target_family = "unix")]
 mod syslog;
 #[cfg(target_family = "unix")]
-pub use syslog::{SyslogTypeEnum, SyslogWriter, SyslogWriterConfig};
+pub use syslog::{SyslogTypeEnum, SyslogWriter, SyslogWriterConfig, SyslogWriterConfigBuilder};
 #[cfg(target_family = "unix")]
 mod unix;
 #[cfg(target_family = "unix")]
@@ -35,7 +64,7 @@ pub use unix::getppid;
 #[cfg(target_family = "windows")]
 mod eventlog;
 #[cfg(target_family = "windows")]
-pub use eventlog::{SyslogTypeEnum, SyslogWriter, SyslogWriterConfig};
+pub use eventlog::{SyslogTypeEnum, SyslogWriter, SyslogWriterConfig, SyslogWriterConfigBuilder};
 #[cfg(target_family = "windows")]
 mod windows;
 #[cfg(target_family = "windows")]
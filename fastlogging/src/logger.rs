@@ -6,7 +6,7 @@ use std::{
 use flume::Sender;
 
 use crate::{
-    LoggingError, SUCCESS, TRACE,
+    ErrorChain, LoggingError, SUCCESS, TRACE,
     def::{CRITICAL, DEBUG, ERROR, EXCEPTION, FATAL, INFO, LoggingTypeEnum, WARNING},
 };
 
@@ -176,6 +176,34 @@ impl Logger {
         }
     }
 
+    /// Log EXCEPTION level message of error `err` with its `source()` chain and backtrace.
+    pub fn exception_err(&self, err: &dyn std::error::Error) -> Result<(), LoggingError> {
+        if self.level > EXCEPTION {
+            return Ok(());
+        }
+        if let Some(ref tx) = self.tx {
+            let tname = if self.tname {
+                thread::current().name().unwrap_or_default().to_string()
+            } else {
+                "".to_string()
+            };
+            let tid = if self.tid { thread_id::get() as u32 } else { 0 };
+            return tx
+                .send(LoggingTypeEnum::MessageErr((
+                    EXCEPTION,
+                    self.domain.clone(),
+                    format!("{}: {err}", self.domain),
+                    tid,
+                    tname,
+                    Box::new(ErrorChain::new(err)),
+                )))
+                .map_err(|e| LoggingError::SendError(format!("Failed to send message: {e}")));
+        }
+        Err(LoggingError::ConfigError(
+            "Logger not registered at Logging instance. Call add_logger first.".to_string(),
+        ))
+    }
+
     pub fn __repr__(&self) -> String {
         format!("Logger(level={} domain={})", self.level, self.domain)
     }
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
@@ -6,173 +6,514 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 use flume::{Receiver, Sender, bounded};
-use parking_lot::{RwLock, RwLockReadGuard};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 
 use crate::callback::CallbackWriter;
+use crate::capture::{CaptureHandle, CaptureWriter, CaptureWriterConfig, CapturedRecord};
 use crate::config::{ConfigFile, ExtConfig, FileMerge, LoggingInstance};
 use crate::console::{ConsoleWriter, ConsoleWriterConfig};
 use crate::def::{CRITICAL, DEBUG, ERROR, EXCEPTION, FATAL, INFO, LoggingTypeEnum, WARNING};
 use crate::file::FileWriter;
 use crate::logger::Logger;
-use crate::net::{AUTH_KEY, ClientWriter, EncryptionMethod, LoggingServer, ServerConfig};
+use crate::net::{
+    AUTH_KEY, ClientStats, ClientWriter, EncryptionMethod, LoggingServer, ServerConfig,
+};
+use crate::pretty::PROCESS_START;
+use crate::reader::LogRecord;
+use crate::recorder::{RecordedMessage, RecorderWriter};
 use crate::{
-    LevelSyms, LoggingError, MessageStructEnum, NOTSET, SUCCESS, SyslogWriter, TRACE,
-    WriterConfigEnum, WriterEnum, WriterTypeEnum, level2short, level2str, level2string, level2sym,
+    ErrorChain, LevelSyms, LoggingError, MessageStructEnum, NOTSET, SUCCESS, SyslogWriter, TRACE,
+    WriterConfigEnum, WriterEnum, WriterTypeEnum, level2str, level2string,
 };
 
+pub(crate) const DEFAULT_TIME_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
+
+/// Log record as received by the broker thread.
+pub(crate) struct Record {
+    pub(crate) level: u8,
+    pub(crate) domain: String,
+    pub(crate) message: String,
+    pub(crate) tname: Option<String>,
+    pub(crate) tid: u32,
+    pub(crate) date: DateTime<Local>,
+    pub(crate) error: Option<Box<ErrorChain>>,
+}
+
+/// Host and process which logged a record.
+pub(crate) struct Origin<'a> {
+    pub(crate) hostname: Option<&'a str>,
+    pub(crate) pname: &'a str,
+    pub(crate) pid: u32,
+}
+
+impl<'a> From<&'a LoggingInstance> for Origin<'a> {
+    fn from(config: &'a LoggingInstance) -> Self {
+        Self {
+            hostname: config.hostname.as_deref(),
+            pname: &config.pname,
+            pid: config.pid,
+        }
+    }
+}
+
 #[inline]
-fn build_string_message(
+pub(crate) fn build_string_message(
     buffer: &mut String,
-    config: &RwLockReadGuard<LoggingInstance>,
-    level: u8,
-    domain: &str,
-    message: String,
-    tname: Option<String>,
-    tid: u32,
+    origin: &Origin,
+    record: &Record,
+    level2sym: &LevelSyms,
+    time_format: &str,
 ) {
-    buffer.push_str(&Local::now().format("%Y.%m.%d %H:%M:%S").to_string());
-    if let Some(ref hostname) = config.hostname {
+    buffer.push_str(&record.date.format(time_format).to_string());
+    if let Some(hostname) = origin.hostname {
         buffer.push(' ');
         buffer.push_str(hostname);
     }
-    if !config.pname.is_empty() {
+    if !origin.pname.is_empty() {
         buffer.push(' ');
-        buffer.push_str(&config.pname);
+        buffer.push_str(origin.pname);
     }
-    if config.pid > 0 {
-        if config.pname.is_empty() {
+    if origin.pid > 0 {
+        if origin.pname.is_empty() {
             buffer.push(' ');
         }
         buffer.push('[');
-        buffer.push_str(&config.pid.to_string());
+        buffer.push_str(&origin.pid.to_string());
         buffer.push(']');
     }
-    if let Some(ref tname) = tname {
+    if let Some(ref tname) = record.tname {
         buffer.push('>');
         buffer.push_str(tname);
     }
-    if tid > 0 {
-        if tname.is_none() {
+    if record.tid > 0 {
+        if record.tname.is_none() {
             buffer.push('>');
         }
         buffer.push('[');
-        buffer.push_str(&tid.to_string());
+        buffer.push_str(&record.tid.to_string());
         buffer.push(']');
     }
     buffer.push(' ');
-    buffer.push_str(domain);
+    buffer.push_str(&record.domain);
     buffer.push(':');
     buffer.push(' ');
-    buffer.push_str(match config.level2sym {
-        LevelSyms::Sym => level2sym(level),
-        LevelSyms::Short => level2short(level),
-        LevelSyms::Str => level2str(level),
-    });
+    buffer.push_str(level2string(level2sym, record.level));
     buffer.push(' ');
-    buffer.push_str(&message);
+    buffer.push_str(&record.message);
+    if let Some(ref error) = record.error {
+        for cause in error.causes.iter() {
+            buffer.push_str("\n  caused by: ");
+            buffer.push_str(cause);
+        }
+        if let Some(ref backtrace) = error.backtrace {
+            buffer.push_str("\n  backtrace:");
+            for line in backtrace.lines() {
+                buffer.push_str("\n    ");
+                buffer.push_str(line);
+            }
+        }
+    }
 }
 
+/// Append string with JSON escaping.
 #[inline]
-fn build_json_message(
+fn push_json_string(buffer: &mut String, value: &str) {
+    buffer.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if c.is_control() => buffer.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
+/// Append text with XML escaping.
+#[inline]
+fn push_xml_text(buffer: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '<' => buffer.push_str("&lt;"),
+            '>' => buffer.push_str("&gt;"),
+            '&' => buffer.push_str("&amp;"),
+            c => buffer.push(c),
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn build_json_message(
     buffer: &mut String,
-    config: &RwLockReadGuard<LoggingInstance>,
-    level: u8,
-    domain: &str,
-    message: String,
-    tname: Option<String>,
-    tid: u32,
+    origin: &Origin,
+    record: &Record,
+    level2sym: &LevelSyms,
+    time_format: &str,
 ) {
     buffer.push('{');
     buffer.push_str("\"date\":");
-    buffer.push_str(&Local::now().format("\"%Y.%m.%d %H:%M:%S\"").to_string());
-    if let Some(ref hostname) = config.hostname {
-        buffer.push_str(",\"host\":\"");
-        buffer.push_str(hostname);
-        buffer.push('"');
+    push_json_string(buffer, &record.date.format(time_format).to_string());
+    if let Some(hostname) = origin.hostname {
+        buffer.push_str(",\"host\":");
+        push_json_string(buffer, hostname);
     }
-    if !config.pname.is_empty() {
-        buffer.push_str(",\"pname\":\"");
-        buffer.push_str(&config.pname);
-        buffer.push('"');
+    if !origin.pname.is_empty() {
+        buffer.push_str(",\"pname\":");
+        push_json_string(buffer, origin.pname);
     }
-    if config.pid > 0 {
+    if origin.pid > 0 {
         buffer.push_str(",\"pid\":");
-        buffer.push_str(&config.pid.to_string());
+        buffer.push_str(&origin.pid.to_string());
     }
-    if let Some(ref tname) = tname {
-        buffer.push_str(",\"tname\":\"");
-        buffer.push_str(tname);
-        buffer.push('"');
+    if let Some(ref tname) = record.tname {
+        buffer.push_str(",\"tname\":");
+        push_json_string(buffer, tname);
     }
-    if tid > 0 {
+    if record.tid > 0 {
         buffer.push_str(",\"tid\":");
-        buffer.push_str(&tid.to_string());
-    }
-    buffer.push_str(",\"domain\":\"");
-    buffer.push_str(domain);
-    buffer.push_str("\",\"level\":\"");
-    buffer.push_str(match config.level2sym {
-        LevelSyms::Sym => level2sym(level),
-        LevelSyms::Short => level2short(level),
-        LevelSyms::Str => level2str(level),
-    });
-    buffer.push_str("\",\"message\":\"");
-    buffer.push_str(&message);
-    buffer.push_str("\"}");
+        buffer.push_str(&record.tid.to_string());
+    }
+    buffer.push_str(",\"domain\":");
+    push_json_string(buffer, &record.domain);
+    buffer.push_str(",\"level\":\"");
+    buffer.push_str(level2string(level2sym, record.level));
+    buffer.push_str("\",\"message\":");
+    push_json_string(buffer, &record.message);
+    if let Some(ref error) = record.error {
+        buffer.push_str(",\"causes\":[");
+        for (i, cause) in error.causes.iter().enumerate() {
+            if i > 0 {
+                buffer.push(',');
+            }
+            push_json_string(buffer, cause);
+        }
+        buffer.push(']');
+        if let Some(ref backtrace) = error.backtrace {
+            buffer.push_str(",\"backtrace\":");
+            push_json_string(buffer, backtrace);
+        }
+    }
+    buffer.push('}');
 }
 
 #[inline]
-fn build_xml_message(
+pub(crate) fn build_xml_message(
     buffer: &mut String,
-    config: &RwLockReadGuard<LoggingInstance>,
-    level: u8,
-    domain: &str,
-    message: String,
-    tname: Option<String>,
-    tid: u32,
+    origin: &Origin,
+    record: &Record,
+    level2sym: &LevelSyms,
+    time_format: &str,
 ) {
     buffer.push_str("<log>");
     buffer.push_str("<date>");
-    buffer.push_str(&Local::now().format("%Y.%m.%d %H:%M:%S").to_string());
+    push_xml_text(buffer, &record.date.format(time_format).to_string());
     buffer.push_str("</date>");
-    if let Some(ref hostname) = config.hostname {
+    if let Some(hostname) = origin.hostname {
         buffer.push_str("<host>");
-        buffer.push_str(hostname);
+        push_xml_text(buffer, hostname);
         buffer.push_str("</host>");
     }
-    if !config.pname.is_empty() {
+    if !origin.pname.is_empty() {
         buffer.push_str("<pname>");
-        buffer.push_str(&config.pname);
+        push_xml_text(buffer, origin.pname);
         buffer.push_str("</pname>");
     }
-    if config.pid > 0 {
+    if origin.pid > 0 {
         buffer.push_str("<pid>");
-        buffer.push_str(&config.pid.to_string());
+        buffer.push_str(&origin.pid.to_string());
         buffer.push_str("</pid>");
     }
-    if let Some(ref tname) = tname {
+    if let Some(ref tname) = record.tname {
         buffer.push_str("<tname>");
-        buffer.push_str(tname);
+        push_xml_text(buffer, tname);
         buffer.push_str("</tname>");
     }
-    if tid > 0 {
+    if record.tid > 0 {
         buffer.push_str("<tid>");
-        buffer.push_str(&tid.to_string());
+        buffer.push_str(&record.tid.to_string());
         buffer.push_str("</tid>");
     }
     buffer.push_str("<domain>");
-    buffer.push_str(domain);
+    push_xml_text(buffer, &record.domain);
     buffer.push_str("</domain><level>");
-    buffer.push_str(match config.level2sym {
-        LevelSyms::Sym => level2sym(level),
-        LevelSyms::Short => level2short(level),
-        LevelSyms::Str => level2str(level),
-    });
+    buffer.push_str(level2string(level2sym, record.level));
     buffer.push_str("</level><message>");
-    buffer.push_str(&message);
-    buffer.push_str("</message></log>");
+    push_xml_text(buffer, &record.message);
+    buffer.push_str("</message>");
+    if let Some(ref error) = record.error {
+        buffer.push_str("<causes>");
+        for cause in error.causes.iter() {
+            buffer.push_str("<cause>");
+            push_xml_text(buffer, cause);
+            buffer.push_str("</cause>");
+        }
+        buffer.push_str("</causes>");
+        if let Some(ref backtrace) = error.backtrace {
+            buffer.push_str("<backtrace>");
+            push_xml_text(buffer, backtrace);
+            buffer.push_str("</backtrace>");
+        }
+    }
+    buffer.push_str("</log>");
+}
+
+/// Append logfmt value. Values containing spaces, `=` or `"` are quoted.
+#[inline]
+fn push_logfmt_value(buffer: &mut String, value: &str) {
+    if value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '"' || c == '\\')
+    {
+        buffer.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => buffer.push_str("\\\""),
+                '\\' => buffer.push_str("\\\\"),
+                '\n' => buffer.push_str("\\n"),
+                '\r' => buffer.push_str("\\r"),
+                '\t' => buffer.push_str("\\t"),
+                c => buffer.push(c),
+            }
+        }
+        buffer.push('"');
+    } else {
+        buffer.push_str(value);
+    }
+}
+
+#[inline]
+pub(crate) fn build_logfmt_message(
+    buffer: &mut String,
+    origin: &Origin,
+    record: &Record,
+    level2sym: &LevelSyms,
+    time_format: &str,
+) {
+    buffer.push_str("time=");
+    push_logfmt_value(buffer, &record.date.format(time_format).to_string());
+    if let Some(hostname) = origin.hostname {
+        buffer.push_str(" host=");
+        push_logfmt_value(buffer, hostname);
+    }
+    if !origin.pname.is_empty() {
+        buffer.push_str(" pname=");
+        push_logfmt_value(buffer, origin.pname);
+    }
+    if origin.pid > 0 {
+        buffer.push_str(" pid=");
+        buffer.push_str(&origin.pid.to_string());
+    }
+    if let Some(ref tname) = record.tname {
+        buffer.push_str(" tname=");
+        push_logfmt_value(buffer, tname);
+    }
+    if record.tid > 0 {
+        buffer.push_str(" tid=");
+        buffer.push_str(&record.tid.to_string());
+    }
+    buffer.push_str(" domain=");
+    push_logfmt_value(buffer, &record.domain);
+    buffer.push_str(" level=");
+    push_logfmt_value(buffer, level2string(level2sym, record.level));
+    buffer.push_str(" msg=");
+    push_logfmt_value(buffer, &record.message);
+    if let Some(ref error) = record.error {
+        for (i, cause) in error.causes.iter().enumerate() {
+            buffer.push_str(" cause.");
+            buffer.push_str(&i.to_string());
+            buffer.push('=');
+            push_logfmt_value(buffer, cause);
+        }
+        if let Some(ref backtrace) = error.backtrace {
+            buffer.push_str(" backtrace=");
+            push_logfmt_value(buffer, backtrace);
+        }
+    }
+}
+
+/// Format `record` with `structured`. Binary records are formatted as strings.
+pub(crate) fn build_message(
+    origin: &Origin,
+    record: &Record,
+    structured: &MessageStructEnum,
+    level2sym: &LevelSyms,
+    time_format: &Option<String>,
+) -> String {
+    let mut buffer = String::with_capacity(256);
+    let fmt = time_format.as_deref().unwrap_or(DEFAULT_TIME_FORMAT);
+    match structured {
+        MessageStructEnum::String | MessageStructEnum::Binary => {
+            build_string_message(&mut buffer, origin, record, level2sym, fmt)
+        }
+        MessageStructEnum::Json => build_json_message(&mut buffer, origin, record, level2sym, fmt),
+        MessageStructEnum::Xml => build_xml_message(&mut buffer, origin, record, level2sym, fmt),
+        MessageStructEnum::Logfmt => {
+            build_logfmt_message(&mut buffer, origin, record, level2sym, fmt)
+        }
+    }
+    buffer
+}
+
+/// Formats a record once per distinct (structure, level naming, time format)
+/// combination requested by the writers.
+struct MessageCache {
+    entries: Vec<(MessageStructEnum, LevelSyms, Option<String>, String)>,
+}
+
+impl MessageCache {
+    fn new() -> Self {
+        Self {
+            entries: Vec::with_capacity(4),
+        }
+    }
+
+    fn get(
+        &mut self,
+        config: &LoggingInstance,
+        record: &Record,
+        structured: &Option<MessageStructEnum>,
+        level2sym: &Option<LevelSyms>,
+        time_format: &Option<String>,
+    ) -> String {
+        let structured = structured.as_ref().unwrap_or(&config.structured);
+        let level2sym = level2sym.as_ref().unwrap_or(&config.level2sym);
+        if let Some((_, _, _, buffer)) = self
+            .entries
+            .iter()
+            .find(|(s, l, t, _)| s == structured && l == level2sym && t == time_format)
+        {
+            return buffer.clone();
+        }
+        let buffer = build_message(
+            &Origin::from(config),
+            record,
+            structured,
+            level2sym,
+            time_format,
+        );
+        self.entries.push((
+            structured.clone(),
+            level2sym.clone(),
+            time_format.clone(),
+            buffer.clone(),
+        ));
+        buffer
+    }
+}
+
+/// Unformatted `record` with the origin of `instance`.
+fn log_record(instance: &LoggingInstance, record: &Record) -> LogRecord {
+    LogRecord {
+        level: record.level,
+        domain: record.domain.clone(),
+        message: record.message.clone(),
+        hostname: instance.hostname.clone(),
+        pname: instance.pname.clone(),
+        pid: instance.pid,
+        tname: record.tname.clone(),
+        tid: record.tid,
+        date: record.date,
+        error: record.error.as_deref().cloned(),
+    }
+}
+
+/// Structured fields of `record` for capture writers. Uses the keys of JSON messages.
+fn record_fields(origin: &Origin, record: &Record) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    if let Some(hostname) = origin.hostname {
+        fields.insert("host".to_string(), hostname.to_string());
+    }
+    if !origin.pname.is_empty() {
+        fields.insert("pname".to_string(), origin.pname.to_string());
+    }
+    if origin.pid > 0 {
+        fields.insert("pid".to_string(), origin.pid.to_string());
+    }
+    if let Some(ref tname) = record.tname {
+        fields.insert("tname".to_string(), tname.clone());
+    }
+    if record.tid > 0 {
+        fields.insert("tid".to_string(), record.tid.to_string());
+    }
+    fields
+}
+
+/// Send recorded log messages to all writers except recorders, regardless of their log level.
+fn send_records(
+    instance: &LoggingInstance,
+    records: Vec<RecordedMessage>,
+) -> Result<(), LoggingError> {
+    for (level, domain, message) in records {
+        for writer in instance.writers.values() {
+            match writer {
+                WriterEnum::Root => {}
+                WriterEnum::Console(console_writer) => {
+                    console_writer.send(level, domain.clone(), message.clone())?
+                }
+                WriterEnum::File(file_writer) => {
+                    file_writer.send(level, domain.clone(), message.clone())?
+                }
+                WriterEnum::Client(client_writer) => {
+                    client_writer.send(level, domain.clone(), message.clone())?
+                }
+                WriterEnum::Server(_logging_server) => {}
+                WriterEnum::Callback(callback_writer) => {
+                    callback_writer.send(level, domain.clone(), message.clone())?
+                }
+                WriterEnum::Syslog(syslog_writer) => {
+                    syslog_writer.send(level, domain.clone(), message.clone())?
+                }
+                WriterEnum::Recorder(_recorder_writer) => {}
+                WriterEnum::Capture(capture_writer) => capture_writer.send(CapturedRecord {
+                    level,
+                    domain: domain.clone(),
+                    message: message.clone(),
+                    formatted: message.clone(),
+                    tname: None,
+                    tid: 0,
+                    date: Local::now(),
+                    error: None,
+                    fields: BTreeMap::new(),
+                })?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn sync_writers(
+    instance: &LoggingInstance,
+    types: Vec<WriterTypeEnum>,
+    timeout: f64,
+) -> Result<(), LoggingError> {
+    let pid = process::id();
+    let debug = instance.debug;
+    if debug > 0 {
+        println!("{pid} logging_thread_worker: SYNC");
+    }
+    for typ in types {
+        for (_, wids) in instance
+            .typ2wids
+            .iter()
+            .filter(|(writer_typ, _)| typ.selects(writer_typ))
+        {
+            for wid in wids {
+                if debug > 0 {
+                    println!("{pid} logging_thread_worker: SYNC(wid={wid})");
+                }
+                instance.writers.get(wid).unwrap().sync(timeout)?;
+            }
+        }
+    }
+    Ok(())
 }
 
 fn logging_thread_worker(
@@ -181,39 +522,50 @@ fn logging_thread_worker(
     instance: Arc<RwLock<LoggingInstance>>,
     stop: Arc<AtomicBool>,
 ) -> Result<(), LoggingError> {
-    let mut buffer = String::with_capacity(4096);
+    let mut cache = MessageCache::new();
     loop {
         if stop.load(Ordering::Relaxed) {
             break;
         }
         let mut remote = false;
-        let (level, domain, message, tname, tid) = match rx.recv()? {
-            LoggingTypeEnum::Message((level, domain, message)) => (level, domain, message, None, 0),
+        let (level, domain, message, tname, tid, error) = match rx.recv()? {
+            LoggingTypeEnum::Message((level, domain, message)) => {
+                (level, domain, message, None, 0, None)
+            }
             LoggingTypeEnum::MessageRemote((level, domain, message)) => {
                 remote = true;
-                (level, domain, message, None, 0)
+                (level, domain, message, None, 0, None)
             }
             LoggingTypeEnum::MessageExt((level, domain, message, tid, tname)) => {
-                (level, domain, message, Some(tname), tid)
+                (level, domain, message, Some(tname), tid, None)
+            }
+            LoggingTypeEnum::MessageErr((level, domain, message, tid, tname, error)) => {
+                let tname = (!tname.is_empty()).then_some(tname);
+                (level, domain, message, tname, tid, Some(error))
             }
             LoggingTypeEnum::Sync((types, timeout)) => {
+                sync_writers(&instance.read(), types, timeout)?;
+                sync_tx.send(1)?;
+                continue;
+            }
+            LoggingTypeEnum::SyncReply((types, timeout, reply_tx)) => {
+                sync_writers(&instance.read(), types, timeout)?;
+                // The receiver might have stopped waiting already.
+                let _ = reply_tx.send(1);
+                continue;
+            }
+            LoggingTypeEnum::Dump => {
                 let instance = instance.read();
-                let pid = process::id();
-                let debug = instance.debug;
-                if debug > 0 {
-                    println!("{pid} logging_thread_worker: SYNC");
-                }
-                for typ in types {
-                    if let Some(wids) = instance.typ2wids.get(&typ) {
-                        for wid in wids {
-                            if debug > 0 {
-                                println!("{pid} logging_thread_worker: SYNC(wid={wid})");
-                            }
-                            instance.writers.get(wid).unwrap().sync(timeout)?;
-                        }
+                if instance.debug > 0 {
+                    println!("{} logging_thread_worker: DUMP", process::id());
+                }
+                for writer in instance.writers.values() {
+                    if let WriterEnum::Recorder(recorder_writer) = writer
+                        && let Some(records) = recorder_writer.dump()?
+                    {
+                        send_records(&instance, records)?;
                     }
                 }
-                sync_tx.send(1)?;
                 continue;
             }
             LoggingTypeEnum::Stop => {
@@ -223,85 +575,139 @@ fn logging_thread_worker(
                 break;
             }
         };
-        // Build message
+        let record = Record {
+            level,
+            domain,
+            message,
+            tname,
+            tid,
+            date: Local::now(),
+            error,
+        };
+        cache.entries.clear();
+        // Build message once per distinct format and send it to writers
         // {date} {hostname} {pname}[{pid}]>{tname}[{tid}] {domain}: {level} {message}
-        let instance_read = instance.read();
-        buffer.clear();
-        if remote {
-            buffer.push_str(&message);
-        } else {
-            match instance_read.structured {
-                MessageStructEnum::String => {
-                    build_string_message(
-                        &mut buffer,
-                        &instance_read,
-                        level,
-                        &domain,
-                        message,
-                        tname,
-                        tid,
-                    );
-                }
-                MessageStructEnum::Json => {
-                    build_json_message(
-                        &mut buffer,
-                        &instance_read,
-                        level,
-                        &domain,
-                        message,
-                        tname,
-                        tid,
-                    );
-                }
-                MessageStructEnum::Xml => {
-                    build_xml_message(
-                        &mut buffer,
-                        &instance_read,
-                        level,
-                        &domain,
-                        message,
-                        tname,
-                        tid,
-                    );
-                }
-            }
-        }
-        // Send message to writers
-        if instance_read.debug > 2 {
+        let instance = instance.read();
+        if instance.debug > 2 {
             println!(
-                "{} logging_thread_worker: MESSAGE {buffer:?}",
-                process::id()
+                "{} logging_thread_worker: MESSAGE {:?}",
+                process::id(),
+                record.message
             );
         }
-        drop(instance_read);
-        let mut instance_write = instance.write();
-        for writer in instance_write.writers.values_mut() {
+        let mut build = |structured: &Option<MessageStructEnum>,
+                         level2sym: &Option<LevelSyms>,
+                         time_format: &Option<String>| {
+            if remote {
+                record.message.clone()
+            } else {
+                cache.get(&instance, &record, structured, level2sym, time_format)
+            }
+        };
+        // Recorders first, so that dumped log messages precede the triggering one
+        for writer in instance.writers.values() {
+            if let WriterEnum::Recorder(recorder_writer) = writer {
+                let config = recorder_writer.config.read();
+                if config.level <= level {
+                    let buffer = build(&config.structured, &config.level2sym, &config.time_format);
+                    drop(config);
+                    if let Some(records) =
+                        recorder_writer.send(level, record.domain.clone(), buffer)?
+                    {
+                        send_records(&instance, records)?;
+                    }
+                }
+            }
+        }
+        for writer in instance.writers.values() {
             match writer {
                 WriterEnum::Root => {}
                 WriterEnum::Console(console_writer) => {
-                    if console_writer.config.read().level <= level {
-                        console_writer.send(level, domain.clone(), buffer.clone())?;
+                    let config = console_writer.config.read();
+                    if config.level <= level {
+                        if !remote && config.pretty.is_some() {
+                            // The pretty layout is rendered by the console writer.
+                            let level2sym = config
+                                .level2sym
+                                .as_ref()
+                                .unwrap_or(&instance.level2sym)
+                                .clone();
+                            drop(config);
+                            console_writer
+                                .send_record(log_record(&instance, &record), level2sym)?;
+                            continue;
+                        }
+                        let buffer =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        console_writer.send(level, record.domain.clone(), buffer)?;
                     }
                 }
                 WriterEnum::File(file_writer) => {
-                    if file_writer.config.read().level <= level {
-                        file_writer.send(level, domain.clone(), buffer.clone())?;
+                    let config = file_writer.config.read();
+                    if config.level <= level {
+                        let structured = config.structured.as_ref().unwrap_or(&instance.structured);
+                        if !remote
+                            && *structured == MessageStructEnum::Binary
+                            && config.supports_binary()
+                        {
+                            drop(config);
+                            // Binary records are encoded by the file writer without formatting.
+                            file_writer.send_record(log_record(&instance, &record))?;
+                            continue;
+                        }
+                        let buffer =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        file_writer.send(level, record.domain.clone(), buffer)?;
                     }
                 }
                 WriterEnum::Client(client_writer) => {
-                    if client_writer.config.read().level <= level {
-                        client_writer.send(level, domain.clone(), buffer.clone())?;
+                    let config = client_writer.config.read();
+                    if config.level <= level {
+                        let buffer =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        client_writer.send(level, record.domain.clone(), buffer)?;
                     }
                 }
                 WriterEnum::Server(_logging_server) => {}
                 WriterEnum::Callback(callback_writer) => {
-                    if callback_writer.config.read().level <= level {
-                        callback_writer.send(level, domain.clone(), buffer.clone())?;
+                    let config = callback_writer.config.read();
+                    if config.level <= level {
+                        let buffer =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        callback_writer.send(level, record.domain.clone(), buffer)?;
                     }
                 }
                 WriterEnum::Syslog(syslog_writer) => {
-                    if syslog_writer.config.read().level <= level {
-                        syslog_writer.send(level, domain.clone(), buffer.clone())?;
+                    let config = syslog_writer.config.read();
+                    if config.level <= level {
+                        let buffer =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        syslog_writer.send(level, record.domain.clone(), buffer)?;
+                    }
+                }
+                WriterEnum::Recorder(_recorder_writer) => {}
+                WriterEnum::Capture(capture_writer) => {
+                    let config = capture_writer.config.read();
+                    if config.level <= level {
+                        let formatted =
+                            build(&config.structured, &config.level2sym, &config.time_format);
+                        drop(config);
+                        capture_writer.send(CapturedRecord {
+                            level,
+                            domain: record.domain.clone(),
+                            message: record.message.clone(),
+                            formatted,
+                            tname: record.tname.clone(),
+                            tid: record.tid,
+                            date: record.date,
+                            error: record.error.as_deref().cloned(),
+                            fields: record_fields(&Origin::from(&*instance), &record),
+                        })?;
                     }
                 }
             }
@@ -365,6 +771,16 @@ fn logging_thread(
                     eprintln!("Failed to stop syslog logger: {err:?}");
                 }
             }
+            WriterEnum::Recorder(recorder_writer) => {
+                if let Err(err) = recorder_writer.shutdown() {
+                    eprintln!("Failed to stop recorder writer: {err:?}");
+                }
+            }
+            WriterEnum::Capture(capture_writer) => {
+                if let Err(err) = capture_writer.shutdown() {
+                    eprintln!("Failed to stop capture writer: {err:?}");
+                }
+            }
         }
     }
     if let Some(err) = some_err {
@@ -374,6 +790,28 @@ fn logging_thread(
     }
 }
 
+/// Reopen log files of all file writers of `instance`.
+pub(crate) fn reopen_instance_files(instance: &LoggingInstance) -> Result<(), LoggingError> {
+    for writer in instance.writers.values() {
+        if let WriterEnum::File(writer) = writer {
+            writer.reopen()?;
+        }
+    }
+    Ok(())
+}
+
+/// Writer types synced by `sync_all`.
+pub(crate) fn sync_all_types() -> Vec<WriterTypeEnum> {
+    vec![
+        WriterTypeEnum::Console,
+        WriterTypeEnum::Files,
+        WriterTypeEnum::Clients,
+        WriterTypeEnum::Servers,
+        WriterTypeEnum::Callback,
+        WriterTypeEnum::Syslog,
+    ]
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct Logging {
@@ -399,6 +837,8 @@ impl Logging {
         ext_config: Option<ExtConfig>,          // Extended logging configuration
         config_path: Option<PathBuf>,           // Optional configuration file
     ) -> Result<Self, LoggingError> {
+        // Reference for relative timestamps of the pretty console layout
+        Lazy::force(&PROCESS_START);
         // Initialize config from optional config file.
         let mut config_file = ConfigFile::new();
         let mut instance = LoggingInstance::new(level, domain.into(), configs.unwrap_or_default())?;
@@ -447,6 +887,11 @@ impl Logging {
         Ok(logging)
     }
 
+    /// Create builder for logging instance.
+    pub fn builder() -> LoggingBuilder {
+        LoggingBuilder::default()
+    }
+
     pub fn init() -> Result<Self, LoggingError> {
         let writer = WriterConfigEnum::Console(ConsoleWriterConfig::new(NOTSET, false));
         Logging::new(NOTSET, "root", Some(vec![writer]), None, None)
@@ -503,6 +948,17 @@ impl Logging {
                         self.stop.clone(),
                     )?)));
                 }
+                WriterConfigEnum::Recorder(recorder_writer_config) => {
+                    instance.add_writer(WriterEnum::Recorder(Box::new(RecorderWriter::new(
+                        recorder_writer_config.clone(),
+                        self.server_tx.clone(),
+                    )?)));
+                }
+                WriterConfigEnum::Capture(capture_writer_config) => {
+                    instance.add_writer(WriterEnum::Capture(Box::new(CaptureWriter::new(
+                        capture_writer_config.clone(),
+                    ))));
+                }
             }
         }
         Ok(())
@@ -553,6 +1009,8 @@ impl Logging {
             WriterEnum::Server(logging_server) => logging_server.set_level(level),
             WriterEnum::Callback(callback_writer) => callback_writer.set_level(level),
             WriterEnum::Syslog(syslog_writer) => syslog_writer.set_level(level),
+            WriterEnum::Recorder(recorder_writer) => recorder_writer.set_level(level),
+            WriterEnum::Capture(capture_writer) => capture_writer.set_level(level),
         }
         Ok(())
     }
@@ -635,6 +1093,17 @@ impl Logging {
         self.instance.write().add_writers(writers)
     }
 
+    /// Add capture writer with log level `level`. Returns writer ID and handle to the captured log messages.
+    pub fn add_capture_writer(
+        &mut self,
+        level: u8,
+    ) -> Result<(usize, CaptureHandle), LoggingError> {
+        let config = CaptureWriterConfig::new(level);
+        let handle = config.handle();
+        let wid = self.add_writer_config(&config.into())?;
+        Ok((wid, handle))
+    }
+
     pub fn remove_writers(&mut self, wids: Option<Vec<usize>>) -> Vec<WriterEnum> {
         self.instance.write().remove_writers(wids)
     }
@@ -659,6 +1128,8 @@ impl Logging {
             WriterEnum::Server(logging_server) => logging_server.enable(),
             WriterEnum::Callback(callback_writer) => callback_writer.enable(),
             WriterEnum::Syslog(syslog_writer) => syslog_writer.enable(),
+            WriterEnum::Recorder(recorder_writer) => recorder_writer.enable(),
+            WriterEnum::Capture(capture_writer) => capture_writer.enable(),
         }
         Ok(())
     }
@@ -683,6 +1154,8 @@ impl Logging {
             WriterEnum::Server(logging_server) => logging_server.disable(),
             WriterEnum::Callback(callback_writer) => callback_writer.disable(),
             WriterEnum::Syslog(syslog_writer) => syslog_writer.disable(),
+            WriterEnum::Recorder(recorder_writer) => recorder_writer.disable(),
+            WriterEnum::Capture(capture_writer) => capture_writer.disable(),
         }
         Ok(())
     }
@@ -730,22 +1203,38 @@ impl Logging {
     }
 
     pub fn sync_all(&self, timeout: f64) -> Result<(), LoggingError> {
-        self.sync(
-            vec![
-                WriterTypeEnum::Console,
-                WriterTypeEnum::Files,
-                WriterTypeEnum::Clients,
-                WriterTypeEnum::Servers,
-                WriterTypeEnum::Callback,
-                WriterTypeEnum::Syslog,
-            ],
-            timeout,
-        )?;
+        self.sync(sync_all_types(), timeout)?;
         Ok(())
     }
 
+    /// Dump log messages of all recorder writers, either into their dump files or to the
+    /// other writers.
+    pub fn dump_recorder(&self) -> Result<(), LoggingError> {
+        self.server_tx
+            .send(LoggingTypeEnum::Dump)
+            .map_err(|e| LoggingError::SendError(format!("Failed to send DUMP command: {e}")))
+    }
+
     // File logger
 
+    /// Write all log messages of file writers to disk with fdatasync. Wait for maximum time
+    /// `timeout` seconds.
+    pub fn fsync(&self, timeout: f64) -> Result<(), LoggingError> {
+        // Deliver queued messages to the file writers first.
+        self.sync(vec![WriterTypeEnum::Files], timeout)?;
+        for writer in self.instance.read().writers.values() {
+            if let WriterEnum::File(writer) = writer {
+                writer.fsync(timeout)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reopen all log files, e.g. after they were moved by logrotate.
+    pub fn reopen_files(&self) -> Result<(), LoggingError> {
+        reopen_instance_files(&self.instance.read())
+    }
+
     pub fn rotate(&self, path: Option<PathBuf>) -> Result<(), LoggingError> {
         for writer in self.instance.read().writers.values() {
             if let WriterEnum::File(writer) = writer
@@ -801,6 +1290,8 @@ impl Logging {
                 WriterEnum::Server(logging_server) => logging_server.debug = debug,
                 WriterEnum::Callback(callback_writer) => callback_writer.debug = debug,
                 WriterEnum::Syslog(syslog_writer) => syslog_writer.debug = debug,
+                WriterEnum::Recorder(recorder_writer) => recorder_writer.debug = debug,
+                WriterEnum::Capture(capture_writer) => capture_writer.debug = debug,
             }
         }
     }
@@ -834,6 +1325,11 @@ impl Logging {
         self.instance.read().get_server_configs()
     }
 
+    /// Statistics per client host of logging server `wid`.
+    pub fn get_server_client_stats(&self, wid: usize) -> Result<Vec<ClientStats>, LoggingError> {
+        self.instance.read().get_server_client_stats(wid)
+    }
+
     pub fn get_root_server_address_port(&self) -> Option<String> {
         self.instance.read().get_root_server_address_port()
     }
@@ -981,6 +1477,32 @@ impl Logging {
         Ok(())
     }
 
+    /// Log EXCEPTION level message of error `err`, together with its `source()` chain
+    /// and a backtrace, if enabled with `RUST_BACKTRACE`.
+    pub fn exception_err(&self, err: &dyn std::error::Error) -> Result<(), LoggingError> {
+        if self.level <= EXCEPTION {
+            let tname = if self.tname {
+                thread::current().name().unwrap_or_default().to_string()
+            } else {
+                "".to_string()
+            };
+            let tid = if self.tid { thread_id::get() as u32 } else { 0 };
+            self.server_tx
+                .send(LoggingTypeEnum::MessageErr((
+                    EXCEPTION,
+                    self.domain.clone(),
+                    err.to_string(),
+                    tid,
+                    tname,
+                    Box::new(ErrorChain::new(err)),
+                )))
+                .map_err(|e| {
+                    LoggingError::SendError(format!("Failed to send EXCEPTION message: {e:?}"))
+                })?;
+        }
+        Ok(())
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "Logging(level={} domain={})",
@@ -994,6 +1516,108 @@ impl Logging {
     }
 }
 
+/// Builder for [`Logging`].
+#[derive(Debug, Clone)]
+pub struct LoggingBuilder {
+    level: u8,
+    domain: String,
+    configs: Vec<WriterConfigEnum>,
+    ext_config: Option<ExtConfig>,
+    config_path: Option<PathBuf>,
+    level2sym: Option<LevelSyms>,
+    debug: u8,
+}
+
+impl Default for LoggingBuilder {
+    fn default() -> Self {
+        Self {
+            level: NOTSET,
+            domain: "root".to_string(),
+            configs: Vec::new(),
+            ext_config: None,
+            config_path: None,
+            level2sym: None,
+            debug: 0,
+        }
+    }
+}
+
+impl LoggingBuilder {
+    /// Set global log level.
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    /// Set log domain.
+    pub fn domain<S: Into<String>>(&mut self, domain: S) -> &mut Self {
+        self.domain = domain.into();
+        self
+    }
+
+    /// Add writer configuration.
+    pub fn writer<C: Into<WriterConfigEnum>>(&mut self, config: C) -> &mut Self {
+        self.configs.push(config.into());
+        self
+    }
+
+    /// Add list of writer configurations.
+    pub fn writers(&mut self, configs: Vec<WriterConfigEnum>) -> &mut Self {
+        self.configs.extend(configs);
+        self
+    }
+
+    /// Set extended logging configuration.
+    pub fn ext_config(&mut self, ext_config: ExtConfig) -> &mut Self {
+        self.ext_config = Some(ext_config);
+        self
+    }
+
+    /// Load configuration file `config_path` and merge it into the configuration.
+    pub fn config_path<P: Into<PathBuf>>(&mut self, config_path: P) -> &mut Self {
+        self.config_path = Some(config_path.into());
+        self
+    }
+
+    /// Set how log levels are written into log messages.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Set debug level. Only for developers.
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Validate settings and create logging instance.
+    pub fn build(&self) -> Result<Logging, LoggingError> {
+        if self.domain.is_empty() {
+            return Err(LoggingError::InvalidValue(
+                "Logging domain must not be empty".to_string(),
+            ));
+        }
+        for config in self.configs.iter() {
+            config.check()?;
+        }
+        let mut logging = Logging::new(
+            self.level,
+            self.domain.clone(),
+            Some(self.configs.clone()),
+            self.ext_config.clone(),
+            self.config_path.clone(),
+        )?;
+        if let Some(ref level2sym) = self.level2sym {
+            logging.set_level2sym(level2sym);
+        }
+        if self.debug > 0 {
+            logging.set_debug(self.debug);
+        }
+        Ok(logging)
+    }
+}
+
 impl Default for Logging {
     fn default() -> Self {
         Self::new(NOTSET, "root", None, None, None).unwrap()
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     fmt,
     io::{BufWriter, Error, Write},
     net::TcpStream,
@@ -14,11 +15,33 @@ use std::{
 use flume::{Receiver, SendError, Sender, bounded};
 use parking_lot::RwLock;
 use regex::Regex;
-use ring::aead;
+use ring::aead::{self, SealingKey};
 
-use crate::LoggingError;
+use crate::{
+    LevelSyms, LoggingError, MessageStructEnum, NOTSET,
+    def::{check_filters, check_time_format},
+};
+
+use super::{
+    EncryptionMethod, NonceGenerator,
+    def::{
+        DEFAULT_MAX_MESSAGE_SIZE, NetConfig, PROTOCOL_V2, check_max_message_size,
+        default_max_message_size,
+    },
+};
 
-use super::{EncryptionMethod, def::NetConfig};
+/// Appended to truncated log messages.
+const TRUNCATED: &str = " [truncated]";
+
+/// Handling of log messages larger than the maximum message size.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum OversizeEnum {
+    /// Send the message in several parts, split at line breaks if possible (default)
+    #[default]
+    Split,
+    /// Send only the beginning of the message, marked with " [truncated]"
+    Truncate,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientTypeEnum {
@@ -38,12 +61,27 @@ pub struct ClientWriterConfig {
     pub domain_filter: Option<String>,
     /// Optional filter log messages by their contents
     pub message_filter: Option<String>,
+    /// Optional message structure. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<MessageStructEnum>,
+    /// Optional level naming. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level2sym: Option<LevelSyms>,
+    /// Optional strftime format of the timestamp. Default is "%Y.%m.%d %H:%M:%S".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
     /// IP address to connect and send log messages
     pub address: String,
     /// IP port
     pub port: u16,
     /// Optional key for authentication and message encryption
     pub key: EncryptionMethod,
+    /// Maximum size of log messages in bytes. Must not exceed the limit of the server.
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
+    /// Handling of larger log messages
+    #[serde(default)]
+    pub oversize: OversizeEnum,
     /// Debug level. Only for developers.
     pub debug: u8,
 }
@@ -66,13 +104,39 @@ impl ClientWriterConfig {
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             address,
             port,
             key,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            oversize: OversizeEnum::Split,
             debug: 0,
         }
     }
 
+    /// Create builder for client writer configuration connecting to `address` (`host:port`).
+    pub fn builder<S: Into<String>>(address: S) -> ClientWriterConfigBuilder {
+        ClientWriterConfigBuilder {
+            config: Self {
+                enabled: true,
+                level: NOTSET,
+                domain_filter: None,
+                message_filter: None,
+                structured: None,
+                level2sym: None,
+                time_format: None,
+                address: address.into(),
+                port: 0,
+                key: EncryptionMethod::NONE,
+                max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+                oversize: OversizeEnum::Split,
+                debug: 0,
+            },
+        }
+    }
+
     pub fn get_address_port(&self) -> String {
         if self.port > 0 {
             format!("{}:{}", self.address, self.port)
@@ -80,6 +144,15 @@ impl ClientWriterConfig {
             self.address.clone()
         }
     }
+
+    /// Address as reported by the running writer.
+    pub fn get_address(&self) -> String {
+        if self.address.contains(':') {
+            self.address.clone()
+        } else {
+            format!("{}:{}", self.address, self.port)
+        }
+    }
 }
 
 impl fmt::Display for ClientWriterConfig {
@@ -88,6 +161,170 @@ impl fmt::Display for ClientWriterConfig {
     }
 }
 
+/// Builder for [`ClientWriterConfig`].
+#[derive(Debug, Clone)]
+pub struct ClientWriterConfigBuilder {
+    config: ClientWriterConfig,
+}
+
+impl ClientWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    pub fn key(&mut self, key: EncryptionMethod) -> &mut Self {
+        self.config.key = key;
+        self
+    }
+
+    /// Maximum size of log messages in bytes. Default is 1 MiB.
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut Self {
+        self.config.max_message_size = max_message_size;
+        self
+    }
+
+    /// Handling of log messages larger than the maximum size. Default is splitting them.
+    pub fn oversize(&mut self, oversize: OversizeEnum) -> &mut Self {
+        self.config.oversize = oversize;
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Validate settings and create client writer configuration.
+    pub fn build(&self) -> Result<ClientWriterConfig, LoggingError> {
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        check_max_message_size(self.config.max_message_size)?;
+        let mut config = self.config.clone();
+        config.port = match config.address.rsplit_once(':') {
+            Some((_host, port)) => port.parse::<u16>().map_err(|e| {
+                LoggingError::InvalidValue(format!(
+                    "Invalid port in client address {:?}: {e}",
+                    config.address
+                ))
+            })?,
+            None => {
+                return Err(LoggingError::InvalidValue(format!(
+                    "Client address {:?} has no port",
+                    config.address
+                )));
+            }
+        };
+        Ok(config)
+    }
+}
+
+/// Largest index not above `index` which is a char boundary of `text`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Parts of `message` of at most `max_size` bytes.
+fn split_message<'a>(
+    message: &'a str,
+    max_size: usize,
+    oversize: &OversizeEnum,
+) -> Vec<Cow<'a, str>> {
+    if message.len() <= max_size {
+        return vec![Cow::Borrowed(message)];
+    }
+    match oversize {
+        OversizeEnum::Truncate => {
+            let end = floor_char_boundary(message, max_size - TRUNCATED.len());
+            vec![Cow::Owned(format!("{}{TRUNCATED}", &message[..end]))]
+        }
+        OversizeEnum::Split => {
+            let mut parts = Vec::new();
+            let mut rest = message;
+            while rest.len() > max_size {
+                let end = floor_char_boundary(rest, max_size);
+                // Split at line breaks if possible, e.g. between stack frames.
+                let (part, next) = match rest[..end].rfind('\n') {
+                    Some(pos) if pos > 0 => (&rest[..pos], &rest[pos + 1..]),
+                    _ => (&rest[..end], &rest[end..]),
+                };
+                parts.push(Cow::Borrowed(part));
+                rest = next;
+            }
+            parts.push(Cow::Borrowed(rest));
+            parts
+        }
+    }
+}
+
+/// Write protocol version 2 frame. Domain and message are encrypted if `sk` is set.
+fn write_frame<W: Write>(
+    stream: &mut W,
+    sk: &mut Option<SealingKey<NonceGenerator>>,
+    seal: &str,
+    level: u8,
+    domain: &[u8],
+    message: &[u8],
+) -> Result<(), LoggingError> {
+    let (domain, message) = match sk {
+        Some(sk) => {
+            let mut domain = domain.to_vec();
+            sk.seal_in_place_append_tag(aead::Aad::from(seal), &mut domain)
+                .map_err(|e| Error::other(e.to_string()))?;
+            let mut message = message.to_vec();
+            sk.seal_in_place_append_tag(aead::Aad::from(seal), &mut message)
+                .map_err(|e| Error::other(e.to_string()))?;
+            (Cow::Owned(domain), Cow::Owned(message))
+        }
+        None => (Cow::Borrowed(domain), Cow::Borrowed(message)),
+    };
+    let mut header = [0u8; 8];
+    header[..4].copy_from_slice(&(message.len() as u32).to_le_bytes());
+    header[4] = level;
+    header[6..].copy_from_slice(&(domain.len() as u16).to_le_bytes());
+    stream.write_all(&header)?;
+    stream.write_all(&domain)?;
+    stream.write_all(&message)?;
+    Ok(())
+}
+
 fn client_writer_thread(
     config: Arc<RwLock<NetConfig>>,
     rx: Receiver<ClientTypeEnum>,
@@ -111,7 +348,7 @@ fn client_writer_thread(
             process::id()
         );
     }
-    let mut buffer = [0u8; 4];
+    stream.write_all(&PROTOCOL_V2)?;
     {
         let config = config.read();
         if !config.key.is_encrypted() {
@@ -119,14 +356,10 @@ fn client_writer_thread(
                 println!("{} client_writer_thread SEND KEY", process::id());
             }
             let key = config.key.key().unwrap();
-            let size = key.len();
-            buffer[0] = size as u8;
-            buffer[1] = (size >> 8) as u8;
-            stream.write_all(&buffer)?;
-            stream.write_all(key)?;
-            stream.flush()?;
+            write_frame(&mut stream, &mut None, &config.seal, 0, &[], key)?;
         }
     }
+    stream.flush()?;
     loop {
         if stop.load(Ordering::Relaxed) {
             if debug > 0 {
@@ -143,36 +376,21 @@ fn client_writer_thread(
                     );
                 }
                 let mut config_write = config.write();
-                let size;
-                let seal = config_write.seal.clone();
-                let seal = aead::Aad::from(&seal);
-                if let Some(ref mut sk) = config_write.sk {
-                    let mut domain = domain.as_bytes().to_vec();
-                    sk.seal_in_place_append_tag(seal, &mut domain)
-                        .map_err(|e| Error::other(e.to_string()))?;
-                    let mut message = message.as_bytes().to_vec();
-                    sk.seal_in_place_append_tag(seal, &mut message)
-                        .map_err(|e| Error::other(e.to_string()))?;
-                    size = message.len();
-                    buffer[0] = size as u8;
-                    buffer[1] = (size >> 8) as u8;
-                    buffer[2] = level;
-                    buffer[3] = domain.len() as u8;
-                    let _ = stream.write_all(&buffer);
-                    let _ = stream.write_all(&domain);
-                    let _ = stream.write_all(&message);
-                } else {
-                    size = message.len();
-                    buffer[0] = size as u8;
-                    buffer[1] = (size >> 8) as u8;
-                    buffer[2] = level;
-                    buffer[3] = domain.len() as u8;
-                    //println!("SEND {buffer:?}");
-                    let _ = stream.write_all(&buffer);
-                    let _ = stream.write_all(domain.as_bytes());
-                    //println!("SEND1 {:?}", domain.as_bytes());
-                    let _ = stream.write_all(message.as_bytes());
-                    //println!("SEND2 {:?}", message.as_bytes());
+                let config_write = &mut *config_write;
+                let domain = &domain[..floor_char_boundary(&domain, u16::MAX as usize)];
+                for part in split_message(
+                    &message,
+                    config_write.max_message_size,
+                    &config_write.oversize,
+                ) {
+                    write_frame(
+                        &mut stream,
+                        &mut config_write.sk,
+                        &config_write.seal,
+                        level,
+                        domain.as_bytes(),
+                        part.as_bytes(),
+                    )?;
                 }
                 stream.flush()?;
             }
@@ -209,12 +427,23 @@ impl ClientWriter {
         writer_config: ClientWriterConfig,
         stop: Arc<AtomicBool>,
     ) -> Result<Self, LoggingError> {
-        let config = Arc::new(RwLock::new(NetConfig::new(
+        check_max_message_size(writer_config.max_message_size)?;
+        let mut net_config = NetConfig::new(
             writer_config.level,
             writer_config.address,
             writer_config.port,
             writer_config.key,
-        )?));
+        )?;
+        net_config.enabled = writer_config.enabled;
+        net_config.domain_filter = writer_config.domain_filter;
+        net_config.message_filter = writer_config.message_filter;
+        net_config.structured = writer_config.structured;
+        net_config.level2sym = writer_config.level2sym;
+        net_config.time_format = writer_config.time_format;
+        net_config.max_message_size = writer_config.max_message_size;
+        net_config.oversize = writer_config.oversize;
+        net_config.debug = writer_config.debug;
+        let config = Arc::new(RwLock::new(net_config));
         let (tx, rx) = bounded(1000);
         let (sync_tx, sync_rx) = bounded(1);
         let (tx_started, rx_started) = bounded(1);
@@ -4,25 +4,58 @@ use once_cell::sync::Lazy;
 use rand::{RngExt, distr::Alphanumeric, rng};
 use ring::aead::{self, BoundKey, SealingKey};
 
-use crate::{ClientWriterConfig, LoggingError, ServerConfig};
+use crate::{ClientWriterConfig, LevelSyms, LoggingError, MessageStructEnum, ServerConfig};
 
-use super::{EncryptionMethod, NonceGenerator};
+use super::{EncryptionMethod, NonceGenerator, OversizeEnum};
 
 pub static AUTH_KEY: Lazy<Vec<u8>> =
     Lazy::new(|| rng().sample_iter(&Alphanumeric).take(32).collect());
 
+/// Default maximum size of log messages sent over the network, in bytes.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1 << 20;
+/// Smallest allowed maximum message size.
+pub(crate) const MIN_MAX_MESSAGE_SIZE: usize = 256;
+
+/// First bytes of connections with protocol version 2. Version 1 servers read them as
+/// invalid message size and close the connection.
+///
+/// Version 2 frames: `[size:u32 LE, level:u8, 0, domain size:u16 LE, domain, message]`.
+/// Version 1 frames: `[size:u16 LE, level:u8, domain size:u8, domain, message]`.
+pub(crate) const PROTOCOL_V2: [u8; 4] = [0xfe, 0xff, 2, 0];
+/// First bytes of a connection which stops the server.
+pub(crate) const STOP_SERVER: [u8; 4] = [0xff; 4];
+
+pub(crate) fn default_max_message_size() -> usize {
+    DEFAULT_MAX_MESSAGE_SIZE
+}
+
+pub(crate) fn check_max_message_size(max_message_size: usize) -> Result<(), LoggingError> {
+    if max_message_size < MIN_MAX_MESSAGE_SIZE || max_message_size > u32::MAX as usize {
+        return Err(LoggingError::InvalidValue(format!(
+            "Maximum message size {max_message_size} is not between {MIN_MAX_MESSAGE_SIZE} and {}",
+            u32::MAX
+        )));
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct NetConfig {
     pub(crate) enabled: bool,
     pub(crate) level: u8,
     pub(crate) domain_filter: Option<String>,
     pub(crate) message_filter: Option<String>,
+    pub(crate) structured: Option<MessageStructEnum>,
+    pub(crate) level2sym: Option<LevelSyms>,
+    pub(crate) time_format: Option<String>,
     pub(crate) address: String,
     pub(crate) port: u16,
     pub(crate) key: EncryptionMethod,
     pub(crate) sk: Option<SealingKey<NonceGenerator>>,
     pub(crate) seal: String,
     pub(crate) port_file: Option<PathBuf>,
+    pub(crate) max_message_size: usize,
+    pub(crate) oversize: OversizeEnum,
     pub(crate) debug: u8,
 }
 
@@ -38,12 +71,17 @@ impl NetConfig {
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             address,
             port,
             key: key.clone(),
             sk: None,
             seal: "FastLoggingRs".to_string(),
             port_file: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            oversize: OversizeEnum::Split,
             debug: 0,
         };
         config.set_encryption(key)?;
@@ -87,6 +125,7 @@ impl NetConfig {
             port: self.port,
             key: self.key.clone(),
             port_file: self.port_file.clone(),
+            max_message_size: self.max_message_size,
         }
     }
 
@@ -96,9 +135,14 @@ impl NetConfig {
             level: self.level,
             domain_filter: self.domain_filter.clone(),
             message_filter: self.message_filter.clone(),
+            structured: self.structured.clone(),
+            level2sym: self.level2sym.clone(),
+            time_format: self.time_format.clone(),
             address: self.address.clone(),
             port: self.port,
             key: self.key.clone(),
+            max_message_size: self.max_message_size,
+            oversize: self.oversize.clone(),
             debug: self.debug,
         }
     }
@@ -1,12 +1,26 @@
+use std::fmt;
+
 use ring::{aead, error::Unspecified};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub enum EncryptionMethod {
+    #[default]
     NONE,
     AuthKey(Vec<u8>),
     AES(Vec<u8>),
 }
 
+/// Debug output without the key.
+impl fmt::Debug for EncryptionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NONE => write!(f, "NONE"),
+            Self::AuthKey(_) => write!(f, "AuthKey(<redacted>)"),
+            Self::AES(_) => write!(f, "AES(<redacted>)"),
+        }
+    }
+}
+
 impl EncryptionMethod {
     pub fn is_encrypted(&self) -> bool {
         matches!(self, Self::AES(_))
@@ -76,7 +90,7 @@ impl NonceGenerator {
 
 impl aead::NonceSequence for NonceGenerator {
     fn advance(&mut self) -> Result<aead::Nonce, Unspecified> {
-        self.last_nonce += self.last_nonce.checked_add(1).ok_or(Unspecified)?;
+        self.last_nonce = self.last_nonce.checked_add(1).ok_or(Unspecified)?;
         let mut nonce = [0u8; 12];
         nonce[0..8].copy_from_slice(&self.last_nonce.to_le_bytes()); // 0 guarantees that this is correct lmao
         Ok(aead::Nonce::assume_unique_for_key(nonce))
@@ -1,22 +1,32 @@
-mod def;
-pub use def::AUTH_KEY;
+pub(crate) mod def;
+pub use def::{AUTH_KEY, DEFAULT_MAX_MESSAGE_SIZE};
 mod server;
-pub use server::{LoggingServer, ServerConfig};
+pub use server::{ClientStats, LoggingServer, ServerConfig, ServerConfigBuilder};
 mod client;
-pub use client::{ClientTypeEnum, ClientWriter, ClientWriterConfig};
+pub use client::{
+    ClientTypeEnum, ClientWriter, ClientWriterConfig, ClientWriterConfigBuilder, OversizeEnum,
+};
 mod encryption;
 pub use encryption::{EncryptionMethod, NonceGenerator};
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        io::Write,
+        net::TcpStream,
+        sync::{Arc, atomic::AtomicBool},
+        thread,
+        time::{Duration, Instant},
+    };
+
     use tempfile::TempDir;
 
     use crate::{
-        ClientWriterConfig, ConsoleWriterConfig, DEBUG, FileWriterConfig, Logging, NOTSET,
-        ServerConfig,
+        CaptureWriterConfig, ClientWriter, ClientWriterConfig, ConsoleWriterConfig, DEBUG,
+        FileWriterConfig, Logging, NOTSET, ServerConfig, WriterConfigEnum,
     };
 
-    use super::EncryptionMethod;
+    use super::{EncryptionMethod, OversizeEnum, def::PROTOCOL_V2};
 
     #[test]
     fn unencrypted_one_client() {
@@ -75,4 +85,225 @@ mod tests {
         let _log_text = std::fs::read_to_string(&log_file).unwrap();
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn client_stats() {
+        let capture = CaptureWriterConfig::builder().build().unwrap();
+        let handle = capture.handle();
+        let mut logging_server = Logging::builder()
+            .domain("server")
+            .writer(ServerConfig::new(
+                DEBUG,
+                "127.0.0.1",
+                EncryptionMethod::NONE,
+            ))
+            .writer(capture)
+            .build()
+            .unwrap();
+        let (wid, server) = logging_server
+            .get_server_configs()
+            .into_iter()
+            .next()
+            .unwrap();
+        let mut logging_client = Logging::builder()
+            .domain("client")
+            .writer(ClientWriterConfig::new(
+                DEBUG,
+                format!("127.0.0.1:{}", server.port),
+                logging_server.get_server_auth_key(),
+            ))
+            .build()
+            .unwrap();
+        for i in 0..3 {
+            logging_client.info(format!("Message {i}")).unwrap();
+        }
+        handle.wait_for(3, 5.0).unwrap();
+        let stats = logging_server.get_server_client_stats(wid).unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].address.to_string(), "127.0.0.1");
+        assert_eq!(stats[0].connections, 1);
+        assert_eq!(stats[0].messages, 3);
+        assert!(stats[0].bytes > 3 * "Message 0".len() as u64);
+        logging_client.shutdown(false).unwrap();
+        assert!(logging_server.get_server_client_stats(wid + 1).is_err());
+        logging_server.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn client_eof() {
+        let mut logging_server = Logging::builder()
+            .domain("server")
+            .writer(ServerConfig::new(
+                DEBUG,
+                "127.0.0.1",
+                EncryptionMethod::NONE,
+            ))
+            .build()
+            .unwrap();
+        let (wid, server) = logging_server
+            .get_server_configs()
+            .into_iter()
+            .next()
+            .unwrap();
+        // Client closes the connection within a frame header.
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+        stream.write_all(&PROTOCOL_V2).unwrap();
+        stream.write_all(&[1, 0]).unwrap();
+        drop(stream);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let stats = loop {
+            let stats = logging_server.get_server_client_stats(wid).unwrap();
+            if stats.first().is_some_and(|client| client.connections == 0) {
+                break stats;
+            }
+            assert!(Instant::now() < deadline, "{stats:?}");
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(stats[0].total_connections, 1);
+        assert_eq!(stats[0].errors, 0);
+        logging_server.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn large_messages() {
+        let mut config = ClientWriterConfig::builder("127.0.0.1:1").build().unwrap();
+        for max_message_size in [0, 11] {
+            config.max_message_size = max_message_size;
+            assert!(WriterConfigEnum::Client(config.clone()).check().is_err());
+            assert!(ClientWriter::new(config.clone(), Arc::new(AtomicBool::new(false))).is_err());
+        }
+        let capture = CaptureWriterConfig::builder().build().unwrap();
+        let handle = capture.handle();
+        let mut logging_server = Logging::builder()
+            .domain("server")
+            .writer(
+                ServerConfig::builder("127.0.0.1")
+                    .level(DEBUG)
+                    .max_message_size(200_000)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(capture)
+            .build()
+            .unwrap();
+        let (wid, server) = logging_server
+            .get_server_configs()
+            .into_iter()
+            .next()
+            .unwrap();
+        let address = format!("127.0.0.1:{}", server.port);
+        let client = |domain: &str, max_message_size: usize, oversize: OversizeEnum| {
+            Logging::builder()
+                .domain(domain)
+                .writer(
+                    ClientWriterConfig::builder(address.clone())
+                        .level(DEBUG)
+                        .key(logging_server.get_server_auth_key())
+                        .max_message_size(max_message_size)
+                        .oversize(oversize)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap()
+        };
+        // Large message and long domain
+        let domain = "d".repeat(300);
+        let message = "x".repeat(100_000);
+        let mut logging_client = client(&domain, 1 << 20, OversizeEnum::Split);
+        logging_client.info(message.clone()).unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        let record = handle.drain().pop().unwrap();
+        assert_eq!(record.domain, domain);
+        assert!(record.message.ends_with(&format!(" {message}")));
+        // Messages above the server limit are skipped without closing the connection.
+        logging_client.info("y".repeat(300_000)).unwrap();
+        logging_client.info("After".to_string()).unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        assert!(handle.drain().pop().unwrap().message.ends_with(" After"));
+        logging_client.shutdown(false).unwrap();
+        // Split at line breaks. Messages are sent with their header.
+        let lines: Vec<String> = (0..50)
+            .map(|i| format!("{i:02}{}", "z".repeat(48)))
+            .collect();
+        let mut logging_client = client("split", 1000, OversizeEnum::Split);
+        logging_client.info(lines.join("\n")).unwrap();
+        handle.wait_for(3, 5.0).unwrap();
+        let parts: Vec<String> = handle
+            .drain()
+            .into_iter()
+            .map(|record| record.message.split_once(": ").unwrap().1.to_string())
+            .collect();
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|part| part.len() <= 1000));
+        assert!(parts[1].starts_with("19z"));
+        assert!(parts.join("\n").ends_with(&lines.join("\n")));
+        logging_client.shutdown(false).unwrap();
+        // Truncate
+        let mut logging_client = client("truncate", 1000, OversizeEnum::Truncate);
+        logging_client.info("ä".repeat(1000)).unwrap();
+        handle.wait_for(1, 5.0).unwrap();
+        let message = handle.drain().pop().unwrap().message;
+        let message = message.split_once(": ").unwrap().1;
+        assert!(message.len() <= 1000);
+        assert!(message.ends_with("ää [truncated]"));
+        logging_client.shutdown(false).unwrap();
+        let stats = logging_server.get_server_client_stats(wid).unwrap();
+        assert_eq!(stats[0].messages, 6);
+        assert_eq!(stats[0].dropped, 1);
+        assert_eq!(stats[0].errors, 0);
+        logging_server.shutdown(false).unwrap();
+    }
+
+    #[test]
+    fn encrypted() {
+        let key = EncryptionMethod::AES(b"abcdefghijklmnopqrstuvwxyz012345".to_vec());
+        let capture = CaptureWriterConfig::builder().build().unwrap();
+        let handle = capture.handle();
+        let mut logging_server = Logging::builder()
+            .domain("server")
+            .writer(
+                ServerConfig::builder("127.0.0.1")
+                    .level(DEBUG)
+                    .key(key.clone())
+                    .max_message_size(10_000)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(capture)
+            .build()
+            .unwrap();
+        let (wid, server) = logging_server
+            .get_server_configs()
+            .into_iter()
+            .next()
+            .unwrap();
+        let mut logging_client = Logging::builder()
+            .domain("client")
+            .writer(
+                ClientWriterConfig::builder(format!("127.0.0.1:{}", server.port))
+                    .level(DEBUG)
+                    .key(key)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging_client.info("First".to_string()).unwrap();
+        logging_client.info("x".repeat(20_000)).unwrap();
+        logging_client.info("y".repeat(8_000)).unwrap();
+        handle.wait_for(2, 5.0).unwrap();
+        let records = handle.drain();
+        assert_eq!(records[0].domain, "client");
+        assert!(records[0].message.ends_with(" First"));
+        assert!(
+            records[1]
+                .message
+                .ends_with(&format!(" {}", "y".repeat(8_000)))
+        );
+        logging_client.shutdown(false).unwrap();
+        let stats = logging_server.get_server_client_stats(wid).unwrap();
+        assert_eq!(stats[0].dropped, 1);
+        logging_server.shutdown(false).unwrap();
+    }
 }
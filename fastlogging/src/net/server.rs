@@ -1,8 +1,8 @@
 use std::{
     collections::HashMap,
     fmt,
-    io::{Error, ErrorKind, Read, Write},
-    net::{Shutdown, TcpListener, TcpStream},
+    io::{self, Error, ErrorKind, Read, Write},
+    net::{IpAddr, Shutdown, TcpListener, TcpStream},
     path::PathBuf,
     process,
     sync::{
@@ -13,14 +13,21 @@ use std::{
     time::Duration,
 };
 
+use chrono::{DateTime, Local};
 use flume::{Sender, bounded};
 use parking_lot::RwLock;
 use regex::Regex;
 use ring::aead::{self, BoundKey};
 
-use crate::{LoggingError, def::LoggingTypeEnum};
+use crate::{LoggingError, NOTSET, def::LoggingTypeEnum, root::write_port_file};
 
-use super::{EncryptionMethod, NonceGenerator, def::NetConfig};
+use super::{
+    EncryptionMethod, NonceGenerator,
+    def::{
+        DEFAULT_MAX_MESSAGE_SIZE, NetConfig, PROTOCOL_V2, STOP_SERVER, check_max_message_size,
+        default_max_message_size,
+    },
+};
 
 #[repr(C)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +42,9 @@ pub struct ServerConfig {
     pub key: EncryptionMethod,
     /// Temporary file for key exchange between server and client process
     pub port_file: Option<PathBuf>,
+    /// Maximum size of received log messages in bytes. Larger messages are skipped.
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
 }
 
 impl ServerConfig {
@@ -56,6 +66,22 @@ impl ServerConfig {
             port,
             key,
             port_file: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Create builder for server configuration listening on `address` (`host` or `host:port`).
+    pub fn builder<S: Into<String>>(address: S) -> ServerConfigBuilder {
+        ServerConfigBuilder {
+            address: address.into(),
+            config: Self {
+                level: NOTSET,
+                address: String::new(),
+                port: 0,
+                key: EncryptionMethod::NONE,
+                port_file: None,
+                max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            },
         }
     }
 
@@ -66,6 +92,15 @@ impl ServerConfig {
             self.address.clone()
         }
     }
+
+    /// Address as reported by the running server.
+    pub fn get_address(&self) -> String {
+        if self.address.contains(':') {
+            self.address.clone()
+        } else {
+            format!("{}:{}", self.address, self.port)
+        }
+    }
 }
 
 impl fmt::Display for ServerConfig {
@@ -74,99 +109,223 @@ impl fmt::Display for ServerConfig {
     }
 }
 
-fn read(
-    stream: &mut TcpStream,
-    buffer: &mut [u8],
-    read_max: usize,
-) -> Result<usize, std::io::Error> {
-    let mut bytes_read = 0;
-    loop {
-        let cnt = match stream.read(&mut buffer[bytes_read..read_max]) {
-            Ok(s) => s,
-            Err(e) => {
-                if e.kind() != ErrorKind::WouldBlock && e.kind() != ErrorKind::TimedOut {
-                    eprintln!("read ERROR: {e:?}");
-                }
-                return Err(e);
-            }
+/// Builder for [`ServerConfig`].
+#[derive(Debug, Clone)]
+pub struct ServerConfigBuilder {
+    address: String,
+    config: ServerConfig,
+}
+
+impl ServerConfigBuilder {
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn key(&mut self, key: EncryptionMethod) -> &mut Self {
+        self.config.key = key;
+        self
+    }
+
+    /// Write listening port and key into `port_file` for client processes.
+    pub fn port_file<P: Into<PathBuf>>(&mut self, port_file: P) -> &mut Self {
+        self.config.port_file = Some(port_file.into());
+        self
+    }
+
+    /// Maximum size of received log messages in bytes. Default is 1 MiB.
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut Self {
+        self.config.max_message_size = max_message_size;
+        self
+    }
+
+    /// Validate settings and create server configuration.
+    pub fn build(&self) -> Result<ServerConfig, LoggingError> {
+        let mut config = self.config.clone();
+        // If port is missing or 0 then the used port will be chosen by the OS.
+        (config.address, config.port) = match self.address.rsplit_once(':') {
+            Some((host, port)) => (
+                host.to_string(),
+                port.parse::<u16>().map_err(|e| {
+                    LoggingError::InvalidValue(format!(
+                        "Invalid port in server address {:?}: {e}",
+                        self.address
+                    ))
+                })?,
+            ),
+            None => (self.address.clone(), 0),
         };
-        bytes_read += cnt;
-        if cnt == 0 {
-            stream.peer_addr()?;
-            thread::sleep(Duration::from_millis(10));
+        if config.address.is_empty() {
+            return Err(LoggingError::InvalidValue(
+                "Server requires an address".to_string(),
+            ));
         }
-        if bytes_read >= read_max {
-            break;
+        check_max_message_size(config.max_message_size)?;
+        Ok(config)
+    }
+}
+
+/// Statistics of the clients of a logging server from one host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientStats {
+    pub address: IpAddr,
+    /// Currently open connections
+    pub connections: usize,
+    /// Connections since server start
+    pub total_connections: u64,
+    /// Received log messages, including messages below the server level
+    pub messages: u64,
+    /// Received bytes of log messages
+    pub bytes: u64,
+    /// Connections closed because of invalid data
+    pub errors: u64,
+    /// Log messages skipped because they exceed the maximum message size
+    pub dropped: u64,
+    /// Time of last connect or message
+    pub last_seen: DateTime<Local>,
+}
+
+impl ClientStats {
+    fn new(address: IpAddr) -> Self {
+        Self {
+            address,
+            connections: 0,
+            total_connections: 0,
+            messages: 0,
+            bytes: 0,
+            errors: 0,
+            dropped: 0,
+            last_seen: Local::now(),
         }
     }
-    Ok(bytes_read)
+}
+
+type ClientStatsMap = Arc<RwLock<HashMap<IpAddr, ClientStats>>>;
+
+/// Count received message of `size` bytes from `address`.
+fn count_message(stats: &ClientStatsMap, address: IpAddr, size: usize) {
+    if let Some(client) = stats.write().get_mut(&address) {
+        client.messages += 1;
+        client.bytes += size as u64;
+        client.last_seen = Local::now();
+    }
+}
+
+/// Count skipped message from `address`.
+fn count_dropped(stats: &ClientStatsMap, address: IpAddr) {
+    if let Some(client) = stats.write().get_mut(&address) {
+        client.dropped += 1;
+        client.last_seen = Local::now();
+    }
+}
+
+/// Read frame header. Returns `false` if the client has closed the connection.
+fn read_header(stream: &mut TcpStream, header: &mut [u8]) -> Result<bool, io::Error> {
+    match stream.read_exact(header) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Protocol {
+    V1,
+    V2,
 }
 
 fn handle_client(
     config: Arc<RwLock<NetConfig>>,
     stream: &mut TcpStream,
     tx: Sender<LoggingTypeEnum>,
+    stats: &ClientStatsMap,
     stop: Arc<AtomicBool>,
     stop_server: Arc<AtomicBool>,
 ) -> Result<bool, LoggingError> {
-    let perr_addr = stream.peer_addr().unwrap().to_string();
-    let mut domain_buffer = [0u8; 256];
-    let mut buffer = [0u8; 4352];
-    let mut authenticated = false;
+    let peer = stream.peer_addr()?;
+    let perr_addr = peer.to_string();
     let mut debug = config.read().debug;
     if debug > 0 {
         println!("{} handle_client BEGIN", process::id());
     }
+    let mut header = [0u8; 8];
+    if !read_header(stream, &mut header[..4])? {
+        return Ok(false);
+    }
+    if header[..4] == STOP_SERVER {
+        return Ok(true);
+    }
+    // Clients of protocol version 1 start with their first frame.
+    let protocol = if header[..4] == PROTOCOL_V2 {
+        Protocol::V2
+    } else {
+        Protocol::V1
+    };
+    let (key, seal) = {
+        let config = config.read();
+        (config.key.clone(), config.seal.clone())
+    };
+    let mut opening_key = match key {
+        EncryptionMethod::AES(ref key) => {
+            if protocol == Protocol::V1 {
+                Err(LoggingError::RecvError(
+                    "Encrypted connections require protocol version 2".to_string(),
+                ))?;
+            }
+            Some(aead::OpeningKey::new(
+                aead::UnboundKey::new(&aead::AES_256_GCM, key)
+                    .map_err(|e| Error::other(e.to_string()))?,
+                NonceGenerator::new(),
+            ))
+        }
+        _ => None,
+    };
+    let tag_len = if opening_key.is_some() {
+        aead::MAX_TAG_LEN
+    } else {
+        0
+    };
+    // If channel is unencrypted then an AUTH_KEY is required first.
+    let mut authenticated = opening_key.is_some();
+    let mut have_header = protocol == Protocol::V1;
+    let mut domain_buffer = Vec::new();
+    let mut buffer = Vec::new();
     loop {
         if stop.load(Ordering::Relaxed) || stop_server.load(Ordering::Relaxed) {
             break;
         }
         debug = config.read().debug;
         if debug > 1 {
-            println!(
-                "{} handle_client: WAIT {:?}",
-                process::id(),
-                stream.peer_addr()
-            );
+            println!("{} handle_client: WAIT {peer:?}", process::id());
         }
-        if let Err(err) = read(stream, &mut buffer, 4) {
-            if err.kind() == ErrorKind::WouldBlock || err.kind() != ErrorKind::TimedOut {
-                continue;
-            }
-            eprintln!(
-                "{} handle_client: ERROR {err:?} {:?}",
-                process::id(),
-                &buffer[..4]
-            );
+        let header_size = match protocol {
+            Protocol::V1 => 4,
+            Protocol::V2 => 8,
+        };
+        if !have_header && !read_header(stream, &mut header[..header_size])? {
             break;
         }
-        let message_size = (buffer[0] as usize) | ((buffer[1] as usize) << 8);
-        if message_size > buffer.len() {
-            // Exit if received data is too big
-            if message_size < 0xfffe {
-                Err(LoggingError::RecvError(format!(
-                    "Receive size {message_size} is too big"
-                )))?;
-            } else if message_size == 0xffff {
-                // Exit server
-                return Ok(true);
-            }
-            // Exit just this client
-            return Ok(false);
-        }
+        have_header = false;
+        let (message_size, msg_level, domain_size) = match protocol {
+            Protocol::V1 => (
+                u16::from_le_bytes([header[0], header[1]]) as usize,
+                header[2],
+                header[3] as usize,
+            ),
+            Protocol::V2 => (
+                u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize,
+                header[4],
+                u16::from_le_bytes([header[6], header[7]]) as usize,
+            ),
+        };
         if !authenticated {
-            //println!("handle_client: AUTHENTICATE");
-            // If channel is unencrypted then an AUTH_KEY is required first.
-            // Wait up to 5 seconds for auth key.
-            read(stream, &mut buffer, message_size)?;
-            let key: Vec<u8> = config.read().key.key_cloned().unwrap();
-            /*println!(
-                "AUTH_KEY: {} {message_size}\n{:?}\n{:?}",
-                key.len(),
-                key,
-                &buffer[..message_size]
-            );*/
-            if key.len() != message_size || !key.starts_with(&buffer[..message_size]) {
+            let key = key.key().unwrap();
+            if key.len() != message_size {
+                Err(LoggingError::RecvError("Invalid auth key".to_string()))?;
+            }
+            buffer.resize(message_size, 0);
+            stream.read_exact(&mut buffer)?;
+            if key != buffer.as_slice() {
                 Err(LoggingError::RecvError("Invalid auth key".to_string()))?;
             }
             if debug > 1 {
@@ -175,18 +334,46 @@ fn handle_client(
             authenticated = true;
             continue;
         }
-        let msg_level = buffer[2];
-        let domain_size = buffer[3] as usize;
-        let domain_data = &mut domain_buffer[..domain_size];
-        stream.read_exact(domain_data)?;
-        let message_data = &mut buffer[..message_size];
-        stream.read_exact(message_data)?;
+        if message_size > config.read().max_message_size + tag_len {
+            if debug > 0 {
+                println!(
+                    "{} handle_client: SKIP MESSAGE with {message_size} bytes",
+                    process::id()
+                );
+            }
+            let skip_size = (domain_size + message_size) as u64;
+            if io::copy(&mut (&mut *stream).take(skip_size), &mut io::sink())? < skip_size {
+                break;
+            }
+            if let Some(ref mut key) = opening_key {
+                // Opening advances the nonce even if it fails. This keeps the nonces of
+                // client and server in sync.
+                let _ = key.open_in_place(aead::Aad::from(&seal), &mut []);
+                let _ = key.open_in_place(aead::Aad::from(&seal), &mut []);
+            }
+            count_dropped(stats, peer.ip());
+            continue;
+        }
+        domain_buffer.resize(domain_size, 0);
+        stream.read_exact(&mut domain_buffer)?;
+        buffer.resize(message_size, 0);
+        stream.read_exact(&mut buffer)?;
+        count_message(stats, peer.ip(), header_size + domain_size + message_size);
+        // Encrypted data has to be opened always to keep the nonces in sync.
+        let (domain_data, message_data) = match opening_key {
+            Some(ref mut key) => (
+                &*key
+                    .open_in_place(aead::Aad::from(&seal), &mut domain_buffer)
+                    .map_err(|e| Error::other(e.to_string()))?,
+                &*key
+                    .open_in_place(aead::Aad::from(&seal), &mut buffer)
+                    .map_err(|e| Error::other(e.to_string()))?,
+            ),
+            None => (domain_buffer.as_slice(), buffer.as_slice()),
+        };
         if msg_level >= config.read().level {
-            let domain = std::str::from_utf8(domain_data).unwrap().to_string();
-            let message = format!(
-                "{perr_addr}: {}",
-                std::str::from_utf8(message_data).unwrap()
-            );
+            let domain = String::from_utf8_lossy(domain_data).to_string();
+            let message = format!("{perr_addr}: {}", String::from_utf8_lossy(message_data));
             if debug > 2 {
                 println!(
                     "{} handle_client: MESSAGE {domain}: {message:?}",
@@ -197,83 +384,7 @@ fn handle_client(
         }
     }
     if debug > 0 {
-        println!(
-            "{} handle_client: FINISHED {:?}",
-            process::id(),
-            stream.peer_addr()
-        );
-    }
-    Ok(false)
-}
-
-fn handle_encrypted_client(
-    config: Arc<RwLock<NetConfig>>,
-    stream: &mut TcpStream,
-    tx: Sender<LoggingTypeEnum>,
-    stop: Arc<AtomicBool>,
-    stop_server: Arc<AtomicBool>,
-) -> Result<bool, LoggingError> {
-    //println!("handle_encrypted_client");
-    let perr_addr = stream.peer_addr().unwrap().to_string();
-    let mut domain_buffer = [0u8; 512];
-    let mut buffer = [0u8; 4352];
-    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-    let mut key = aead::OpeningKey::new(
-        aead::UnboundKey::new(&aead::AES_256_GCM, config.read().key.key().unwrap())
-            .map_err(|e| Error::other(e.to_string()))?,
-        NonceGenerator::new(),
-    );
-    let seal = aead::Aad::from(config.read().seal.clone());
-    let mut config_level;
-    let mut debug;
-    loop {
-        if stop.load(Ordering::Relaxed) || stop_server.load(Ordering::Relaxed) {
-            break;
-        }
-        let config_read = config.read();
-        config_level = config_read.level;
-        debug = config_read.debug;
-        if debug > 1 {
-            println!("handle_encrypted_client: WAIT");
-        }
-        if let Err(err) = stream.read_exact(&mut buffer[..3])
-            && err.kind() == ErrorKind::WouldBlock
-        {
-            continue;
-        }
-        let size = (buffer[0] as usize) | ((buffer[1] as usize) << 8);
-        if size > buffer.len() {
-            // Exit if received data is too big
-            if size < 0xffff {
-                Err(LoggingError::RecvError(format!(
-                    "Receive size {size} is too big"
-                )))?;
-            }
-            return Ok(true);
-        }
-        let msg_level = buffer[2];
-        let domain_size = buffer[3] as usize;
-        let domain_data = &mut domain_buffer[..domain_size];
-        stream.read_exact(domain_data)?;
-        let message_data = &mut buffer[..size];
-        stream.read_exact(message_data)?;
-        if msg_level >= config_level {
-            let _ = key
-                .open_in_place(seal.clone(), domain_data)
-                .map_err(|e| Error::other(e.to_string()))?;
-            let _ = key
-                .open_in_place(seal.clone(), message_data)
-                .map_err(|e| Error::other(e.to_string()))?;
-            let domain = std::str::from_utf8(domain_data).unwrap().to_string();
-            let message = format!(
-                "{perr_addr}: {}",
-                std::str::from_utf8(message_data).unwrap()
-            );
-            if debug > 2 {
-                println!("handle_encrypted_client: MESSAGE {domain}: {message:?}");
-            }
-            tx.send(LoggingTypeEnum::MessageRemote((msg_level, domain, message)))?;
-        }
+        println!("{} handle_client: FINISHED {peer:?}", process::id());
     }
     Ok(false)
 }
@@ -282,6 +393,7 @@ fn server_thread(
     config: Arc<RwLock<NetConfig>>,
     listener: TcpListener,
     tx: Sender<LoggingTypeEnum>,
+    stats: ClientStatsMap,
     stop: Arc<AtomicBool>,
 ) -> Result<(), LoggingError> {
     let mut debug = config.read().debug;
@@ -302,7 +414,6 @@ fn server_thread(
             }
             break;
         }
-        // Message format: [size:u16, level:u8, data]
         let mut stream = match stream {
             Ok(stream) => stream,
             Err(e) => {
@@ -330,8 +441,18 @@ fn server_thread(
         if *buggy_clients.read().get(&addr).unwrap_or(&0) > 3 {
             continue;
         }
+        {
+            let mut stats = stats.write();
+            let client = stats
+                .entry(addr.ip())
+                .or_insert_with(|| ClientStats::new(addr.ip()));
+            client.connections += 1;
+            client.total_connections += 1;
+            client.last_seen = Local::now();
+        }
         let config = config.clone();
         let tx = tx.clone();
+        let stats = stats.clone();
         let buggy_clients = buggy_clients.clone();
         let stop = stop.clone();
         let stop_server = stop_server.clone();
@@ -345,10 +466,11 @@ fn server_thread(
                     process::id()
                 );
             }
-            let result = match is_encrypted {
-                false => handle_client(config, &mut stream, tx, stop, stop_server.clone()),
-                true => handle_encrypted_client(config, &mut stream, tx, stop, stop_server.clone()),
-            };
+            let result = handle_client(config, &mut stream, tx, &stats, stop, stop_server.clone());
+            if let Some(client) = stats.write().get_mut(&addr.ip()) {
+                client.connections -= 1;
+                client.errors += result.is_err() as u64;
+            }
             if debug > 0 {
                 println!(
                     "{} server_thread: CLIENT {} DISCONNECTED {addr:?}",
@@ -404,6 +526,7 @@ fn server_thread(
 #[derive(Debug)]
 pub struct LoggingServer {
     pub(crate) config: Arc<RwLock<NetConfig>>,
+    stats: ClientStatsMap,
     thr: Option<JoinHandle<()>>,
     pub(crate) debug: u8,
 }
@@ -414,12 +537,12 @@ impl LoggingServer {
         tx: Sender<LoggingTypeEnum>,
         stop: Arc<AtomicBool>,
     ) -> Result<Self, LoggingError> {
-        let config = Arc::new(RwLock::new(NetConfig::new(
-            config.level,
-            config.address,
-            config.port,
-            config.key,
-        )?));
+        let mut net_config = NetConfig::new(config.level, config.address, config.port, config.key)?;
+        net_config.port_file = config.port_file;
+        net_config.max_message_size = config.max_message_size;
+        let config = Arc::new(RwLock::new(net_config));
+        let stats: ClientStatsMap = Arc::new(RwLock::new(HashMap::new()));
+        let stats_clone = stats.clone();
         let config_clone = config.clone();
         let (tx_started, rx_started) = bounded(1);
         let thr = thread::Builder::new()
@@ -452,10 +575,19 @@ impl LoggingServer {
                         }
                         .port();
                     }
+                    if let Some(ref port_file) = config_clone.port_file
+                        && let Err(err) =
+                            write_port_file(port_file, config_clone.port, &config_clone.key)
+                    {
+                        eprintln!("LOGSRV: Failed to write port file {port_file:?}: {err:?}");
+                        return;
+                    }
                     listener
                 };
                 tx_started.send(1).expect("Failed to send started signal");
-                if let Err(err) = server_thread(config_clone.clone(), listener, tx, stop) {
+                if let Err(err) =
+                    server_thread(config_clone.clone(), listener, tx, stats_clone, stop)
+                {
                     eprintln!("LOGSRV: server_thread: {err:?}");
                 }
                 //println!("SERVER FIN {}", process::id());
@@ -472,6 +604,7 @@ impl LoggingServer {
             .map_err(|e| Error::other(format!("Failed to start logging server: {e}")))?;
         Ok(Self {
             config,
+            stats,
             thr: Some(thr),
             debug: 0,
         })
@@ -480,10 +613,9 @@ impl LoggingServer {
     pub fn shutdown(&mut self) -> Result<(), LoggingError> {
         if let Some(thr) = self.thr.take() {
             // Send SHUTDOWN (255) to server socket
-            let stop_cmd = [255, 255, 255, 255];
             loop {
                 let mut stream = TcpStream::connect(self.config.read().get_address())?;
-                stream.write_all(&stop_cmd)?;
+                stream.write_all(&STOP_SERVER)?;
                 stream.flush()?;
                 //stream.shutdown(Shutdown::Both)?;
                 thread::sleep(Duration::from_millis(10));
@@ -502,6 +634,13 @@ impl LoggingServer {
         }
     }
 
+    /// Statistics per client host, ordered by address.
+    pub fn client_stats(&self) -> Vec<ClientStats> {
+        let mut stats: Vec<_> = self.stats.read().values().cloned().collect();
+        stats.sort_by_key(|client| client.address);
+        stats
+    }
+
     pub fn enable(&self) {
         self.config.write().enabled = true;
     }
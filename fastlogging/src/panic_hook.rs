@@ -0,0 +1,174 @@
+use std::{
+    backtrace::Backtrace,
+    panic::{self, PanicHookInfo},
+    sync::{Arc, Once, Weak},
+    thread,
+    time::Duration,
+};
+
+use flume::{Sender, bounded};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    EXCEPTION, Logging, config::LoggingInstance, def::LoggingTypeEnum, logging::sync_all_types,
+    recorder::panic_dump, root::ROOT_LOGGER,
+};
+
+/// Maximum time in seconds to wait for the writers after a panic has been logged.
+pub const PANIC_SYNC_TIMEOUT: f64 = 2.0;
+
+#[derive(Debug)]
+enum PanicTarget {
+    Logging {
+        instance: Weak<RwLock<LoggingInstance>>,
+        server_tx: Sender<LoggingTypeEnum>,
+    },
+    Root,
+}
+
+static PANIC_TARGET: Lazy<Mutex<Option<PanicTarget>>> = Lazy::new(|| Mutex::new(None));
+static PANIC_HOOK: Once = Once::new();
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "Box<dyn Any>"
+    };
+    let current = thread::current();
+    let tname = current.name().unwrap_or("<unnamed>");
+    let location = match info.location() {
+        Some(location) => format!(" at {location}"),
+        None => String::new(),
+    };
+    format!(
+        "thread '{tname}' ({:?}) panicked{location}:\n{payload}\nstack backtrace:\n{}",
+        current.id(),
+        Backtrace::force_capture()
+    )
+}
+
+fn log_panic(info: &PanicHookInfo) {
+    let Some(target) = PANIC_TARGET.try_lock() else {
+        return;
+    };
+    match target.as_ref() {
+        Some(PanicTarget::Logging {
+            instance,
+            server_tx,
+        }) => {
+            // Use the current settings. The panic might have happened while the instance
+            // was locked.
+            let Some((level, domain)) = instance.upgrade().and_then(|instance| {
+                instance
+                    .try_read()
+                    .map(|instance| (instance.level, instance.domain.clone()))
+            }) else {
+                return;
+            };
+            if level > EXCEPTION {
+                return;
+            }
+            let message = panic_message(info);
+            // Own reply channel, so that concurrent `Logging::sync` calls are not disturbed.
+            let (reply_tx, reply_rx) = bounded(1);
+            if server_tx
+                .send(LoggingTypeEnum::Message((EXCEPTION, domain, message)))
+                .is_ok()
+                && server_tx
+                    .send(LoggingTypeEnum::SyncReply((
+                        sync_all_types(),
+                        PANIC_SYNC_TIMEOUT,
+                        reply_tx,
+                    )))
+                    .is_ok()
+            {
+                let _ = reply_rx.recv_timeout(Duration::from_secs_f64(PANIC_SYNC_TIMEOUT));
+            }
+        }
+        Some(PanicTarget::Root) => {
+            // The panic might have happened while the root logger was locked.
+            if let Some(logging) = ROOT_LOGGER.try_read() {
+                let _ = logging.exception(panic_message(info));
+                let _ = logging.sync_all(PANIC_SYNC_TIMEOUT);
+            }
+        }
+        None => {}
+    }
+}
+
+/// Install the panic hook once. It logs the panic to the panic target, dumps the recorder
+/// writers and calls the previous panic hook.
+pub(crate) fn install_hook() {
+    PANIC_HOOK.call_once(|| {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            log_panic(info);
+            panic_dump();
+            prev_hook(info);
+        }));
+    });
+}
+
+fn set_panic_target(target: PanicTarget) {
+    *PANIC_TARGET.lock() = Some(target);
+    install_hook();
+}
+
+/// Log panics with message, location, thread and backtrace at EXCEPTION level to `logging`
+/// and wait until the writers are synced. The previous panic hook is still called afterwards.
+/// Calling it again replaces the logging instance. Level and domain are read when a panic
+/// occurs, so later changes are respected.
+pub fn install_panic_hook(logging: &Logging) {
+    set_panic_target(PanicTarget::Logging {
+        instance: Arc::downgrade(&logging.instance),
+        server_tx: logging.server_tx.clone(),
+    });
+}
+
+/// Log panics to the root logger. See [`install_panic_hook`].
+pub(crate) fn install_root_panic_hook() {
+    set_panic_target(PanicTarget::Root);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{CaptureWriterConfig, EXCEPTION, Logging, NOLOG, NOTSET, install_panic_hook};
+
+    #[test]
+    fn panic_hook() {
+        let config = CaptureWriterConfig::new(NOTSET);
+        let handle = config.handle();
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("panic")
+            .writer(config)
+            .build()
+            .unwrap();
+        install_panic_hook(&logging);
+        let result = thread::Builder::new()
+            .name("worker".to_string())
+            .spawn(|| panic!("Worker failed"))
+            .unwrap()
+            .join();
+        assert!(result.is_err());
+        handle.wait_for(1, 5.0).unwrap();
+        handle.assert_logged(
+            Some(EXCEPTION),
+            Some("panic"),
+            r"^thread 'worker' \(ThreadId\(\d+\)\) panicked at .*panic_hook\.rs:\d+:\d+:\nWorker failed\nstack backtrace:\n",
+        );
+        // Level changes after installing the hook are respected, like by `set_root_level`.
+        logging.instance.write().level = NOLOG;
+        let result = thread::spawn(|| panic!("Ignored panic")).join();
+        assert!(result.is_err());
+        logging.sync_all(5.0).unwrap();
+        handle.assert_not_logged(None, None, "Ignored panic");
+        logging.shutdown(false).unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    collections::HashMap,
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::SystemTime,
+};
+
+use flume::{Receiver, Sender};
+use parking_lot::RwLock;
+
+use crate::{
+    FileWriter, FileWriterConfig, LoggingError, RotationTimezone, def::check_time_format,
+    file::FileTypeEnum,
+};
+
+/// Default maximum number of open log files of a writer with a path template.
+pub(crate) const DEFAULT_MAX_OPEN_FILES: usize = 16;
+/// Date format of `{date}`.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Timeout for syncing a single log file of a path template.
+const SYNC_TIMEOUT: f64 = 5.0;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Domain,
+    Date(String),
+    Pname,
+    Pid,
+}
+
+/// Log file path with the placeholders `{domain}`, `{date}`, `{date:<strftime format>}`,
+/// `{pname}` and `{pid}`, which are expanded per log message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PathTemplate {
+    parts: Vec<Part>,
+}
+
+impl PathTemplate {
+    /// Parse `path`. Returns `None` if `path` contains no placeholders. Other text in
+    /// braces is kept as it is.
+    pub(crate) fn parse(path: &Path) -> Result<Option<Self>, LoggingError> {
+        let Some(mut rest) = path.to_str() else {
+            return Ok(None);
+        };
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            let part = match &rest[1..end] {
+                "domain" => Part::Domain,
+                "date" => Part::Date(DEFAULT_DATE_FORMAT.to_string()),
+                "pname" => Part::Pname,
+                "pid" => Part::Pid,
+                key => match key.strip_prefix("date:") {
+                    Some(format) => {
+                        check_time_format(&Some(format.to_string()))?;
+                        Part::Date(format.to_string())
+                    }
+                    None => {
+                        text.push('{');
+                        rest = &rest[1..];
+                        continue;
+                    }
+                },
+            };
+            if !text.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut text)));
+            }
+            parts.push(part);
+            rest = &rest[end + 1..];
+        }
+        if parts.is_empty() {
+            return Ok(None);
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Some(Self { parts }))
+    }
+
+    /// Path of the log file for messages of `domain` at `time`.
+    pub(crate) fn expand(
+        &self,
+        domain: &str,
+        pname: &str,
+        timezone: RotationTimezone,
+        time: SystemTime,
+    ) -> PathBuf {
+        let mut path = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => path.push_str(text),
+                Part::Domain => path.push_str(&path_component(domain)),
+                Part::Date(format) => path.push_str(&timezone.format(time, format)),
+                Part::Pname => path.push_str(&path_component(pname)),
+                Part::Pid => path.push_str(&process::id().to_string()),
+            }
+        }
+        PathBuf::from(path)
+    }
+}
+
+/// Domains and process names are inserted as a single path component.
+fn path_component(name: &str) -> String {
+    let name = name.replace(['/', '\\', '\0'], "_");
+    match name.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => name,
+    }
+}
+
+fn open_file_writer(
+    config: &Arc<RwLock<FileWriterConfig>>,
+    path: &Path,
+    stop: &Arc<AtomicBool>,
+) -> Result<FileWriter, LoggingError> {
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        create_dir_all(dir)?;
+    }
+    FileWriter::start(config.clone(), path.to_path_buf(), None, stop.clone())
+}
+
+/// Apply `action` to all open file writers. A writer which fails is closed and removed, so
+/// that it is opened again with the next log message for its file, while the others go on.
+fn for_each_writer(
+    writers: &mut HashMap<PathBuf, (FileWriter, u64)>,
+    name: &str,
+    action: impl Fn(&FileWriter) -> Result<(), LoggingError>,
+) {
+    let failed: Vec<PathBuf> = writers
+        .iter()
+        .filter_map(|(path, (writer, _))| match action(writer) {
+            Ok(()) => None,
+            Err(err) => {
+                eprintln!("Failed to {name} log file {path:?}: {err:?}");
+                Some(path.clone())
+            }
+        })
+        .collect();
+    for path in failed {
+        if let Some((mut writer, _)) = writers.remove(&path) {
+            let _ = writer.shutdown();
+        }
+    }
+}
+
+/// Write log messages into the log files expanded from `template`. Every log file has its
+/// own file writer sharing `config`. At most `max_open_files` are kept open, the least
+/// recently used one is closed first.
+pub(crate) fn template_writer_thread_worker(
+    config: Arc<RwLock<FileWriterConfig>>,
+    template: PathTemplate,
+    rx: Receiver<FileTypeEnum>,
+    stop: Arc<AtomicBool>,
+    sync_tx: Sender<u8>,
+) -> Result<(), LoggingError> {
+    let pname = std::env::current_exe()
+        .ok()
+        .and_then(|pb| pb.file_name().map(|s| s.to_os_string()))
+        .and_then(|s| s.into_string().ok())
+        .unwrap_or_default();
+    // Open file writers and their last use
+    let mut writers: HashMap<PathBuf, (FileWriter, u64)> = HashMap::new();
+    let mut uses = 0;
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        let Ok(message) = rx.recv() else {
+            break;
+        };
+        match message {
+            message @ (FileTypeEnum::Message(_) | FileTypeEnum::Record(_)) => {
+                let domain = match message {
+                    FileTypeEnum::Message((_, ref domain, _)) => domain,
+                    FileTypeEnum::Record(ref record) => &record.domain,
+                    _ => unreachable!(),
+                };
+                let (timezone, max_open_files) = {
+                    let c = config.read();
+                    (
+                        c.timezone,
+                        c.max_open_files.unwrap_or(DEFAULT_MAX_OPEN_FILES),
+                    )
+                };
+                let path = template.expand(domain, &pname, timezone, SystemTime::now());
+                uses += 1;
+                if !writers.contains_key(&path) {
+                    while writers.len() >= max_open_files {
+                        let lru = writers
+                            .iter()
+                            .min_by_key(|(_, (_, last_use))| *last_use)
+                            .map(|(path, _)| path.clone())
+                            .unwrap();
+                        let (mut writer, _) = writers.remove(&lru).unwrap();
+                        if let Err(err) = writer.shutdown() {
+                            eprintln!("Failed to close log file {lru:?}: {err:?}");
+                        }
+                    }
+                    match open_file_writer(&config, &path, &stop) {
+                        Ok(writer) => {
+                            writers.insert(path.clone(), (writer, uses));
+                        }
+                        Err(err) => {
+                            eprintln!("Failed to open log file {path:?}: {err:?}");
+                            continue;
+                        }
+                    }
+                }
+                let (writer, last_use) = writers.get_mut(&path).unwrap();
+                *last_use = uses;
+                if let Err(err) = writer.forward(message) {
+                    eprintln!("Failed to write log file {path:?}: {err:?}");
+                    if let Some((mut writer, _)) = writers.remove(&path) {
+                        let _ = writer.shutdown();
+                    }
+                }
+            }
+            FileTypeEnum::Rotate => {
+                for_each_writer(&mut writers, "rotate", |writer| writer.rotate());
+            }
+            FileTypeEnum::Reopen => {
+                for_each_writer(&mut writers, "reopen", |writer| writer.reopen());
+            }
+            FileTypeEnum::Sync => {
+                for_each_writer(&mut writers, "sync", |writer| writer.sync(SYNC_TIMEOUT));
+                sync_tx.send(1)?;
+            }
+            FileTypeEnum::Fsync => {
+                for_each_writer(&mut writers, "fsync", |writer| writer.fsync(SYNC_TIMEOUT));
+                sync_tx.send(1)?;
+            }
+            FileTypeEnum::Stop => {
+                break;
+            }
+        }
+    }
+    for (path, (mut writer, _)) in writers.drain() {
+        if let Err(err) = writer.shutdown() {
+            eprintln!("Failed to close log file {path:?}: {err:?}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        process,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use tempfile::TempDir;
+
+    use super::PathTemplate;
+    use crate::{DEBUG, FileWriterConfig, Logger, Logging, RotationTimezone};
+
+    #[test]
+    fn expand() {
+        let time = UNIX_EPOCH + Duration::from_secs(86400);
+        let template = PathTemplate::parse(Path::new("logs/{domain}/{date:%Y%m%d}-{pid}.log"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            template.expand("tenant", "app", RotationTimezone::Utc, time),
+            PathBuf::from(format!("logs/tenant/19700102-{}.log", process::id()))
+        );
+        // Domains can not escape from their directory.
+        assert_eq!(
+            template.expand("..", "app", RotationTimezone::Utc, time),
+            PathBuf::from(format!("logs/_/19700102-{}.log", process::id()))
+        );
+        let template = PathTemplate::parse(Path::new("{pname}.{date}.log"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            template.expand("a/b", "app", RotationTimezone::Utc, time),
+            PathBuf::from("app.1970-01-02.log")
+        );
+        // Paths without placeholders and unknown placeholders are no templates.
+        assert_eq!(PathTemplate::parse(Path::new("app.log")).unwrap(), None);
+        assert_eq!(PathTemplate::parse(Path::new("{app}.log")).unwrap(), None);
+        assert!(PathTemplate::parse(Path::new("{date:%Q}.log")).is_err());
+    }
+
+    #[test]
+    fn template_writer() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("root")
+            .writer(
+                FileWriterConfig::builder(temp_dir.path().join("{domain}/app.log"))
+                    .max_open_files(2)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let domains = ["tenant1", "tenant2", "tenant3"];
+        let loggers: Vec<_> = domains
+            .into_iter()
+            .map(|domain| {
+                let mut logger = Logger::new(DEBUG, domain);
+                logging.add_logger(&mut logger);
+                logger
+            })
+            .collect();
+        // Log file of the least recently used domain is closed and opened again.
+        for _ in 0..2 {
+            for (domain, logger) in domains.iter().zip(&loggers) {
+                logger.info(format!("Message for {domain}")).unwrap();
+            }
+        }
+        logging.sync_all(5.0).unwrap();
+        logging.shutdown(false).unwrap();
+        for domain in domains {
+            let log_text =
+                std::fs::read_to_string(temp_dir.path().join(domain).join("app.log")).unwrap();
+            assert_eq!(log_text.lines().count(), 2);
+            assert!(
+                log_text
+                    .lines()
+                    .all(|line| line.ends_with(&format!("Message for {domain}")))
+            );
+        }
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn failing_file() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("root")
+            .writer(
+                FileWriterConfig::builder(temp_dir.path().join("{domain}/app.log"))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut good = Logger::new(DEBUG, "good");
+        let mut bad = Logger::new(DEBUG, "bad");
+        logging.add_logger(&mut good);
+        logging.add_logger(&mut bad);
+        good.info("First good message").unwrap();
+        bad.info("First bad message").unwrap();
+        logging.sync_all(5.0).unwrap();
+        // Reopening fails only for the log file whose directory was removed.
+        std::fs::remove_dir_all(temp_dir.path().join("bad")).unwrap();
+        logging.reopen_files().unwrap();
+        logging.sync_all(5.0).unwrap();
+        good.info("Second good message").unwrap();
+        bad.info("Second bad message").unwrap();
+        logging.sync_all(5.0).unwrap();
+        logging.shutdown(false).unwrap();
+        let read = |domain: &str| {
+            std::fs::read_to_string(temp_dir.path().join(domain).join("app.log")).unwrap()
+        };
+        assert_eq!(read("good").lines().count(), 2);
+        let log_text = read("bad");
+        assert_eq!(log_text.lines().count(), 1);
+        assert!(log_text.ends_with("Second bad message\n"));
+        temp_dir.close().unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+use std::io;
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use termcolor::WriteColor;
+
+use crate::{
+    CRITICAL, ColorStyle, ColorTheme, DEBUG, ERROR, EXCEPTION, INFO, LevelSyms, SUCCESS, TRACE,
+    WARNING, level2string, reader::LogRecord,
+};
+
+/// Reference for relative timestamps. Forced when the first `Logging` instance is created.
+pub(crate) static PROCESS_START: Lazy<DateTime<Local>> = Lazy::new(Local::now);
+
+/// Width of relative timestamps like "+1234.567s".
+const RELATIVE_TIME_WIDTH: usize = 10;
+/// Do not wrap lines into columns narrower than this.
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Multi-line console layout for local development:
+///
+/// ```text
+///    +0.012s INFO    app.db           Connected to database
+///    +1.345s ERROR   app.http         Request failed with a very long message which is
+///                                     wrapped at the terminal width
+///                                       thread:    main[12345]
+///                                       caused by: connection refused
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrettyLayout {
+    /// Width of the domain column. Longer domains are shortened.
+    pub domain_width: usize,
+    /// Time since process start instead of timestamps
+    pub relative_time: bool,
+    /// Show hostname, process, thread and error causes of log messages below them
+    pub fields: bool,
+    /// Wrap long lines
+    pub wrap: bool,
+    /// Wrap width. Default is the width of the terminal or `COLUMNS`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+}
+
+impl Default for PrettyLayout {
+    fn default() -> Self {
+        Self {
+            domain_width: 16,
+            relative_time: true,
+            fields: true,
+            wrap: true,
+            width: None,
+        }
+    }
+}
+
+/// Set `style`, or reset to the default style. Without theme nothing is written.
+fn set_style<W: WriteColor>(
+    out: &mut W,
+    theme: Option<&ColorTheme>,
+    style: Option<&ColorStyle>,
+) -> io::Result<()> {
+    match (theme, style) {
+        (None, _) => Ok(()),
+        (Some(_), Some(style)) => out.set_color(&style.spec()),
+        (Some(_), None) => out.reset(),
+    }
+}
+
+/// Split `line` into parts of at most `width` characters, at whitespace if possible.
+fn wrap(line: &str, width: Option<usize>) -> Vec<&str> {
+    let Some(width) = width else {
+        return vec![line];
+    };
+    let mut parts = Vec::new();
+    let mut rest = line;
+    while let Some((end, next)) = rest.char_indices().nth(width) {
+        let head = &rest[..end];
+        if next.is_whitespace() {
+            parts.push(head.trim_end());
+            rest = rest[end..].trim_start();
+        } else if let Some(pos) = head.rfind(char::is_whitespace).filter(|pos| *pos > 0) {
+            parts.push(head[..pos].trim_end());
+            rest = rest[pos..].trim_start();
+        } else {
+            parts.push(head);
+            rest = &rest[end..];
+        }
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(rest);
+    }
+    parts
+}
+
+/// Write lines of `text`, wrapped into `width` columns. All lines except the first are
+/// indented by `column` spaces.
+fn write_lines<W: WriteColor>(
+    out: &mut W,
+    text: &str,
+    column: usize,
+    width: Option<usize>,
+) -> io::Result<()> {
+    let width = width.map(|width| width.saturating_sub(column).max(MIN_WRAP_WIDTH));
+    let mut first = true;
+    for line in text.lines() {
+        for part in wrap(line, width) {
+            if !first {
+                write!(out, "\n{:column$}", "")?;
+            }
+            out.write_all(part.as_bytes())?;
+            first = false;
+        }
+    }
+    Ok(())
+}
+
+/// Shorten `domain` to `width` characters.
+fn shorten(domain: &str, width: usize) -> String {
+    if width == 0 || domain.chars().count() <= width {
+        domain.to_string()
+    } else {
+        let mut domain: String = domain.chars().take(width - 1).collect();
+        domain.push('…');
+        domain
+    }
+}
+
+/// Hostname, process, thread and error chain of `record`.
+fn fields(record: &LogRecord) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    if let Some(ref hostname) = record.hostname {
+        fields.push(("host", hostname.clone()));
+    }
+    if record.pid > 0 {
+        fields.push(("process", format!("{}[{}]", record.pname, record.pid)));
+    } else if !record.pname.is_empty() {
+        fields.push(("process", record.pname.clone()));
+    }
+    match (&record.tname, record.tid) {
+        (Some(tname), 0) => fields.push(("thread", tname.clone())),
+        (tname, tid) if tid > 0 => fields.push((
+            "thread",
+            format!("{}[{tid}]", tname.as_deref().unwrap_or_default()),
+        )),
+        _ => {}
+    }
+    if let Some(ref error) = record.error {
+        for cause in error.causes.iter() {
+            fields.push(("caused by", cause.clone()));
+        }
+        if let Some(ref backtrace) = error.backtrace {
+            fields.push(("backtrace", backtrace.clone()));
+        }
+    }
+    fields
+}
+
+/// Write `record` in pretty layout. Colors are only written if `theme` is set.
+pub(crate) fn write_pretty<W: WriteColor>(
+    out: &mut W,
+    layout: &PrettyLayout,
+    record: &LogRecord,
+    level2sym: &LevelSyms,
+    time_format: &str,
+    theme: Option<&ColorTheme>,
+    width: Option<usize>,
+) -> io::Result<()> {
+    let level_style = theme.map(|theme| theme.style(record.level));
+    let timestamp_style = theme.and_then(|theme| theme.timestamp.as_ref());
+    let time = if layout.relative_time {
+        let millis = (record.date - *PROCESS_START).num_milliseconds().max(0);
+        format!(
+            "{:>RELATIVE_TIME_WIDTH$}",
+            format!("+{}.{:03}s", millis / 1000, millis % 1000)
+        )
+    } else {
+        record.date.format(time_format).to_string()
+    };
+    let level_width = [
+        TRACE, DEBUG, INFO, SUCCESS, WARNING, ERROR, CRITICAL, EXCEPTION,
+    ]
+    .into_iter()
+    .map(|level| level2string(level2sym, level).len())
+    .max()
+    .unwrap_or_default();
+    let domain = shorten(&record.domain, layout.domain_width);
+    let domain_width = layout.domain_width;
+    set_style(out, theme, timestamp_style)?;
+    out.write_all(time.as_bytes())?;
+    set_style(out, theme, level_style)?;
+    write!(
+        out,
+        " {:level_width$} ",
+        level2string(level2sym, record.level)
+    )?;
+    set_style(out, theme, theme.and_then(|theme| theme.domain.as_ref()))?;
+    write!(out, "{domain:domain_width$}")?;
+    set_style(out, theme, None)?;
+    out.write_all(b" ")?;
+    let column = time.chars().count() + level_width + domain.chars().count().max(domain_width) + 3;
+    set_style(out, theme, level_style)?;
+    write_lines(out, &record.message, column, width)?;
+    if layout.fields {
+        let fields = fields(record);
+        let key_width = fields
+            .iter()
+            .map(|(key, _)| key.len() + 1)
+            .max()
+            .unwrap_or_default();
+        set_style(out, theme, timestamp_style)?;
+        for (key, value) in fields {
+            write!(out, "\n{:column$}  {:key_width$} ", "", format!("{key}:"))?;
+            // Backtrace frames are more readable when not wrapped.
+            let width = if key == "backtrace" { None } else { width };
+            write_lines(out, &value, column + key_width + 3, width)?;
+        }
+    }
+    set_style(out, theme, None)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use termcolor::Buffer;
+
+    use super::{PROCESS_START, PrettyLayout, wrap, write_pretty};
+    use crate::{ERROR, ErrorChain, INFO, LevelSyms, reader::LogRecord};
+
+    fn render(layout: &PrettyLayout, record: &LogRecord, width: Option<usize>) -> String {
+        let mut buffer = Buffer::no_color();
+        write_pretty(
+            &mut buffer,
+            layout,
+            record,
+            &LevelSyms::Str,
+            "%H:%M:%S",
+            None,
+            width,
+        )
+        .unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn pretty() {
+        assert_eq!(wrap("aaa bbb ccc", Some(7)), vec!["aaa bbb", "ccc"]);
+        assert_eq!(
+            wrap("aaaaaaaaaa bb", Some(4)),
+            vec!["aaaa", "aaaa", "aa", "bb"]
+        );
+        assert_eq!(wrap("", Some(4)), vec![""]);
+        let mut record = LogRecord::from_message(INFO, "app".to_string(), "Started".to_string());
+        record.date = *PROCESS_START + Duration::milliseconds(1234);
+        let layout = PrettyLayout {
+            domain_width: 8,
+            fields: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&layout, &record, None),
+            "   +1.234s INFO      app      Started\n"
+        );
+        record.level = ERROR;
+        record.domain = "app.database".to_string();
+        record.message = "Query failed after waiting for the lock\nsecond line".to_string();
+        record.pname = "app".to_string();
+        record.pid = 42;
+        record.tname = Some("main".to_string());
+        record.error = Some(ErrorChain {
+            causes: vec!["connection refused by remote host".to_string()],
+            backtrace: None,
+        });
+        let layout = PrettyLayout {
+            domain_width: 8,
+            relative_time: false,
+            width: Some(50),
+            ..Default::default()
+        };
+        let time = record.date.format("%H:%M:%S");
+        assert_eq!(
+            render(&layout, &record, Some(50)),
+            format!(
+                "{time} ERROR     app.dat… Query failed after\n\
+                 \x20                           waiting for the lock\n\
+                 \x20                           second line\n\
+                 \x20                             process:   app[42]\n\
+                 \x20                             thread:    main\n\
+                 \x20                             caused by: connection refused\n\
+                 \x20                                        by remote host\n"
+            )
+        );
+    }
+}
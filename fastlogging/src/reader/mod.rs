@@ -0,0 +1,255 @@
+//! Read the log records of log files written by a [`FileWriter`](crate::FileWriter).
+//! Compressed backups are decompressed by their extension.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Local};
+use flate2::read::GzDecoder;
+use lzma_rust2::XzReader;
+
+use crate::{
+    CompressionMethodEnum, EncryptionMethod, ErrorChain, LevelSyms, LoggingError,
+    MessageStructEnum,
+    binary::{BinaryDecoder, BinaryEncoder},
+    file_encryption::{FRAME_HEADER_SIZE, FileDecipher, file_key},
+    logging::{Origin, Record, build_message},
+};
+
+mod parse;
+mod query;
+
+pub use query::{RecordReader, RecordReaderBuilder};
+
+/// Frames bigger than this are treated as corrupt.
+const FRAME_SIZE_MAX: usize = 1 << 28;
+
+/// Log record parsed from a log file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub level: u8,
+    pub domain: String,
+    pub message: String,
+    pub hostname: Option<String>,
+    pub pname: String,
+    pub pid: u32,
+    pub tname: Option<String>,
+    pub tid: u32,
+    pub date: DateTime<Local>,
+    pub error: Option<ErrorChain>,
+}
+
+impl LogRecord {
+    /// Record for an already formatted log message.
+    pub(crate) fn from_message(level: u8, domain: String, message: String) -> Self {
+        Self {
+            level,
+            domain,
+            message,
+            hostname: None,
+            pname: String::new(),
+            pid: 0,
+            tname: None,
+            tid: 0,
+            date: Local::now(),
+            error: None,
+        }
+    }
+
+    /// Format record like a writer with `structured`, `level2sym` and `time_format`.
+    pub fn format(
+        &self,
+        structured: &MessageStructEnum,
+        level2sym: &LevelSyms,
+        time_format: &Option<String>,
+    ) -> String {
+        let origin = Origin {
+            hostname: self.hostname.as_deref(),
+            pname: &self.pname,
+            pid: self.pid,
+        };
+        let record = Record {
+            level: self.level,
+            domain: self.domain.clone(),
+            message: self.message.clone(),
+            tname: self.tname.clone(),
+            tid: self.tid,
+            date: self.date,
+            error: self.error.clone().map(Box::new),
+        };
+        build_message(&origin, &record, structured, level2sym, time_format)
+    }
+}
+
+/// Open log file at `path` and decompress it if it is a compressed backup.
+fn open_decompressed(path: &Path) -> Result<Box<dyn Read + Send>, LoggingError> {
+    let file = File::open(path)?;
+    let name = path.to_string_lossy();
+    Ok(
+        if name.ends_with(CompressionMethodEnum::Deflate.extension()) {
+            Box::new(GzDecoder::new(file))
+        } else if name.ends_with(CompressionMethodEnum::Zstd.extension()) {
+            Box::new(zstd::Decoder::new(file)?)
+        } else if name.ends_with(CompressionMethodEnum::Lzma.extension()) {
+            Box::new(XzReader::new(file, true))
+        } else {
+            Box::new(file)
+        },
+    )
+}
+
+/// Iterator over the log records of a log file. Encrypted log files are decrypted.
+pub struct LogReader {
+    reader: Box<dyn BufRead + Send>,
+    decipher: Option<FileDecipher>,
+}
+
+impl LogReader {
+    /// Read plain log records from `reader`, or encrypted records if `encryption` is
+    /// an AES key.
+    pub fn new<R: Read + Send + 'static>(
+        reader: R,
+        encryption: &EncryptionMethod,
+    ) -> Result<Self, LoggingError> {
+        Ok(Self {
+            reader: Box::new(BufReader::new(reader)),
+            decipher: file_key(encryption)?.map(FileDecipher::new),
+        })
+    }
+
+    /// Open log file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoggingError> {
+        Self::new(open_decompressed(path.as_ref())?, &EncryptionMethod::NONE)
+    }
+
+    /// Open log file at `path`, which is encrypted with `encryption`.
+    pub fn open_encrypted<P: AsRef<Path>>(
+        path: P,
+        encryption: &EncryptionMethod,
+    ) -> Result<Self, LoggingError> {
+        Self::new(open_decompressed(path.as_ref())?, encryption)
+    }
+
+    fn next_line(&mut self) -> Result<Option<String>, LoggingError> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    fn next_frame(&mut self) -> Result<Option<String>, LoggingError> {
+        loop {
+            let mut header = [0u8; FRAME_HEADER_SIZE];
+            if self.reader.fill_buf()?.is_empty() {
+                return Ok(None);
+            }
+            self.reader
+                .read_exact(&mut header)
+                .map_err(|e| match e.kind() {
+                    ErrorKind::UnexpectedEof => {
+                        LoggingError::InvalidFile("Truncated frame header".to_string())
+                    }
+                    _ => e.into(),
+                })?;
+            let size = u32::from_le_bytes(header[1..].try_into().unwrap()) as usize;
+            if size > FRAME_SIZE_MAX {
+                return Err(LoggingError::InvalidFile(format!(
+                    "Frame size {size} too big"
+                )));
+            }
+            let mut payload = vec![0u8; size];
+            self.reader
+                .read_exact(&mut payload)
+                .map_err(|e| match e.kind() {
+                    ErrorKind::UnexpectedEof => {
+                        LoggingError::InvalidFile("Truncated frame".to_string())
+                    }
+                    _ => e.into(),
+                })?;
+            if let Some(record) = self
+                .decipher
+                .as_mut()
+                .unwrap()
+                .open(header[0], &mut payload)?
+            {
+                return String::from_utf8(record)
+                    .map(Some)
+                    .map_err(|e| LoggingError::Utf8Error(e.to_string()));
+            }
+        }
+    }
+}
+
+impl Iterator for LogReader {
+    type Item = Result<String, LoggingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.decipher {
+            Some(_) => self.next_frame(),
+            None => self.next_line(),
+        };
+        record.transpose()
+    }
+}
+
+/// Iterator over the records of a log file written with
+/// [`MessageStructEnum::Binary`](crate::MessageStructEnum::Binary).
+pub struct BinaryReader {
+    reader: Box<dyn BufRead + Send>,
+    decoder: BinaryDecoder,
+}
+
+impl BinaryReader {
+    pub fn new<R: Read + Send + 'static>(reader: R) -> Self {
+        Self {
+            reader: Box::new(BufReader::new(reader)),
+            decoder: BinaryDecoder::new(),
+        }
+    }
+
+    /// Open binary log file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoggingError> {
+        Ok(Self::new(open_decompressed(path.as_ref())?))
+    }
+}
+
+impl Iterator for BinaryReader {
+    type Item = Result<LogRecord, LoggingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decoder.decode(&mut self.reader).transpose()
+    }
+}
+
+/// Writes records in the binary log file format, e.g. to convert text log files.
+pub struct BinaryWriter<W: Write> {
+    writer: W,
+    encoder: BinaryEncoder,
+}
+
+impl<W: Write> BinaryWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            encoder: BinaryEncoder::new(),
+        }
+    }
+
+    /// Append `record`. The first record writes the file header.
+    pub fn write(&mut self, record: &LogRecord) -> Result<(), LoggingError> {
+        Ok(self.writer.write_all(self.encoder.encode(record))?)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, LoggingError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
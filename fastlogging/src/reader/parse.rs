@@ -0,0 +1,277 @@
+//! Parsers of the string, JSON, XML and logfmt log message layouts.
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde_json::Value;
+
+use super::LogRecord;
+use crate::{ErrorChain, LoggingError, str2level};
+
+const CAUSED_BY: &str = "\n  caused by: ";
+const BACKTRACE: &str = "\n  backtrace:";
+
+/// Parse `text` formatted with strftime `time_format`.
+pub(crate) fn parse_date(text: &str, time_format: &str) -> Option<DateTime<Local>> {
+    if let Ok(date) = DateTime::parse_from_str(text, time_format) {
+        return Some(date.with_timezone(&Local));
+    }
+    let date = NaiveDateTime::parse_from_str(text, time_format).ok()?;
+    Local.from_local_datetime(&date).earliest()
+}
+
+/// Number of spaces in dates formatted with `time_format`.
+pub(crate) fn date_spaces(time_format: &str) -> usize {
+    Local::now()
+        .format(time_format)
+        .to_string()
+        .matches(' ')
+        .count()
+}
+
+fn invalid(format: &str, err: impl std::fmt::Display) -> LoggingError {
+    LoggingError::InvalidFile(format!("Invalid {format} log record: {err}"))
+}
+
+/// Split `name[id]` into name and id.
+fn name_id(text: &str) -> (Option<String>, u32) {
+    if let Some(text) = text.strip_suffix(']')
+        && let Some((name, id)) = text.rsplit_once('[')
+        && let Ok(id) = id.parse()
+    {
+        return ((!name.is_empty()).then(|| name.to_string()), id);
+    }
+    ((!text.is_empty()).then(|| text.to_string()), 0)
+}
+
+/// Parse process and thread part `pname[pid]>tname[tid]`.
+fn parse_process(record: &mut LogRecord, text: &str) {
+    let (process, thread) = match text.split_once('>') {
+        Some((process, thread)) => (process, Some(thread)),
+        None => (text, None),
+    };
+    let (pname, pid) = name_id(process);
+    record.pname = pname.unwrap_or_default();
+    record.pid = pid;
+    if let Some(thread) = thread {
+        (record.tname, record.tid) = name_id(thread);
+    }
+}
+
+/// Parse first line of a log message in the default string layout
+/// `{date} {hostname} {pname}[{pid}]>{tname}[{tid}] {domain}: {level} {message}`.
+/// Returns `None` if `line` is a continuation line of the previous log message.
+pub(crate) fn parse_string(line: &str, time_format: &str, spaces: usize) -> Option<LogRecord> {
+    let date_end = line.match_indices(' ').nth(spaces)?.0;
+    let (mut date, rest) = (&line[..date_end], &line[date_end + 1..]);
+    // Without hostname and process the thread directly follows the date.
+    let mut thread = None;
+    if let Some(pos) = date.find('>') {
+        thread = Some(&date[pos..]);
+        date = &date[..pos];
+    }
+    let date = parse_date(date, time_format)?;
+    let (head, tail) = rest.split_once(": ")?;
+    let mut tokens: Vec<&str> = head.split(' ').collect();
+    let domain = tokens.pop()?;
+    let (level, message) = tail.split_once(' ').unwrap_or((tail, ""));
+    let level = str2level(level)?;
+    let mut record = LogRecord::from_message(level, domain.to_string(), message.to_string());
+    record.date = date;
+    match (tokens.as_slice(), thread) {
+        ([], Some(thread)) => parse_process(&mut record, thread),
+        ([], None) => {}
+        ([process], None) if process.contains(['[', '>']) => parse_process(&mut record, process),
+        ([hostname], None) => record.hostname = Some(hostname.to_string()),
+        ([hostname, process], None) => {
+            record.hostname = Some(hostname.to_string());
+            parse_process(&mut record, process);
+        }
+        _ => return None,
+    }
+    Some(record)
+}
+
+/// Move causes and backtrace of the message of a complete string record into its error.
+pub(crate) fn finish_string(record: &mut LogRecord) {
+    let Some(pos) = [
+        record.message.find(CAUSED_BY),
+        record.message.find(BACKTRACE),
+    ]
+    .into_iter()
+    .flatten()
+    .min() else {
+        return;
+    };
+    let mut error = ErrorChain::default();
+    let mut rest = &record.message[pos..];
+    while let Some(cause) = rest.strip_prefix(CAUSED_BY) {
+        let end = cause.find('\n').unwrap_or(cause.len());
+        error.causes.push(cause[..end].to_string());
+        rest = &cause[end..];
+    }
+    if let Some(backtrace) = rest.strip_prefix(BACKTRACE) {
+        let lines: Option<Vec<&str>> = backtrace
+            .split('\n')
+            .skip(1)
+            .map(|line| line.strip_prefix("    "))
+            .collect();
+        match lines {
+            Some(lines) => error.backtrace = Some(lines.join("\n")),
+            None => return,
+        }
+    } else if !rest.is_empty() {
+        return;
+    }
+    record.message.truncate(pos);
+    record.error = Some(error);
+}
+
+fn json_str(value: &Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(|s| s.to_string())
+}
+
+/// Parse log message in JSON layout.
+pub(crate) fn parse_json(line: &str, time_format: &str) -> Result<LogRecord, LoggingError> {
+    let value: Value = serde_json::from_str(line).map_err(|e| invalid("JSON", e))?;
+    let date = json_str(&value, "date")
+        .and_then(|date| parse_date(&date, time_format))
+        .ok_or_else(|| invalid("JSON", "date"))?;
+    let level = json_str(&value, "level")
+        .and_then(|level| str2level(&level))
+        .ok_or_else(|| invalid("JSON", "level"))?;
+    let mut record = LogRecord::from_message(
+        level,
+        json_str(&value, "domain").unwrap_or_default(),
+        json_str(&value, "message").unwrap_or_default(),
+    );
+    record.date = date;
+    record.hostname = json_str(&value, "host");
+    record.pname = json_str(&value, "pname").unwrap_or_default();
+    record.pid = value["pid"].as_u64().unwrap_or_default() as u32;
+    record.tname = json_str(&value, "tname");
+    record.tid = value["tid"].as_u64().unwrap_or_default() as u32;
+    if let Some(causes) = value.get("causes").and_then(|causes| causes.as_array()) {
+        record.error = Some(ErrorChain {
+            causes: causes
+                .iter()
+                .filter_map(|cause| cause.as_str().map(|s| s.to_string()))
+                .collect(),
+            backtrace: json_str(&value, "backtrace"),
+        });
+    }
+    Ok(record)
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Parse log message in XML layout `<log>...</log>`.
+pub(crate) fn parse_xml(text: &str, time_format: &str) -> Result<LogRecord, LoggingError> {
+    let mut record = LogRecord::from_message(0, String::new(), String::new());
+    let (mut date, mut level) = (None, None);
+    let mut rest = text.trim();
+    while let Some(start) = rest.find('<') {
+        let end = rest[start..]
+            .find('>')
+            .ok_or_else(|| invalid("XML", "tag"))?
+            + start;
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        if matches!(tag, "log" | "/log" | "causes" | "/causes") {
+            if tag == "causes" {
+                record.error.get_or_insert_default();
+            }
+            continue;
+        }
+        let close = format!("</{tag}>");
+        let value_end = rest.find(&close).ok_or_else(|| invalid("XML", tag))?;
+        let value = xml_unescape(&rest[..value_end]);
+        rest = &rest[value_end + close.len()..];
+        match tag {
+            "date" => date = parse_date(&value, time_format),
+            "host" => record.hostname = Some(value),
+            "pname" => record.pname = value,
+            "pid" => record.pid = value.parse().unwrap_or_default(),
+            "tname" => record.tname = Some(value),
+            "tid" => record.tid = value.parse().unwrap_or_default(),
+            "domain" => record.domain = value,
+            "level" => level = str2level(&value),
+            "message" => record.message = value,
+            "cause" => record.error.get_or_insert_default().causes.push(value),
+            "backtrace" => record.error.get_or_insert_default().backtrace = Some(value),
+            _ => {}
+        }
+    }
+    record.date = date.ok_or_else(|| invalid("XML", "date"))?;
+    record.level = level.ok_or_else(|| invalid("XML", "level"))?;
+    Ok(record)
+}
+
+/// Split logfmt `line` into its `key=value` pairs. Quoted values are unescaped.
+fn logfmt_pairs(line: &str) -> Result<Vec<(&str, String)>, LoggingError> {
+    let mut pairs = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let (key, tail) = rest
+            .split_once('=')
+            .ok_or_else(|| invalid("logfmt", "key"))?;
+        if key.is_empty() || key.contains(' ') {
+            return Err(invalid("logfmt", "key"));
+        }
+        let mut value = String::new();
+        if let Some(quoted) = tail.strip_prefix('"') {
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((pos, '"')) => break pos,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 'r')) => value.push('\r'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, c)) => value.push(c),
+                        None => return Err(invalid("logfmt", key)),
+                    },
+                    Some((_, c)) => value.push(c),
+                    None => return Err(invalid("logfmt", key)),
+                }
+            };
+            rest = &quoted[end + 1..];
+        } else {
+            let end = tail.find(' ').unwrap_or(tail.len());
+            value.push_str(&tail[..end]);
+            rest = &tail[end..];
+        }
+        pairs.push((key, value));
+        rest = rest.trim_start();
+    }
+    Ok(pairs)
+}
+
+/// Parse log message in logfmt layout `time=... level=... msg=...`.
+pub(crate) fn parse_logfmt(line: &str, time_format: &str) -> Result<LogRecord, LoggingError> {
+    let mut record = LogRecord::from_message(0, String::new(), String::new());
+    let (mut date, mut level) = (None, None);
+    for (key, value) in logfmt_pairs(line)? {
+        match key {
+            "time" => date = parse_date(&value, time_format),
+            "host" => record.hostname = Some(value),
+            "pname" => record.pname = value,
+            "pid" => record.pid = value.parse().unwrap_or_default(),
+            "tname" => record.tname = Some(value),
+            "tid" => record.tid = value.parse().unwrap_or_default(),
+            "domain" => record.domain = value,
+            "level" => level = str2level(&value),
+            "msg" => record.message = value,
+            "backtrace" => record.error.get_or_insert_default().backtrace = Some(value),
+            key if key.starts_with("cause.") => {
+                record.error.get_or_insert_default().causes.push(value)
+            }
+            _ => {}
+        }
+    }
+    record.date = date.ok_or_else(|| invalid("logfmt", "time"))?;
+    record.level = level.ok_or_else(|| invalid("logfmt", "level"))?;
+    Ok(record)
+}
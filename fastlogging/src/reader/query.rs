@@ -0,0 +1,732 @@
+use std::{
+    collections::VecDeque,
+    fs::{File, metadata},
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+
+use super::{
+    BinaryReader, LogRecord, open_decompressed,
+    parse::{date_spaces, finish_string, parse_json, parse_logfmt, parse_string, parse_xml},
+};
+use crate::{
+    LoggingError, MessageStructEnum, NOTSET,
+    binary::is_binary,
+    def::{check_filters, check_time_format},
+    file::{BACKUP_EXTENSIONS, FileId, check_backup_pattern, file_id, list_backups},
+    logging::DEFAULT_TIME_FORMAT,
+};
+
+/// Default time between checks for new log messages in follow mode.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Numbered backups `path.N` of log file `path`, which can be compressed, oldest first.
+pub(crate) fn numbered_backups(path: &Path) -> Vec<PathBuf> {
+    let mut backups = Vec::new();
+    for index in 1.. {
+        // Zip archives are not decompressed by the readers.
+        let Some(backup) = BACKUP_EXTENSIONS
+            .iter()
+            .filter(|ext| **ext != ".zip")
+            .find_map(|ext| {
+                let mut backup = path.as_os_str().to_owned();
+                backup.push(format!(".{index}{ext}"));
+                let backup = PathBuf::from(backup);
+                backup.exists().then_some(backup)
+            })
+        else {
+            break;
+        };
+        backups.push(backup);
+    }
+    backups.reverse();
+    backups
+}
+
+/// Detect layout of a log file from its first bytes.
+fn detect(data: &[u8]) -> MessageStructEnum {
+    if is_binary(data) {
+        MessageStructEnum::Binary
+    } else if data.starts_with(b"{") {
+        MessageStructEnum::Json
+    } else if data.starts_with(b"<") {
+        MessageStructEnum::Xml
+    } else if data.starts_with(b"time=") {
+        MessageStructEnum::Logfmt
+    } else {
+        MessageStructEnum::String
+    }
+}
+
+/// Builder of a [`RecordReader`].
+#[derive(Debug, Clone)]
+pub struct RecordReaderBuilder {
+    path: PathBuf,
+    structured: Option<MessageStructEnum>,
+    time_format: Option<String>,
+    backups: bool,
+    backup_pattern: Option<String>,
+    follow: bool,
+    poll_interval: Duration,
+    from: Option<DateTime<Local>>,
+    to: Option<DateTime<Local>>,
+    level: u8,
+    domain_filter: Option<String>,
+    message_filter: Option<String>,
+}
+
+impl RecordReaderBuilder {
+    /// Layout of the log files. By default it is detected per file.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.structured = Some(structured);
+        self
+    }
+
+    /// Timestamp format of the writer (default "%Y.%m.%d %H:%M:%S").
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.time_format = Some(time_format.into());
+        self
+    }
+
+    /// Read numbered, possibly compressed backups `path.N` first, oldest first.
+    pub fn backups(&mut self, backups: bool) -> &mut Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Find backups with the file name pattern of
+    /// [`FileWriterConfigBuilder::backup_pattern`](crate::FileWriterConfigBuilder::backup_pattern).
+    /// They are read oldest first by modification time.
+    pub fn backup_pattern<S: Into<String>>(&mut self, backup_pattern: S) -> &mut Self {
+        self.backup_pattern = Some(backup_pattern.into());
+        self
+    }
+
+    /// Wait for new log messages at the end of the log file like `tail -f`. The log file
+    /// is reopened when it was rotated or truncated. The iterator does not end.
+    pub fn follow(&mut self, follow: bool) -> &mut Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Time between checks for new log messages in follow mode.
+    pub fn poll_interval(&mut self, poll_interval: Duration) -> &mut Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Only return records logged at or after `from`.
+    pub fn from(&mut self, from: DateTime<Local>) -> &mut Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only return records logged before `to`.
+    pub fn to(&mut self, to: DateTime<Local>) -> &mut Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Only return records with `level` or higher.
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    /// Only return records with a domain matching the regular expression `domain_filter`.
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    /// Only return records with a message matching the regular expression `message_filter`.
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Validate settings and create reader.
+    pub fn build(&self) -> Result<RecordReader, LoggingError> {
+        check_filters(&self.domain_filter, &self.message_filter)?;
+        check_time_format(&self.time_format)?;
+        check_backup_pattern(&self.backup_pattern)?;
+        let mut paths: VecDeque<PathBuf> = match (self.backups, &self.backup_pattern) {
+            (false, _) => VecDeque::new(),
+            (true, None) => numbered_backups(&self.path).into(),
+            (true, Some(pattern)) => list_backups(&self.path, Some(pattern))?
+                .into_iter()
+                .map(|(_, _, backup)| backup)
+                // Skip incomplete compressed backups and zip archives
+                .filter(|backup| {
+                    backup
+                        .extension()
+                        .is_none_or(|ext| ext != "tmp" && ext != "zip")
+                })
+                .collect(),
+        };
+        paths.push_back(self.path.clone());
+        let time_format = self
+            .time_format
+            .clone()
+            .unwrap_or(DEFAULT_TIME_FORMAT.to_string());
+        Ok(RecordReader {
+            live_path: self.path.clone(),
+            paths,
+            source: Source::None,
+            structured: self.structured.clone(),
+            date_spaces: date_spaces(&time_format),
+            time_format,
+            follow: self.follow,
+            poll_interval: self.poll_interval,
+            from: self.from,
+            to: self.to,
+            level: self.level,
+            domain_filter: self
+                .domain_filter
+                .as_deref()
+                .map(|f| Regex::new(f).unwrap()),
+            message_filter: self
+                .message_filter
+                .as_deref()
+                .map(|f| Regex::new(f).unwrap()),
+            pending: None,
+        })
+    }
+}
+
+enum Source {
+    None,
+    Text {
+        reader: Box<dyn BufRead + Send>,
+        structured: Option<MessageStructEnum>,
+        /// Incomplete line or XML record
+        buffer: String,
+        /// Id and read position of the live log file in follow mode
+        follow: Option<(FileId, u64)>,
+    },
+    Binary(BinaryReader),
+}
+
+/// Iterator over typed records of log files in string, JSON, XML, logfmt or binary layout,
+/// including compressed backups, with filters for time range, level, domain and message.
+pub struct RecordReader {
+    live_path: PathBuf,
+    paths: VecDeque<PathBuf>,
+    source: Source,
+    structured: Option<MessageStructEnum>,
+    time_format: String,
+    date_spaces: usize,
+    follow: bool,
+    poll_interval: Duration,
+    from: Option<DateTime<Local>>,
+    to: Option<DateTime<Local>>,
+    level: u8,
+    domain_filter: Option<Regex>,
+    message_filter: Option<Regex>,
+    /// String record waiting for continuation lines
+    pending: Option<LogRecord>,
+}
+
+impl RecordReader {
+    /// Read records of log file at `path`.
+    pub fn builder<P: AsRef<Path>>(path: P) -> RecordReaderBuilder {
+        RecordReaderBuilder {
+            path: path.as_ref().to_path_buf(),
+            structured: None,
+            time_format: None,
+            backups: false,
+            backup_pattern: None,
+            follow: false,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            from: None,
+            to: None,
+            level: NOTSET,
+            domain_filter: None,
+            message_filter: None,
+        }
+    }
+
+    /// Read all records of log file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoggingError> {
+        Self::builder(path).build()
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        record.level >= self.level
+            && self.from.is_none_or(|from| record.date >= from)
+            && self.to.is_none_or(|to| record.date < to)
+            && self
+                .domain_filter
+                .as_ref()
+                .is_none_or(|re| re.is_match(&record.domain))
+            && self
+                .message_filter
+                .as_ref()
+                .is_none_or(|re| re.is_match(&record.message))
+    }
+
+    /// Open next log file. Returns `false` if there are no more log files.
+    fn open_next(&mut self) -> Result<bool, LoggingError> {
+        let Some(path) = self.paths.pop_front() else {
+            return Ok(false);
+        };
+        let follow = self.follow && self.paths.is_empty();
+        self.source = if follow {
+            let file = File::open(&path)?;
+            let id = file_id(&file.metadata()?);
+            Source::Text {
+                reader: Box::new(BufReader::new(file)),
+                structured: self.structured.clone(),
+                buffer: String::new(),
+                follow: Some((id, 0)),
+            }
+        } else {
+            let mut reader = BufReader::new(open_decompressed(&path)?);
+            let structured = match self.structured {
+                Some(ref structured) => structured.clone(),
+                None => detect(reader.fill_buf()?),
+            };
+            match structured {
+                MessageStructEnum::Binary => Source::Binary(BinaryReader::new(reader)),
+                structured => Source::Text {
+                    reader: Box::new(reader),
+                    structured: Some(structured),
+                    buffer: String::new(),
+                    follow: None,
+                },
+            }
+        };
+        Ok(true)
+    }
+
+    /// Reopen the live log file if it was rotated or truncated.
+    fn reopen_rotated(&mut self) -> Result<bool, LoggingError> {
+        let Source::Text { ref mut follow, .. } = self.source else {
+            return Ok(false);
+        };
+        let Some((id, position)) = *follow else {
+            return Ok(false);
+        };
+        let Ok(metadata) = metadata(&self.live_path) else {
+            // Rotated, but not yet created again.
+            return Ok(false);
+        };
+        if file_id(&metadata) != id {
+            // Read the rest of the rotated log file, including lines which were completed
+            // or appended after the last read, before following the new log file.
+            *follow = None;
+            self.paths.push_back(self.live_path.clone());
+            return Ok(true);
+        }
+        if metadata.len() < position {
+            self.paths.push_back(self.live_path.clone());
+            return self.open_next();
+        }
+        Ok(false)
+    }
+
+    /// Parse next complete record of the current log file.
+    fn next_record(&mut self) -> Result<Option<LogRecord>, LoggingError> {
+        let mut waited = false;
+        loop {
+            let (reader, structured, buffer, follow) = match self.source {
+                Source::None => {
+                    if !self.open_next()? {
+                        return Ok(self.pending.take());
+                    }
+                    continue;
+                }
+                Source::Binary(ref mut reader) => match reader.next() {
+                    Some(record) => return record.map(Some),
+                    None => {
+                        self.source = Source::None;
+                        continue;
+                    }
+                },
+                Source::Text {
+                    ref mut reader,
+                    ref mut structured,
+                    ref mut buffer,
+                    ref mut follow,
+                } => (reader, structured, buffer, follow),
+            };
+            let start = buffer.len();
+            let size = reader.read_line(buffer)?;
+            if let Some((_, position)) = follow {
+                *position += size as u64;
+            }
+            if size == 0 || (follow.is_some() && !buffer.ends_with('\n')) {
+                // End of log file
+                if follow.is_none() {
+                    let rest = std::mem::take(buffer);
+                    self.source = Source::None;
+                    if !rest.trim().is_empty()
+                        && let Some(record) = self.parse_line(rest)?
+                    {
+                        return Ok(Some(record));
+                    }
+                    continue;
+                }
+                if self.reopen_rotated()? {
+                    continue;
+                }
+                // A record is complete if no continuation line follows within the poll interval.
+                if waited && let Some(record) = self.pending.take() {
+                    return Ok(Some(record));
+                }
+                thread::sleep(self.poll_interval);
+                waited = true;
+                continue;
+            }
+            waited = false;
+            if structured.is_none() {
+                *structured = Some(detect(buffer.as_bytes()));
+                if *structured == Some(MessageStructEnum::Binary) {
+                    return Err(LoggingError::InvalidFile(
+                        "Binary log files can not be followed".to_string(),
+                    ));
+                }
+            }
+            if *structured == Some(MessageStructEnum::Xml) && !buffer[start..].contains("</log>") {
+                continue;
+            }
+            let text = std::mem::take(buffer);
+            if let Some(record) = self.parse_line(text)? {
+                return Ok(Some(record));
+            }
+        }
+    }
+
+    /// Parse complete line or XML record. Returns a record once it is complete.
+    fn parse_line(&mut self, text: String) -> Result<Option<LogRecord>, LoggingError> {
+        let structured = match self.source {
+            Source::Text { ref structured, .. } => structured.clone(),
+            _ => self.structured.clone(),
+        };
+        let line = text.strip_suffix('\n').unwrap_or(&text);
+        match structured.unwrap_or(MessageStructEnum::String) {
+            MessageStructEnum::Json => {
+                if line.trim().is_empty() {
+                    return Ok(None);
+                }
+                parse_json(line, &self.time_format).map(Some)
+            }
+            MessageStructEnum::Xml => parse_xml(line, &self.time_format).map(Some),
+            MessageStructEnum::Logfmt => {
+                if line.trim().is_empty() {
+                    return Ok(None);
+                }
+                parse_logfmt(line, &self.time_format).map(Some)
+            }
+            _ => match parse_string(line, &self.time_format, self.date_spaces) {
+                Some(record) => Ok(self.pending.replace(record)),
+                None => {
+                    // Continuation line of a multi-line message
+                    if let Some(ref mut pending) = self.pending {
+                        pending.message.push('\n');
+                        pending.message.push_str(line);
+                    }
+                    Ok(None)
+                }
+            },
+        }
+    }
+}
+
+impl Iterator for RecordReader {
+    type Item = Result<LogRecord, LoggingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_record() {
+                Ok(Some(mut record)) => {
+                    finish_string(&mut record);
+                    if self.matches(&record) {
+                        return Some(Ok(record));
+                    }
+                }
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::OpenOptions,
+        io::{self, BufRead, Write},
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    use chrono::{Local, TimeDelta};
+    use tempfile::TempDir;
+
+    use super::{RecordReader, Source};
+    use crate::{
+        CompressionMethodEnum, DEBUG, ExtConfig, FileWriterConfig, Logging, MessageStructEnum,
+        WARNING,
+    };
+
+    #[test]
+    fn query() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        for structured in [
+            MessageStructEnum::String,
+            MessageStructEnum::Json,
+            MessageStructEnum::Xml,
+            MessageStructEnum::Logfmt,
+        ] {
+            let log_file = temp_dir.path().join(format!("{structured:?}.log"));
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("query")
+                .writer(
+                    FileWriterConfig::builder(&log_file)
+                        .structured(structured.clone())
+                        .size(2000)
+                        .backlog(100)
+                        .compression(CompressionMethodEnum::Deflate)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            logging.set_ext_config(&ExtConfig::new(
+                structured.clone(),
+                true,
+                true,
+                true,
+                false,
+                true,
+            ));
+            for i in 0..50 {
+                logging.info(format!("Query <message> {i}")).unwrap();
+            }
+            logging
+                .exception_err(&io::Error::other("Query error"))
+                .unwrap();
+            logging.shutdown(false).unwrap();
+            let records: Vec<_> = RecordReader::builder(&log_file)
+                .backups(true)
+                .build()
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(records.len(), 51, "{structured:?}");
+            for (i, record) in records[..50].iter().enumerate() {
+                assert_eq!(record.message, format!("Query <message> {i}"));
+                assert_eq!(record.domain, "query");
+                assert_eq!(record.pid, std::process::id());
+                assert!(record.hostname.is_some());
+                assert!(record.error.is_none());
+            }
+            assert!(records[50].level >= WARNING);
+            assert!(records[50].error.is_some(), "{structured:?}");
+            // Filters
+            let count = |builder: &mut super::RecordReaderBuilder| {
+                builder.backups(true).build().unwrap().count()
+            };
+            let now = Local::now();
+            assert_eq!(count(RecordReader::builder(&log_file).level(WARNING)), 1);
+            assert_eq!(
+                count(RecordReader::builder(&log_file).message_filter(r"message> 4\d$")),
+                10
+            );
+            assert_eq!(
+                count(RecordReader::builder(&log_file).domain_filter("^other$")),
+                0
+            );
+            assert_eq!(
+                count(RecordReader::builder(&log_file).from(now + TimeDelta::hours(1))),
+                0
+            );
+            assert_eq!(
+                count(
+                    RecordReader::builder(&log_file)
+                        .from(now - TimeDelta::hours(1))
+                        .to(now + TimeDelta::hours(1))
+                ),
+                51
+            );
+            // Without backups only records of the live log file are returned.
+            assert!(RecordReader::open(&log_file).unwrap().count() < 51);
+        }
+        assert!(
+            RecordReader::builder("query.log")
+                .message_filter("(")
+                .build()
+                .is_err()
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn backup_pattern() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        for pattern in ["pattern.{index}.log", "pattern-%Y%m%d%H%M%S.log"] {
+            let log_file = temp_dir.path().join("pattern.log");
+            let mut logging = Logging::builder()
+                .level(DEBUG)
+                .domain("pattern")
+                .writer(
+                    FileWriterConfig::builder(&log_file)
+                        .size(500)
+                        .backlog(100)
+                        .backup_pattern(pattern)
+                        .compression(CompressionMethodEnum::Deflate)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            for i in 0..50 {
+                logging.info(format!("Pattern message {i}")).unwrap();
+            }
+            logging.shutdown(false).unwrap();
+            let messages: Vec<_> = RecordReader::builder(&log_file)
+                .backups(true)
+                .backup_pattern(pattern)
+                .build()
+                .unwrap()
+                .map(|r| r.unwrap().message)
+                .collect();
+            assert_eq!(
+                messages,
+                (0..50)
+                    .map(|i| format!("Pattern message {i}"))
+                    .collect::<Vec<_>>(),
+                "{pattern}"
+            );
+            // Numbered backups of the default pattern do not exist.
+            assert!(
+                RecordReader::builder(&log_file)
+                    .backups(true)
+                    .build()
+                    .unwrap()
+                    .count()
+                    < 50
+            );
+            for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+                std::fs::remove_file(entry.unwrap().path()).unwrap();
+            }
+        }
+        assert!(
+            RecordReader::builder("pattern.log")
+                .backup_pattern("backups/{index}.log")
+                .build()
+                .is_err()
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn follow() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("follow.log");
+        let mut logging = Logging::builder()
+            .level(DEBUG)
+            .domain("follow")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .size(1000)
+                    .backlog(100)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.info("Follow message 0").unwrap();
+        logging.sync_all(5.0).unwrap();
+        let reader = RecordReader::builder(&log_file)
+            .follow(true)
+            .poll_interval(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for record in reader {
+                if tx.send(record.unwrap().message).is_err() {
+                    break;
+                }
+            }
+        });
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "Follow message 0"
+        );
+        // Log files are rotated several times while following.
+        for i in 1..100 {
+            logging.info(format!("Follow message {i}")).unwrap();
+            if i % 10 == 0 {
+                logging.sync_all(5.0).unwrap();
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+        for i in 1..90 {
+            assert_eq!(
+                rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+                format!("Follow message {i}")
+            );
+        }
+        assert!(temp_dir.path().join("follow.log.1").exists());
+        logging.shutdown(false).unwrap();
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn follow_partial() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("partial.log");
+        let line = |i: usize| {
+            format!(
+                r#"{{"date":"2024.01.01 00:00:0{i}","level":"INFO","domain":"partial","message":"Partial message {i}"}}"#
+            ) + "\n"
+        };
+        let line1 = line(1);
+        let (head, tail) = line1.split_at(40);
+        std::fs::write(&log_file, line(0)).unwrap();
+        let mut reader = RecordReader::builder(&log_file)
+            .follow(true)
+            .poll_interval(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().message, "Partial message 0");
+        // The reader reaches the end of the log file while a line is half written.
+        let mut file = OpenOptions::new().append(true).open(&log_file).unwrap();
+        file.write_all(head.as_bytes()).unwrap();
+        let Source::Text {
+            reader: ref mut text_reader,
+            ref mut buffer,
+            ..
+        } = reader.source
+        else {
+            panic!("Log file is not followed");
+        };
+        text_reader.read_line(buffer).unwrap();
+        assert_eq!(buffer, head);
+        // Before the rotation is noticed, the writer completes this line, appends another
+        // one and creates a new log file.
+        file.write_all((tail.to_string() + &line(2)).as_bytes())
+            .unwrap();
+        drop(file);
+        std::fs::rename(&log_file, temp_dir.path().join("partial.log.1")).unwrap();
+        std::fs::write(&log_file, line(3)).unwrap();
+        assert!(reader.reopen_rotated().unwrap());
+        for i in 1..4 {
+            assert_eq!(
+                reader.next().unwrap().unwrap().message,
+                format!("Partial message {i}")
+            );
+        }
+        temp_dir.close().unwrap();
+    }
+}
@@ -0,0 +1,565 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use flume::{Sender, bounded};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use regex::Regex;
+
+use crate::{
+    ERROR, LevelSyms, LoggingError, MessageStructEnum, NOTSET,
+    def::{LoggingTypeEnum, check_filters, check_time_format},
+    logging::sync_all_types,
+    panic_hook::{PANIC_SYNC_TIMEOUT, install_hook},
+};
+
+/// Recorders which have to be dumped if the process panics.
+static PANIC_RECORDERS: Lazy<Mutex<Vec<Weak<RecorderState>>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecorderWriterConfig {
+    /// Only record log messages if enabled is true
+    pub enabled: bool,
+    /// Log level for filtering log messages. Messages below the global level are never
+    /// recorded, e.g. TRACE messages only if the global level is TRACE or lower.
+    pub level: u8,
+    /// Optional filter log messages by domain
+    pub domain_filter: Option<String>,
+    /// Optional filter log messages by their contents
+    pub message_filter: Option<String>,
+    /// Optional message structure. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<MessageStructEnum>,
+    /// Optional level naming. Overrides the global setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level2sym: Option<LevelSyms>,
+    /// Optional strftime format of the timestamp. Default is "%Y.%m.%d %H:%M:%S".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+    /// Maximum number of recorded log messages. 0 means no limit.
+    pub capacity: usize,
+    /// Maximum size of recorded log messages in bytes. 0 means no limit.
+    pub max_bytes: usize,
+    /// Dump recorded log messages if a log message with this level or higher is received.
+    pub trigger_level: u8,
+    /// Dump recorded log messages into this file. If not set they are sent to the other writers.
+    pub path: Option<PathBuf>,
+    /// Dump recorded log messages if the process panics. A recorder with this setting
+    /// installs a process-wide panic hook, which also runs for panics caught by
+    /// `catch_unwind`.
+    pub dump_on_panic: bool,
+    /// Debug level. Only for developers.
+    pub debug: u8,
+}
+
+impl RecorderWriterConfig {
+    pub fn new(capacity: usize, path: Option<PathBuf>) -> Self {
+        Self {
+            capacity,
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Create builder for recorder writer configuration.
+    pub fn builder() -> RecorderWriterConfigBuilder {
+        RecorderWriterConfigBuilder::default()
+    }
+
+    pub(crate) fn check_limits(&self) -> Result<(), LoggingError> {
+        if self.capacity == 0 && self.max_bytes == 0 {
+            return Err(LoggingError::InvalidValue(
+                "For recorder writer capacity or max_bytes has to be set!".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RecorderWriterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            level: NOTSET,
+            domain_filter: None,
+            message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
+            capacity: 1000,
+            max_bytes: 0,
+            trigger_level: ERROR,
+            path: None,
+            dump_on_panic: true,
+            debug: 0,
+        }
+    }
+}
+
+impl fmt::Display for RecorderWriterConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Builder for [`RecorderWriterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct RecorderWriterConfigBuilder {
+    config: RecorderWriterConfig,
+}
+
+impl RecorderWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.config.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.config.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.config.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.config.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.config.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.config.time_format = Some(time_format.into());
+        self
+    }
+
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.config.capacity = capacity;
+        self
+    }
+
+    pub fn max_bytes(&mut self, max_bytes: usize) -> &mut Self {
+        self.config.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn trigger_level(&mut self, trigger_level: u8) -> &mut Self {
+        self.config.trigger_level = trigger_level;
+        self
+    }
+
+    pub fn path<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
+        self.config.path = Some(path.into());
+        self
+    }
+
+    pub fn dump_on_panic(&mut self, dump_on_panic: bool) -> &mut Self {
+        self.config.dump_on_panic = dump_on_panic;
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Validate settings and create recorder writer configuration.
+    pub fn build(&self) -> Result<RecorderWriterConfig, LoggingError> {
+        check_filters(&self.config.domain_filter, &self.config.message_filter)?;
+        check_time_format(&self.config.time_format)?;
+        self.config.check_limits()?;
+        Ok(self.config.clone())
+    }
+}
+
+pub type RecordedMessage = (u8, String, String); // level, domain, message
+
+#[derive(Debug, Default)]
+struct RecorderBuffer {
+    records: VecDeque<RecordedMessage>,
+    bytes: usize,
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    config: Arc<RwLock<RecorderWriterConfig>>,
+    buffer: Mutex<RecorderBuffer>,
+    server_tx: Sender<LoggingTypeEnum>,
+}
+
+impl RecorderState {
+    fn push(&self, level: u8, domain: String, message: String) {
+        let config = self.config.read();
+        let mut buffer = self.buffer.lock();
+        buffer.bytes += message.len();
+        buffer.records.push_back((level, domain, message));
+        while buffer.records.len() > 1
+            && ((config.capacity > 0 && buffer.records.len() > config.capacity)
+                || (config.max_bytes > 0 && buffer.bytes > config.max_bytes))
+        {
+            if let Some((_, _, message)) = buffer.records.pop_front() {
+                buffer.bytes -= message.len();
+            }
+        }
+    }
+
+    fn take(&self) -> Vec<RecordedMessage> {
+        let mut buffer = self.buffer.lock();
+        buffer.bytes = 0;
+        buffer.records.drain(..).collect()
+    }
+}
+
+fn write_records(path: &PathBuf, records: &[RecordedMessage]) -> Result<(), LoggingError> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for (_level, _domain, message) in records {
+        file.write_all(message.as_bytes())?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Dump recorders with `dump_on_panic`. Called by the panic hook.
+pub(crate) fn panic_dump() {
+    let Some(recorders) = PANIC_RECORDERS.try_lock() else {
+        return;
+    };
+    let mut dump_writers: Vec<Sender<LoggingTypeEnum>> = Vec::new();
+    for state in recorders.iter().filter_map(|r| r.upgrade()) {
+        let Some(config) = state.config.try_read() else {
+            continue;
+        };
+        if !config.enabled || !config.dump_on_panic {
+            continue;
+        }
+        match config.path {
+            Some(ref path) => {
+                let Some(mut buffer) = state.buffer.try_lock() else {
+                    continue;
+                };
+                buffer.bytes = 0;
+                let records: Vec<_> = buffer.records.drain(..).collect();
+                if let Err(err) = write_records(path, &records) {
+                    eprintln!("RecorderWriter: Failed to dump to {path:?}: {err:?}");
+                }
+            }
+            None => {
+                if !dump_writers
+                    .iter()
+                    .any(|tx| tx.same_channel(&state.server_tx))
+                {
+                    dump_writers.push(state.server_tx.clone());
+                }
+            }
+        }
+    }
+    drop(recorders);
+    // Recorders without a dump file are dumped by the logging broker threads. Wait until
+    // the dumped messages are written, because the process might exit after the panic.
+    for server_tx in dump_writers {
+        let (reply_tx, reply_rx) = bounded(1);
+        if server_tx.send(LoggingTypeEnum::Dump).is_ok()
+            && server_tx
+                .send(LoggingTypeEnum::SyncReply((
+                    sync_all_types(),
+                    PANIC_SYNC_TIMEOUT,
+                    reply_tx,
+                )))
+                .is_ok()
+        {
+            let _ = reply_rx.recv_timeout(Duration::from_secs_f64(PANIC_SYNC_TIMEOUT));
+        }
+    }
+}
+
+fn install_panic_dump(state: &Arc<RecorderState>) {
+    let mut recorders = PANIC_RECORDERS.lock();
+    recorders.retain(|r| r.strong_count() > 0);
+    recorders.push(Arc::downgrade(state));
+    install_hook();
+}
+
+#[derive(Debug)]
+pub struct RecorderWriter {
+    pub(crate) config: Arc<RwLock<RecorderWriterConfig>>,
+    state: Arc<RecorderState>,
+    pub(crate) debug: u8,
+}
+
+impl RecorderWriter {
+    pub fn new(
+        config: RecorderWriterConfig,
+        server_tx: Sender<LoggingTypeEnum>,
+    ) -> Result<Self, LoggingError> {
+        config.check_limits()?;
+        let dump_on_panic = config.dump_on_panic;
+        let config = Arc::new(RwLock::new(config));
+        let state = Arc::new(RecorderState {
+            config: config.clone(),
+            buffer: Mutex::new(RecorderBuffer::default()),
+            server_tx,
+        });
+        if dump_on_panic {
+            install_panic_dump(&state);
+        }
+        Ok(Self {
+            config,
+            state,
+            debug: 0,
+        })
+    }
+
+    pub fn shutdown(&mut self) -> Result<(), LoggingError> {
+        Ok(())
+    }
+
+    pub fn sync(&self, _timeout: f64) -> Result<(), LoggingError> {
+        Ok(())
+    }
+
+    pub fn enable(&self) {
+        self.config.write().enabled = true;
+    }
+
+    pub fn disable(&self) {
+        self.config.write().enabled = false;
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.config.write().enabled = enabled;
+    }
+
+    pub fn set_level(&self, level: u8) {
+        self.config.write().level = level;
+    }
+
+    pub fn set_domain_filter(&self, domain_filter: Option<String>) -> Result<(), regex::Error> {
+        if let Some(ref message) = domain_filter {
+            Regex::new(message)?;
+        }
+        self.config.write().domain_filter = domain_filter;
+        Ok(())
+    }
+
+    pub fn set_message_filter(&self, message_filter: Option<String>) -> Result<(), regex::Error> {
+        if let Some(ref message) = message_filter {
+            Regex::new(message)?;
+        }
+        self.config.write().message_filter = message_filter;
+        Ok(())
+    }
+
+    /// Number of currently recorded log messages.
+    pub fn len(&self) -> usize {
+        self.state.buffer.lock().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.buffer.lock().records.is_empty()
+    }
+
+    /// Record log message. If the level of the message reaches the trigger level the
+    /// recorded messages are dumped. If there is no dump file the recorded messages
+    /// are returned, without the triggering message, to be sent to the other writers.
+    pub fn send(
+        &self,
+        level: u8,
+        domain: String,
+        message: String,
+    ) -> Result<Option<Vec<RecordedMessage>>, LoggingError> {
+        let config = self.config.read();
+        if !config.enabled {
+            return Ok(None);
+        }
+        if let Some(ref domain_filter) = config.domain_filter
+            && !Regex::new(domain_filter).unwrap().is_match(&domain)
+        {
+            return Ok(None);
+        }
+        if let Some(ref message_filter) = config.message_filter
+            && !Regex::new(message_filter).unwrap().is_match(&message)
+        {
+            return Ok(None);
+        }
+        if level < config.trigger_level {
+            drop(config);
+            self.state.push(level, domain, message);
+            return Ok(None);
+        }
+        match config.path.clone() {
+            Some(path) => {
+                drop(config);
+                self.state.push(level, domain, message);
+                write_records(&path, &self.state.take())?;
+                Ok(None)
+            }
+            None => Ok(Some(self.state.take())),
+        }
+    }
+
+    /// Dump recorded log messages. If there is no dump file the recorded messages are
+    /// returned to be sent to the other writers.
+    pub fn dump(&self) -> Result<Option<Vec<RecordedMessage>>, LoggingError> {
+        let path = self.config.read().path.clone();
+        let records = self.state.take();
+        match path {
+            Some(path) => {
+                write_records(&path, &records)?;
+                Ok(None)
+            }
+            None => Ok(Some(records)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use tempfile::TempDir;
+
+    use super::{PANIC_RECORDERS, RecorderWriter};
+    use crate::{
+        CaptureWriterConfig, DEBUG, FileWriterConfig, INFO, Logging, NOTSET, RecorderWriterConfig,
+        WARNING,
+    };
+
+    #[test]
+    fn recorder() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("recorder.log");
+        let dump_file = temp_dir.path().join("recorder.dump");
+        assert!(
+            RecorderWriterConfig::builder()
+                .capacity(0)
+                .max_bytes(0)
+                .build()
+                .is_err()
+        );
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("recorder")
+            .writer(
+                FileWriterConfig::builder(&log_file)
+                    .level(WARNING)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(
+                RecorderWriterConfig::builder()
+                    .capacity(3)
+                    .dump_on_panic(false)
+                    .build()
+                    .unwrap(),
+            )
+            .writer(
+                RecorderWriterConfig::builder()
+                    .level(DEBUG)
+                    .path(&dump_file)
+                    .dump_on_panic(false)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.trace("Trace Message 1".to_string()).unwrap();
+        logging.debug("Debug Message 2".to_string()).unwrap();
+        logging.info("Info Message 3".to_string()).unwrap();
+        logging.info("Info Message 4".to_string()).unwrap();
+        logging.error("Error Message 5".to_string()).unwrap();
+        logging.info("Info Message 6".to_string()).unwrap();
+        logging.dump_recorder().unwrap();
+        logging.shutdown(false).unwrap();
+        let log_text = std::fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<_> = log_text.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].ends_with("recorder: D Debug Message 2"));
+        assert!(lines[1].ends_with("recorder: I Info Message 3"));
+        assert!(lines[2].ends_with("recorder: I Info Message 4"));
+        assert!(lines[3].ends_with("recorder: E Error Message 5"));
+        assert!(lines[4].ends_with("recorder: I Info Message 6"));
+        let dump_text = std::fs::read_to_string(&dump_file).unwrap();
+        let lines: Vec<_> = dump_text.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].ends_with("recorder: D Debug Message 2"));
+        assert!(lines[3].ends_with("recorder: E Error Message 5"));
+        assert!(lines[4].ends_with("recorder: I Info Message 6"));
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn panic_registration() {
+        let registered = |writer: &RecorderWriter| {
+            PANIC_RECORDERS
+                .lock()
+                .iter()
+                .any(|r| r.as_ptr() == Arc::as_ptr(&writer.state))
+        };
+        let (server_tx, _server_rx) = flume::unbounded();
+        // Only recorders with dump_on_panic are dumped by the panic hook.
+        let writer = RecorderWriter::new(
+            RecorderWriterConfig::builder()
+                .dump_on_panic(false)
+                .build()
+                .unwrap(),
+            server_tx.clone(),
+        )
+        .unwrap();
+        assert!(!registered(&writer));
+        let writer =
+            RecorderWriter::new(RecorderWriterConfig::builder().build().unwrap(), server_tx)
+                .unwrap();
+        assert!(registered(&writer));
+    }
+
+    #[test]
+    fn panic_dump() {
+        let capture = CaptureWriterConfig::builder()
+            .level(WARNING)
+            .build()
+            .unwrap();
+        let handle = capture.handle();
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("recorder")
+            .writer(capture)
+            .writer(
+                RecorderWriterConfig::builder()
+                    .capacity(10)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        logging.info("Before panic".to_string()).unwrap();
+        let result = thread::spawn(|| panic!("Recorder panic")).join();
+        assert!(result.is_err());
+        // The panic hook returns after the dump has been written.
+        handle.assert_logged(Some(INFO), Some("recorder"), "Before panic");
+        logging.shutdown(false).unwrap();
+    }
+}
@@ -10,10 +10,10 @@ use parking_lot::RwLock;
 
 use crate::config::{ConfigFile, FileMerge, default_config_file};
 use crate::console::ConsoleWriterConfig;
-use crate::net::{AUTH_KEY, ClientWriterConfig, EncryptionMethod, ServerConfig};
+use crate::net::{AUTH_KEY, ClientStats, ClientWriterConfig, EncryptionMethod, ServerConfig};
 use crate::{
-    ExtConfig, LevelSyms, Logger, Logging, LoggingError, NOTSET, WriterConfigEnum, WriterEnum,
-    WriterTypeEnum, getppid,
+    CaptureHandle, ExtConfig, LevelSyms, Logger, Logging, LoggingError, NOTSET, WriterConfigEnum,
+    WriterEnum, WriterTypeEnum, getppid,
 };
 
 pub static PARENT_LOGGER_ADDRESS: Lazy<RwLock<Option<(u32, ClientWriterConfig)>>> =
@@ -35,38 +35,18 @@ pub static ROOT_LOGGER: Lazy<RwLock<Logging>> = Lazy::new(|| {
         logging
     }
 
-    fn get_port_file(pid: u32) -> PathBuf {
-        let mut temp_dir = env::temp_dir();
-        temp_dir.push(format!("fastlogging_rs_server_port.{pid}"));
-        temp_dir
-    }
-
     fn get_parent_server_address() -> Result<Option<(String, EncryptionMethod)>, LoggingError> {
-        let port_file = get_port_file(getppid());
+        let port_file = port_file(getppid());
         if port_file.exists() {
             // Parent process exists. Check if logging server is reachable.
-            let mut buffer = Vec::new();
-            if fs::File::open(port_file)?.read_to_end(&mut buffer)? >= 4 {
-                let port = u16::from_le_bytes(buffer[..2].try_into().unwrap());
-                let address = format!("127.0.0.1:{port}");
-                let encryption = match buffer[2] {
-                    0 => EncryptionMethod::NONE,
-                    1 => EncryptionMethod::AuthKey(buffer[3..].to_vec()),
-                    2 => EncryptionMethod::AES(buffer[3..].to_vec()),
-                    _ => {
-                        return Err(LoggingError::InvalidValue(format!(
-                            "Invalid encryption type {}",
-                            buffer[2]
-                        )));
-                    }
-                };
-                if let Ok(mut stream) = TcpStream::connect(&address) {
-                    let buffer = vec![0xfeu8, 0xffu8, 0xffu8, 0xffu8];
-                    stream.write_all(&buffer)?;
-                    stream.flush()?;
-                    stream.shutdown(Shutdown::Both)?;
-                    return Ok(Some((address, encryption)));
-                }
+            if let Some((address, encryption)) = read_port_file(&port_file)?
+                && let Ok(mut stream) = TcpStream::connect(&address)
+            {
+                let buffer = vec![0xfeu8, 0xffu8, 0xffu8, 0xffu8];
+                stream.write_all(&buffer)?;
+                stream.flush()?;
+                stream.shutdown(Shutdown::Both)?;
+                return Ok(Some((address, encryption)));
             }
         }
         Ok(None)
@@ -76,7 +56,7 @@ pub static ROOT_LOGGER: Lazy<RwLock<Logging>> = Lazy::new(|| {
         // Check if parent process with fastlogging instance exists.
         let mut logging = create_default_logger(None);
         if let Ok(server) = logging.get_server_config(0) {
-            let port_file = get_port_file(process::id());
+            let port_file = port_file(process::id());
             // Server config above is just a copy. So we need to access the original directly.
             logging
                 .instance
@@ -84,9 +64,7 @@ pub static ROOT_LOGGER: Lazy<RwLock<Logging>> = Lazy::new(|| {
                 .get_server_config(0)
                 .unwrap()
                 .port_file = Some(port_file.clone());
-            let mut file = fs::File::create(port_file)?;
-            file.write_all(&u16::to_le_bytes(server.port))?;
-            file.write_all(&logging.get_server_auth_key().to_bytes())?;
+            write_port_file(&port_file, server.port, &logging.get_server_auth_key())?;
         }
         if let Some((server_address, encryption)) = get_parent_server_address()? {
             // Connect to parent server port
@@ -121,6 +99,48 @@ pub static ROOT_LOGGER: Lazy<RwLock<Logging>> = Lazy::new(|| {
     RwLock::new(logging)
 });
 
+/// Port file of the root logging server of process `pid`.
+pub fn port_file(pid: u32) -> PathBuf {
+    let mut temp_dir = env::temp_dir();
+    temp_dir.push(format!("fastlogging_rs_server_port.{pid}"));
+    temp_dir
+}
+
+/// Write listening `port` and `key` of a logging server into `port_file`.
+pub fn write_port_file(
+    port_file: &Path,
+    port: u16,
+    key: &EncryptionMethod,
+) -> Result<(), LoggingError> {
+    let mut file = fs::File::create(port_file)?;
+    file.write_all(&u16::to_le_bytes(port))?;
+    file.write_all(&key.to_bytes())?;
+    Ok(())
+}
+
+/// Read server address and key from `port_file`. Returns `None` if the file is incomplete.
+pub fn read_port_file(
+    port_file: &Path,
+) -> Result<Option<(String, EncryptionMethod)>, LoggingError> {
+    let mut buffer = Vec::new();
+    if fs::File::open(port_file)?.read_to_end(&mut buffer)? < 3 {
+        return Ok(None);
+    }
+    let port = u16::from_le_bytes(buffer[..2].try_into().unwrap());
+    let encryption = match buffer[2] {
+        0 => EncryptionMethod::NONE,
+        1 => EncryptionMethod::AuthKey(buffer[3..].to_vec()),
+        2 => EncryptionMethod::AES(buffer[3..].to_vec()),
+        _ => {
+            return Err(LoggingError::InvalidValue(format!(
+                "Invalid encryption type {}",
+                buffer[2]
+            )));
+        }
+    };
+    Ok(Some((format!("127.0.0.1:{port}"), encryption)))
+}
+
 /// Initialize ROOT logger.
 pub fn root_init() {
     drop(ROOT_LOGGER.write());
@@ -198,6 +218,13 @@ pub fn add_writers(writers: Vec<WriterEnum>) -> Vec<usize> {
     ROOT_LOGGER.write().add_writers(writers)
 }
 
+/// Add capture writer with log level `level`. Returns writer ID and handle to the captured log messages.
+/// The root logger is shared by all tests of a test binary, so use a domain or message filter in
+/// [`crate::CaptureHandle::find`] to check only the messages of the current test.
+pub fn add_capture_writer(level: u8) -> Result<(usize, CaptureHandle), LoggingError> {
+    ROOT_LOGGER.write().add_capture_writer(level)
+}
+
 /// Remove list of writer. `wids` contains list of writer IDs. The return value is a list of removed writers.
 pub fn remove_writers(wids: Option<Vec<usize>>) -> Vec<WriterEnum> {
     ROOT_LOGGER.write().remove_writers(wids)
@@ -233,6 +260,33 @@ pub fn sync_all(timeout: f64) -> Result<(), LoggingError> {
     ROOT_LOGGER.read().sync_all(timeout)
 }
 
+/// Write all log messages of file writers to disk. Wait for maximum time `timeout` seconds.
+pub fn fsync(timeout: f64) -> Result<(), LoggingError> {
+    ROOT_LOGGER.read().fsync(timeout)
+}
+
+/// Dump log messages of all recorder writers.
+pub fn dump_recorder() -> Result<(), LoggingError> {
+    ROOT_LOGGER.read().dump_recorder()
+}
+
+/// Log panics with backtrace at EXCEPTION level to the root logger and sync its writers.
+/// See [`crate::install_panic_hook`].
+pub fn install_panic_hook() {
+    crate::panic_hook::install_root_panic_hook();
+}
+
+/// Reopen all log files, e.g. after they were moved by logrotate.
+pub fn reopen_files() -> Result<(), LoggingError> {
+    ROOT_LOGGER.read().reopen_files()
+}
+
+/// Reopen all log files of the root logger on SIGHUP. See [`crate::install_sighup_handler`].
+#[cfg(target_family = "unix")]
+pub fn install_sighup_handler() -> Result<(), LoggingError> {
+    crate::sighup::install_root_sighup_handler()
+}
+
 /// Rotate a single log file `path` or all log files with `path` is `None`.
 pub fn rotate(path: Option<PathBuf>) -> Result<(), LoggingError> {
     ROOT_LOGGER.read().rotate(path)
@@ -259,6 +313,8 @@ pub fn set_debug(debug: u8) {
             WriterEnum::Server(server_writer) => server_writer.debug = debug,
             WriterEnum::Callback(callback_writer) => callback_writer.debug = debug,
             WriterEnum::Syslog(syslog_writer) => syslog_writer.debug = debug,
+            WriterEnum::Recorder(recorder_writer) => recorder_writer.debug = debug,
+            WriterEnum::Capture(capture_writer) => capture_writer.debug = debug,
             _ => {}
         }
     }
@@ -280,6 +336,10 @@ pub fn get_server_configs() -> HashMap<usize, ServerConfig> {
     ROOT_LOGGER.read().get_server_configs()
 }
 
+pub fn get_server_client_stats(wid: usize) -> Result<Vec<ClientStats>, LoggingError> {
+    ROOT_LOGGER.read().get_server_client_stats(wid)
+}
+
 pub fn get_root_server_address_port() -> Option<String> {
     ROOT_LOGGER.read().get_root_server_address_port()
 }
@@ -377,3 +437,8 @@ pub fn fatal<S: Into<String>>(message: S) -> Result<(), LoggingError> {
 pub fn exception<S: Into<String>>(message: S) -> Result<(), LoggingError> {
     ROOT_LOGGER.read().exception(message)
 }
+
+/// Log EXCEPTION level message of error `err` with its `source()` chain and backtrace.
+pub fn exception_err(err: &dyn std::error::Error) -> Result<(), LoggingError> {
+    ROOT_LOGGER.read().exception_err(err)
+}
@@ -0,0 +1,359 @@
+use std::{fmt, str::FromStr, time::SystemTime};
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, LocalResult, NaiveDate, TimeZone, Timelike,
+    Utc, Weekday,
+};
+
+use crate::LoggingError;
+
+/// Maximum number of days searched for the next rotation time. Covers leap days.
+const SEARCH_DAYS: u32 = 5 * 366;
+
+/// Timezone in which rotation schedules are evaluated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationTimezone {
+    /// Local timezone of the system, including daylight saving time (default).
+    #[default]
+    Local,
+    Utc,
+    /// Fixed offset in seconds east of UTC.
+    Offset(i32),
+}
+
+impl RotationTimezone {
+    pub(crate) fn check(&self) -> Result<(), LoggingError> {
+        if let RotationTimezone::Offset(offset) = self
+            && FixedOffset::east_opt(*offset).is_none()
+        {
+            return Err(LoggingError::InvalidValue(format!(
+                "Invalid timezone offset {offset}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Format `time` with strftime format `fmt` in this timezone.
+    pub(crate) fn format(&self, time: SystemTime, fmt: &str) -> String {
+        let time = DateTime::<Utc>::from(time);
+        match self {
+            RotationTimezone::Local => time.with_timezone(&Local).format(fmt).to_string(),
+            RotationTimezone::Utc => time.format(fmt).to_string(),
+            RotationTimezone::Offset(offset) => match FixedOffset::east_opt(*offset) {
+                Some(offset) => time.with_timezone(&offset).format(fmt).to_string(),
+                None => time.format(fmt).to_string(),
+            },
+        }
+    }
+}
+
+/// Recurring wall-clock schedule in cron syntax with the fields
+/// `minute hour day-of-month month day-of-week`.
+///
+/// Fields accept `*`, values, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`).
+/// Day-of-week is 0-7 (0 and 7 are Sunday) or `mon`-`sun`, month is 1-12 or `jan`-`dec`.
+/// The shortcuts `@hourly`, `@daily`, `@midnight`, `@weekly`, `@monthly`, `@yearly`
+/// and `@annually` are supported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RotationSchedule {
+    expr: String,
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Option<u32> {
+    if let Ok(value) = value.parse() {
+        return Some(value);
+    }
+    let value = value.to_lowercase();
+    names
+        .iter()
+        .position(|name| *name == value)
+        .map(|pos| pos as u32 + min)
+}
+
+/// Parse one cron field into a bit set. Returns the bit set and whether the field is restricted.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<(u64, bool), String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("Invalid step in {part:?}")),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            match (parse_value(start, min, names), parse_value(end, min, names)) {
+                (Some(start), Some(end)) => (start, end),
+                _ => return Err(format!("Invalid range {range:?}")),
+            }
+        } else {
+            match parse_value(range, min, names) {
+                // "5/15" means every 15th value starting at 5
+                Some(start) if part.contains('/') => (start, max),
+                Some(value) => (value, value),
+                None => return Err(format!("Invalid value {range:?}")),
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("{part:?} is out of range {min}-{max}"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok((bits, field != "*"))
+}
+
+impl RotationSchedule {
+    /// Rotate every hour on the hour.
+    pub fn hourly() -> Self {
+        Self::cron("0 * * * *").unwrap()
+    }
+
+    /// Rotate every day at `hour:minute`.
+    pub fn daily(hour: u32, minute: u32) -> Result<Self, LoggingError> {
+        Self::cron(format!("{minute} {hour} * * *"))
+    }
+
+    /// Rotate every week on `weekday` at `hour:minute`.
+    pub fn weekly(weekday: Weekday, hour: u32, minute: u32) -> Result<Self, LoggingError> {
+        Self::cron(format!(
+            "{minute} {hour} * * {}",
+            weekday.num_days_from_sunday()
+        ))
+    }
+
+    /// Rotate according to cron expression `expr`.
+    pub fn cron<S: Into<String>>(expr: S) -> Result<Self, LoggingError> {
+        let expr = expr.into();
+        let fields = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            fields => fields,
+        };
+        let fields: Vec<_> = fields.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(LoggingError::InvalidValue(format!(
+                "Invalid rotation schedule {expr:?}: Expected 5 fields"
+            )));
+        }
+        let parse = |field: &str, min, max, names: &[&str]| {
+            parse_field(field, min, max, names).map_err(|e| {
+                LoggingError::InvalidValue(format!("Invalid rotation schedule {expr:?}: {e}"))
+            })
+        };
+        let (minutes, _) = parse(fields[0], 0, 59, &[])?;
+        let (hours, _) = parse(fields[1], 0, 23, &[])?;
+        let (days, days_restricted) = parse(fields[2], 1, 31, &[])?;
+        let (months, _) = parse(fields[3], 1, 12, &MONTH_NAMES)?;
+        let (mut weekdays, weekdays_restricted) = parse(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        // 7 is an alias for Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & 0x7f;
+        }
+        Ok(Self {
+            expr,
+            minutes,
+            hours: hours as u32,
+            days: days as u32,
+            months: months as u16,
+            weekdays: weekdays as u8,
+            days_restricted,
+            weekdays_restricted,
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        // Like cron: if both are restricted, either has to match.
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    fn next_after_tz<Tz: TimeZone>(&self, time: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = time.timezone();
+        let start = (time.naive_local() + Duration::minutes(1))
+            .with_second(0)?
+            .with_nanosecond(0)?;
+        let mut date = start.date();
+        for day in 0..SEARCH_DAYS {
+            if self.matches_date(date) {
+                for hour in (0..24).filter(|h| self.hours & (1 << h) != 0) {
+                    if day == 0 && hour < start.hour() {
+                        continue;
+                    }
+                    for minute in (0..60).filter(|m| self.minutes & (1 << m) != 0) {
+                        if day == 0 && hour == start.hour() && minute < start.minute() {
+                            continue;
+                        }
+                        // Times skipped by daylight saving time changes are ignored.
+                        match tz.from_local_datetime(&date.and_hms_opt(hour, minute, 0)?) {
+                            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
+                                return Some(dt);
+                            }
+                            LocalResult::None => {}
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// Next rotation time after `time`, evaluated in timezone `tz`.
+    pub fn next_after(&self, tz: RotationTimezone, time: SystemTime) -> Option<SystemTime> {
+        let time = DateTime::<Utc>::from(time);
+        match tz {
+            RotationTimezone::Local => self
+                .next_after_tz(time.with_timezone(&Local))
+                .map(SystemTime::from),
+            RotationTimezone::Utc => self.next_after_tz(time).map(SystemTime::from),
+            RotationTimezone::Offset(offset) => self
+                .next_after_tz(time.with_timezone(&FixedOffset::east_opt(offset)?))
+                .map(SystemTime::from),
+        }
+    }
+}
+
+impl fmt::Display for RotationSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl FromStr for RotationSchedule {
+    type Err = LoggingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::cron(s)
+    }
+}
+
+impl TryFrom<String> for RotationSchedule {
+    type Error = LoggingError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::cron(value)
+    }
+}
+
+impl From<RotationSchedule> for String {
+    fn from(value: RotationSchedule) -> Self {
+        value.expr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use chrono::{DateTime, Utc, Weekday};
+
+    use super::{RotationSchedule, RotationTimezone};
+
+    fn next(schedule: &RotationSchedule, tz: RotationTimezone, time: &str) -> String {
+        let time = SystemTime::from(DateTime::parse_from_rfc3339(time).unwrap());
+        DateTime::<Utc>::from(schedule.next_after(tz, time).unwrap())
+            .format("%Y-%m-%d %H:%M %a")
+            .to_string()
+    }
+
+    #[test]
+    fn schedule() {
+        let utc = RotationTimezone::Utc;
+        let hourly = RotationSchedule::hourly();
+        assert_eq!(
+            next(&hourly, utc, "2024-03-10T10:00:00Z"),
+            "2024-03-10 11:00 Sun"
+        );
+        assert_eq!(
+            next(&hourly, utc, "2024-03-10T10:59:59Z"),
+            "2024-03-10 11:00 Sun"
+        );
+        let daily = RotationSchedule::daily(0, 0).unwrap();
+        assert_eq!(
+            next(&daily, utc, "2024-12-31T23:30:00Z"),
+            "2025-01-01 00:00 Wed"
+        );
+        let weekly = RotationSchedule::weekly(Weekday::Mon, 6, 30).unwrap();
+        assert_eq!(
+            next(&weekly, utc, "2024-03-11T06:30:00Z"),
+            "2024-03-18 06:30 Mon"
+        );
+        // Midnight in UTC+2 is 22:00 UTC on the previous day.
+        let tz = RotationTimezone::Offset(2 * 3600);
+        assert_eq!(
+            next(&daily, tz, "2024-03-10T12:00:00Z"),
+            "2024-03-10 22:00 Sun"
+        );
+        let cron: RotationSchedule = "*/15 8-17 * * mon-fri".parse().unwrap();
+        assert_eq!(
+            next(&cron, utc, "2024-03-08T17:50:00Z"),
+            "2024-03-11 08:00 Mon"
+        );
+        assert_eq!(
+            next(&cron, utc, "2024-03-11T08:00:00Z"),
+            "2024-03-11 08:15 Mon"
+        );
+        let leap = RotationSchedule::cron("0 0 29 feb *").unwrap();
+        assert_eq!(
+            next(&leap, utc, "2024-03-01T00:00:00Z"),
+            "2028-02-29 00:00 Tue"
+        );
+        let monthly = RotationSchedule::cron("@monthly").unwrap();
+        assert_eq!(
+            next(&monthly, utc, "2024-01-31T00:00:00Z"),
+            "2024-02-01 00:00 Thu"
+        );
+        // Both day-of-month and day-of-week restricted: either matches.
+        let either = RotationSchedule::cron("0 0 1 * 7").unwrap();
+        assert_eq!(
+            next(&either, utc, "2024-03-01T00:00:00Z"),
+            "2024-03-03 00:00 Sun"
+        );
+        for invalid in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(RotationSchedule::cron(invalid).is_err(), "{invalid}");
+        }
+        assert!(RotationSchedule::daily(24, 0).is_err());
+        assert!(RotationTimezone::Offset(86400).check().is_err());
+        let json = serde_json::to_string(&cron).unwrap();
+        assert_eq!(json, "\"*/15 8-17 * * mon-fri\"");
+        assert_eq!(
+            serde_json::from_str::<RotationSchedule>(&json).unwrap(),
+            cron
+        );
+        assert!(serde_json::from_str::<RotationSchedule>("\"* *\"").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    sync::{Arc, Weak},
+    thread,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use signal_hook::{consts::SIGHUP, iterator::Signals};
+
+use crate::{
+    Logging, LoggingError, config::LoggingInstance, logging::reopen_instance_files,
+    root::ROOT_LOGGER,
+};
+
+#[derive(Debug)]
+enum SighupTarget {
+    /// Does not keep the logging instance alive.
+    Logging(Weak<RwLock<LoggingInstance>>),
+    Root,
+}
+
+static SIGHUP_TARGET: Lazy<Mutex<Option<SighupTarget>>> = Lazy::new(|| Mutex::new(None));
+static SIGHUP_THREAD: Mutex<bool> = Mutex::new(false);
+
+fn reopen_files() {
+    let result = match SIGHUP_TARGET.lock().as_ref() {
+        Some(SighupTarget::Logging(instance)) => match instance.upgrade() {
+            Some(instance) => reopen_instance_files(&instance.read()),
+            None => Ok(()),
+        },
+        Some(SighupTarget::Root) => ROOT_LOGGER.read().reopen_files(),
+        None => Ok(()),
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to reopen log files on SIGHUP: {err:?}");
+    }
+}
+
+fn set_sighup_target(target: SighupTarget) -> Result<(), LoggingError> {
+    *SIGHUP_TARGET.lock() = Some(target);
+    let mut started = SIGHUP_THREAD.lock();
+    if !*started {
+        let mut signals = Signals::new([SIGHUP])?;
+        thread::Builder::new()
+            .name("SighupHandler".to_string())
+            .spawn(move || {
+                for _ in signals.forever() {
+                    reopen_files();
+                }
+            })?;
+        *started = true;
+    }
+    Ok(())
+}
+
+/// Reopen all log files of `logging` when the process receives SIGHUP, like logrotate's
+/// `postrotate` scripts expect. Calling it again replaces the logging instance.
+pub fn install_sighup_handler(logging: &Logging) -> Result<(), LoggingError> {
+    set_sighup_target(SighupTarget::Logging(Arc::downgrade(&logging.instance)))
+}
+
+/// Reopen all log files of the root logger on SIGHUP. See [`install_sighup_handler`].
+pub(crate) fn install_root_sighup_handler() -> Result<(), LoggingError> {
+    set_sighup_target(SighupTarget::Root)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc, thread, time::Duration};
+
+    use tempfile::TempDir;
+
+    use crate::{FileWriterConfig, Logging, NOTSET, install_sighup_handler};
+
+    #[test]
+    fn sighup() {
+        let temp_dir = TempDir::with_prefix("fastlogging").unwrap();
+        let log_file = temp_dir.path().join("sighup.log");
+        let moved_file = temp_dir.path().join("sighup.log.1");
+        let mut logging = Logging::builder()
+            .level(NOTSET)
+            .domain("sighup")
+            .writer(FileWriterConfig::builder(&log_file).build().unwrap())
+            .build()
+            .unwrap();
+        let count = Arc::strong_count(&logging.instance);
+        install_sighup_handler(&logging).unwrap();
+        assert_eq!(Arc::strong_count(&logging.instance), count);
+        logging.info("Before Move".to_string()).unwrap();
+        logging.sync_all(5.0).unwrap();
+        fs::rename(&log_file, &moved_file).unwrap();
+        signal_hook::low_level::raise(signal_hook::consts::SIGHUP).unwrap();
+        for _ in 0..100 {
+            if log_file.exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        logging.info("After Move".to_string()).unwrap();
+        logging.shutdown(false).unwrap();
+        assert!(
+            fs::read_to_string(&moved_file)
+                .unwrap()
+                .contains("Before Move")
+        );
+        let log_text = fs::read_to_string(&log_file).unwrap();
+        assert!(!log_text.contains("Before Move"));
+        assert!(log_text.contains("After Move"));
+        temp_dir.close().unwrap();
+    }
+}
@@ -13,7 +13,11 @@ use parking_lot::RwLock;
 use regex::Regex;
 use syslog::{Facility, Formatter3164};
 
-use crate::{CRITICAL, DEBUG, ERROR, EXCEPTION, INFO, LoggingError, SUCCESS, WARNING};
+use crate::{
+    CRITICAL, DEBUG, ERROR, EXCEPTION, INFO, LevelSyms, LoggingError, MessageStructEnum, SUCCESS,
+    WARNING,
+    def::{check_filters, check_time_format},
+};
 
 #[derive(Debug)]
 pub enum SyslogTypeEnum {
@@ -28,6 +32,12 @@ pub struct SyslogWriterConfig {
     pub(crate) level: u8, // Log level
     pub(crate) domain_filter: Option<String>,
     pub(crate) message_filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) structured: Option<MessageStructEnum>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) level2sym: Option<LevelSyms>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) time_format: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
     formatter: Formatter3164,
     pub(crate) debug: u8,
@@ -40,6 +50,9 @@ impl SyslogWriterConfig {
             level,
             domain_filter: None,
             message_filter: None,
+            structured: None,
+            level2sym: None,
+            time_format: None,
             formatter: Formatter3164 {
                 facility: Facility::LOG_USER,
                 hostname,
@@ -49,6 +62,11 @@ impl SyslogWriterConfig {
             debug: 0,
         }
     }
+
+    /// Create builder for syslog writer configuration.
+    pub fn builder() -> SyslogWriterConfigBuilder {
+        SyslogWriterConfigBuilder::default()
+    }
 }
 
 impl fmt::Display for SyslogWriterConfig {
@@ -57,6 +75,102 @@ impl fmt::Display for SyslogWriterConfig {
     }
 }
 
+/// Builder for [`SyslogWriterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct SyslogWriterConfigBuilder {
+    enabled: Option<bool>,
+    level: u8,
+    domain_filter: Option<String>,
+    message_filter: Option<String>,
+    structured: Option<MessageStructEnum>,
+    level2sym: Option<LevelSyms>,
+    time_format: Option<String>,
+    hostname: Option<String>,
+    pname: String,
+    pid: u32,
+    debug: u8,
+}
+
+impl SyslogWriterConfigBuilder {
+    pub fn enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    pub fn domain_filter<S: Into<String>>(&mut self, domain_filter: S) -> &mut Self {
+        self.domain_filter = Some(domain_filter.into());
+        self
+    }
+
+    pub fn message_filter<S: Into<String>>(&mut self, message_filter: S) -> &mut Self {
+        self.message_filter = Some(message_filter.into());
+        self
+    }
+
+    /// Override message structure for this writer.
+    pub fn structured(&mut self, structured: MessageStructEnum) -> &mut Self {
+        self.structured = Some(structured);
+        self
+    }
+
+    /// Override level naming for this writer.
+    pub fn level2sym(&mut self, level2sym: LevelSyms) -> &mut Self {
+        self.level2sym = Some(level2sym);
+        self
+    }
+
+    /// Override strftime format of the timestamp for this writer.
+    pub fn time_format<S: Into<String>>(&mut self, time_format: S) -> &mut Self {
+        self.time_format = Some(time_format.into());
+        self
+    }
+
+    pub fn hostname<S: Into<String>>(&mut self, hostname: S) -> &mut Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    pub fn pname<S: Into<String>>(&mut self, pname: S) -> &mut Self {
+        self.pname = pname.into();
+        self
+    }
+
+    pub fn pid(&mut self, pid: u32) -> &mut Self {
+        self.pid = pid;
+        self
+    }
+
+    pub fn debug(&mut self, debug: u8) -> &mut Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Validate settings and create syslog writer configuration.
+    pub fn build(&self) -> Result<SyslogWriterConfig, LoggingError> {
+        check_filters(&self.domain_filter, &self.message_filter)?;
+        check_time_format(&self.time_format)?;
+        let mut config = SyslogWriterConfig::new(
+            self.level,
+            self.hostname.clone(),
+            self.pname.clone(),
+            self.pid,
+        );
+        config.enabled = self.enabled.unwrap_or(true);
+        config.domain_filter.clone_from(&self.domain_filter);
+        config.message_filter.clone_from(&self.message_filter);
+        config.structured.clone_from(&self.structured);
+        config.level2sym.clone_from(&self.level2sym);
+        config.time_format.clone_from(&self.time_format);
+        config.debug = self.debug;
+        Ok(config)
+    }
+}
+
 fn syslog_writer_thread(
     config: Arc<RwLock<SyslogWriterConfig>>,
     rx: Receiver<SyslogTypeEnum>,
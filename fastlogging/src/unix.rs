@@ -1,5 +1,35 @@
-use std::os::unix::process;
+use std::{
+    ffi::CString,
+    io,
+    os::unix::{ffi::OsStrExt, process},
+    path::Path,
+};
 
 pub fn getppid() -> u32 {
     process::parent_id()
 }
+
+/// Free disk space in bytes available to unprivileged users on the file system of `path`.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn free_space(path: &Path) -> io::Result<u64> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Width in columns of the terminal connected to stdout, or stderr if `stderr` is true.
+pub(crate) fn terminal_width(stderr: bool) -> Option<usize> {
+    let fd = if stderr {
+        libc::STDERR_FILENO
+    } else {
+        libc::STDOUT_FILENO
+    };
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } != 0 || size.ws_col == 0 {
+        return None;
+    }
+    Some(size.ws_col as usize)
+}
@@ -1,8 +1,15 @@
+use std::{io, os::windows::ffi::OsStrExt, path::Path};
+
 use windows_sys::{
     self,
     Win32::{
         Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE},
+        Storage::FileSystem::GetDiskFreeSpaceExW,
         System::{
+            Console::{
+                GetConsoleScreenBufferInfo, GetStdHandle, CONSOLE_SCREEN_BUFFER_INFO,
+                STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+            },
             Diagnostics::ToolHelp::{
                 CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32,
                 TH32CS_SNAPPROCESS,
@@ -45,3 +52,37 @@ pub fn getppid() -> u32 {
     }
     ppid
 }
+
+/// Free disk space in bytes available to the current user on the volume of `path`.
+pub(crate) fn free_space(path: &Path) -> io::Result<u64> {
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available: u64 = 0;
+    if unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    } == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+/// Width in columns of the console connected to stdout, or stderr if `stderr` is true.
+pub(crate) fn terminal_width(stderr: bool) -> Option<usize> {
+    let handle = unsafe {
+        GetStdHandle(if stderr {
+            STD_ERROR_HANDLE
+        } else {
+            STD_OUTPUT_HANDLE
+        })
+    };
+    let mut info: CONSOLE_SCREEN_BUFFER_INFO = unsafe { std::mem::zeroed() };
+    if unsafe { GetConsoleScreenBufferInfo(handle, &mut info) } == 0 {
+        return None;
+    }
+    Some((info.srWindow.Right - info.srWindow.Left + 1) as usize)
+}
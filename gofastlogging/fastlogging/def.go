@@ -105,6 +105,7 @@ const (
 	String MessageStruct = iota
 	Json
 	Xml
+	Logfmt
 )
 
 // Into converts MessageStruct to the underlying C enum value. See [LevelSymbol.Into].
@@ -116,6 +117,8 @@ func (s MessageStruct) Into() uint8 {
 		return 1
 	case Xml:
 		return 2
+	case Logfmt:
+		return 3
 	}
 	return 0
 }
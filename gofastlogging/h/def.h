@@ -123,7 +123,8 @@ typedef struct CWriterEnums {
 typedef enum CMessageStructEnum : uint8_t {
   MessageStructEnum_String = 0,
   MessageStructEnum_Json = 1,
-  MessageStructEnum_Xml = 2
+  MessageStructEnum_Xml = 2,
+  MessageStructEnum_Logfmt = 3
 } CMessageStructEnum;
 
 typedef enum CEncryptionMethodEnum : uint8_t {
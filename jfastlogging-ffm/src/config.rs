@@ -20,6 +20,7 @@ pub unsafe extern "C" fn extConfigNew(
         0 => MessageStructEnum::String,
         1 => MessageStructEnum::Json,
         2 => MessageStructEnum::Xml,
+        3 => MessageStructEnum::Logfmt,
         _ => {
             // FFM: No exception mechanism, just return null
             return ptr::null_mut();
@@ -63,7 +63,7 @@ public class FastLogging {
 	}
 
 	public enum MessageStructEnum {
-		String(0), Json(1), Xml(2);
+		String(0), Json(1), Xml(2), Logfmt(3);
 
 		private final int value;
 
@@ -28,6 +28,7 @@ pub fn extConfigNew(
             0 => MessageStructEnum::String,
             1 => MessageStructEnum::Json,
             2 => MessageStructEnum::Xml,
+            3 => MessageStructEnum::Logfmt,
             _ => {
                 env.throw(format!("Invalid value {structured} for structured"))
                     .unwrap();
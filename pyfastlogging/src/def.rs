@@ -2,7 +2,9 @@ use std::path::PathBuf;
 
 use pyo3::{exceptions::PyValueError, prelude::*};
 
-use crate::writer::{CallbackWriterConfig, RootConfig, SyslogWriterConfig};
+use crate::writer::{
+    CallbackWriterConfig, CaptureWriterConfig, RecorderWriterConfig, RootConfig, SyslogWriterConfig,
+};
 use crate::{ClientWriterConfig, ConsoleWriterConfig, FileWriterConfig, ServerConfig};
 
 #[pyclass(eq, eq_int, from_py_object)]
@@ -100,6 +102,8 @@ pub enum MessageStructEnum {
     String,
     Json,
     Xml,
+    Logfmt,
+    Binary,
 }
 
 impl From<MessageStructEnum> for fastlogging::MessageStructEnum {
@@ -109,6 +113,8 @@ impl From<MessageStructEnum> for fastlogging::MessageStructEnum {
             String => fastlogging::MessageStructEnum::String,
             Json => fastlogging::MessageStructEnum::Json,
             Xml => fastlogging::MessageStructEnum::Xml,
+            Logfmt => fastlogging::MessageStructEnum::Logfmt,
+            Binary => fastlogging::MessageStructEnum::Binary,
         }
     }
 }
@@ -124,6 +130,105 @@ impl MessageStructEnum {
     }
 }
 
+#[pyclass(eq, eq_int, from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleTargetEnum {
+    /// Write log messages to stdout
+    StdOut,
+    /// Write log messages to stderr
+    StdErr,
+    /// Write log messages to stdout and stderr
+    Both,
+    /// Write log messages below split level to stdout and all others to stderr
+    Split,
+}
+
+impl From<ConsoleTargetEnum> for fastlogging::ConsoleTargetEnum {
+    fn from(val: ConsoleTargetEnum) -> Self {
+        use ConsoleTargetEnum::*;
+        match val {
+            StdOut => fastlogging::ConsoleTargetEnum::StdOut,
+            StdErr => fastlogging::ConsoleTargetEnum::StdErr,
+            Both => fastlogging::ConsoleTargetEnum::Both,
+            Split => fastlogging::ConsoleTargetEnum::Split,
+        }
+    }
+}
+
+#[pymethods]
+impl ConsoleTargetEnum {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass(eq, eq_int, from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Colors if the stream is a terminal. NO_COLOR disables and CLICOLOR_FORCE enforces colors.
+    Auto,
+    /// Always write escape codes
+    Always,
+    /// Never write escape codes
+    Never,
+}
+
+impl From<ColorMode> for fastlogging::ColorMode {
+    fn from(val: ColorMode) -> Self {
+        use ColorMode::*;
+        match val {
+            Auto => fastlogging::ColorMode::Auto,
+            Always => fastlogging::ColorMode::Always,
+            Never => fastlogging::ColorMode::Never,
+        }
+    }
+}
+
+#[pymethods]
+impl ColorMode {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass(eq, eq_int, from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OversizeEnum {
+    /// Send the message in several parts, split at line breaks if possible
+    Split,
+    /// Send only the beginning of the message, marked with " [truncated]"
+    Truncate,
+}
+
+impl From<OversizeEnum> for fastlogging::OversizeEnum {
+    fn from(val: OversizeEnum) -> Self {
+        use OversizeEnum::*;
+        match val {
+            Split => fastlogging::OversizeEnum::Split,
+            Truncate => fastlogging::OversizeEnum::Truncate,
+        }
+    }
+}
+
+#[pymethods]
+impl OversizeEnum {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
 #[pyclass(eq, eq_int, from_py_object)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CompressionMethodEnum {
@@ -214,6 +319,8 @@ pub enum WriterTypeEnum {
     Servers {},
     Syslog {},
     Callback {},
+    Recorder {},
+    Capture {},
 }
 
 impl From<WriterTypeEnum> for fastlogging::WriterTypeEnum {
@@ -230,6 +337,8 @@ impl From<WriterTypeEnum> for fastlogging::WriterTypeEnum {
             Servers {} => fastlogging::WriterTypeEnum::Servers,
             Syslog {} => fastlogging::WriterTypeEnum::Syslog,
             Callback {} => fastlogging::WriterTypeEnum::Callback,
+            Recorder {} => fastlogging::WriterTypeEnum::Recorder,
+            Capture {} => fastlogging::WriterTypeEnum::Capture,
         }
     }
 }
@@ -250,6 +359,8 @@ impl From<fastlogging::WriterTypeEnum> for WriterTypeEnum {
             Servers => WriterTypeEnum::Servers {},
             Syslog => WriterTypeEnum::Syslog {},
             Callback => WriterTypeEnum::Callback {},
+            Recorder => WriterTypeEnum::Recorder {},
+            Capture => WriterTypeEnum::Capture {},
         }
     }
 }
@@ -275,6 +386,8 @@ pub enum WriterConfigEnum {
     Server { config: ServerConfig },
     Syslog { config: SyslogWriterConfig },
     Callback { config: CallbackWriterConfig },
+    Recorder { config: RecorderWriterConfig },
+    Capture { config: CaptureWriterConfig },
 }
 
 impl From<WriterConfigEnum> for fastlogging::WriterConfigEnum {
@@ -288,6 +401,8 @@ impl From<WriterConfigEnum> for fastlogging::WriterConfigEnum {
             Server { config } => fastlogging::WriterConfigEnum::Server(config.into()),
             Syslog { config } => fastlogging::WriterConfigEnum::Syslog(config.into()),
             Callback { config } => fastlogging::WriterConfigEnum::Callback(config.0),
+            Recorder { config } => fastlogging::WriterConfigEnum::Recorder(config.0),
+            Capture { config } => fastlogging::WriterConfigEnum::Capture(config.0),
         }
     }
 }
@@ -317,6 +432,12 @@ impl From<fastlogging::WriterConfigEnum> for WriterConfigEnum {
             Callback(config) => WriterConfigEnum::Callback {
                 config: CallbackWriterConfig(config),
             },
+            Recorder(config) => WriterConfigEnum::Recorder {
+                config: RecorderWriterConfig(config),
+            },
+            Capture(config) => WriterConfigEnum::Capture {
+                config: CaptureWriterConfig(config),
+            },
         }
     }
 }
@@ -332,6 +453,8 @@ impl From<&WriterConfigEnum> for fastlogging::WriterConfigEnum {
             Server { config } => fastlogging::WriterConfigEnum::Server(config.into()),
             Syslog { config } => fastlogging::WriterConfigEnum::Syslog(config.into()),
             Callback { config } => fastlogging::WriterConfigEnum::Callback(config.0.clone()),
+            Recorder { config } => fastlogging::WriterConfigEnum::Recorder(config.0.clone()),
+            Capture { config } => fastlogging::WriterConfigEnum::Capture(config.0.clone()),
         }
     }
 }
@@ -361,6 +484,12 @@ impl<'a> From<&'a fastlogging::WriterConfigEnum> for WriterConfigEnum {
             Callback(config) => WriterConfigEnum::Callback {
                 config: CallbackWriterConfig(config.clone()),
             },
+            Recorder(config) => WriterConfigEnum::Recorder {
+                config: RecorderWriterConfig(config.clone()),
+            },
+            Capture(config) => WriterConfigEnum::Capture {
+                config: CaptureWriterConfig(config.clone()),
+            },
         }
     }
 }
@@ -401,6 +530,18 @@ impl From<CallbackWriterConfig> for WriterConfigEnum {
     }
 }
 
+impl From<RecorderWriterConfig> for WriterConfigEnum {
+    fn from(config: RecorderWriterConfig) -> Self {
+        Self::Recorder { config }
+    }
+}
+
+impl From<CaptureWriterConfig> for WriterConfigEnum {
+    fn from(config: CaptureWriterConfig) -> Self {
+        Self::Capture { config }
+    }
+}
+
 #[pymethods]
 impl WriterConfigEnum {
     fn __repr__(&self) -> String {
@@ -3,8 +3,14 @@ use pyo3::prelude::*;
 mod def;
 pub use def::{EncryptionMethod, LevelSyms, WriterConfigEnum, WriterTypeEnum};
 mod writer;
-use writer::{CallbackWriterConfig, ExtConfig};
-pub use writer::{ClientWriterConfig, ConsoleWriterConfig, FileWriterConfig, ServerConfig};
+use writer::{CallbackWriterConfig, CallbackWriterConfigBuilder, ExtConfig};
+pub use writer::{
+    ClientWriterConfig, ClientWriterConfigBuilder, ConsoleWriterConfig, ConsoleWriterConfigBuilder,
+    FileWriterConfig, FileWriterConfigBuilder, ServerConfig, ServerConfigBuilder,
+    CaptureHandle, CaptureWriterConfig, RecorderWriterConfig, RecorderWriterConfigBuilder,
+    SyslogWriterConfig,
+    SyslogWriterConfigBuilder,
+};
 mod error;
 pub use error::LoggingError;
 mod logger;
@@ -37,6 +43,9 @@ fn init(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add("NOTSET", fastlogging::NOTSET)?;
     m.add_class::<def::Level2Sym>()?;
     m.add_class::<def::MessageStructEnum>()?;
+    m.add_class::<def::ConsoleTargetEnum>()?;
+    m.add_class::<def::ColorMode>()?;
+    m.add_class::<def::OversizeEnum>()?;
     m.add_class::<def::CompressionMethodEnum>()?;
     m.add_class::<def::EncryptionMethod>()?;
     m.add_class::<def::WriterTypeEnum>()?;
@@ -47,7 +56,18 @@ fn init(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<ServerConfig>()?;
     m.add_class::<ClientWriterConfig>()?;
     m.add_class::<CallbackWriterConfig>()?;
+    m.add_class::<RecorderWriterConfig>()?;
+    m.add_class::<CaptureWriterConfig>()?;
+    m.add_class::<CaptureHandle>()?;
+    m.add_class::<ConsoleWriterConfigBuilder>()?;
+    m.add_class::<FileWriterConfigBuilder>()?;
+    m.add_class::<ServerConfigBuilder>()?;
+    m.add_class::<ClientWriterConfigBuilder>()?;
+    m.add_class::<SyslogWriterConfigBuilder>()?;
+    m.add_class::<CallbackWriterConfigBuilder>()?;
+    m.add_class::<RecorderWriterConfigBuilder>()?;
     m.add_class::<logging::Logging>()?;
+    m.add_class::<logging::LoggingBuilder>()?;
     m.add_class::<logger::Logger>()?;
     m.add_function(wrap_pyfunction!(root::root_init, m)?)?;
     m.add_function(wrap_pyfunction!(root::shutdown, m)?)?;
@@ -69,6 +89,9 @@ fn init(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(root::disable_type, m)?)?;
     m.add_function(wrap_pyfunction!(root::sync, m)?)?;
     m.add_function(wrap_pyfunction!(root::sync_all, m)?)?;
+    m.add_function(wrap_pyfunction!(root::dump_recorder, m)?)?;
+    m.add_function(wrap_pyfunction!(root::fsync, m)?)?;
+    m.add_function(wrap_pyfunction!(root::reopen_files, m)?)?;
     m.add_function(wrap_pyfunction!(root::rotate, m)?)?;
     m.add_function(wrap_pyfunction!(root::set_encryption, m)?)?;
     m.add_function(wrap_pyfunction!(root::set_debug, m)?)?;
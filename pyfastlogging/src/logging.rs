@@ -13,7 +13,8 @@ use fastlogging::{
 use crate::def::{EncryptionMethod, LevelSyms, WriterConfigEnum, WriterTypeEnum};
 use crate::logger::Logger;
 use crate::writer::{
-    CallbackWriterConfig, ExtConfig, RootConfig, ServerConfig, SyslogWriterConfig,
+    CallbackWriterConfig, CaptureWriterConfig, ExtConfig, RecorderWriterConfig, RootConfig,
+    ServerConfig, SyslogWriterConfig,
 };
 use crate::{ClientWriterConfig, ConsoleWriterConfig, FileWriterConfig, LoggingError};
 
@@ -104,6 +105,10 @@ impl Logging {
                     writer_configs.push(WriterConfigEnum::Syslog { config: v }.into());
                 } else if let Ok(v) = config.extract::<CallbackWriterConfig>(py) {
                     writer_configs.push(WriterConfigEnum::Callback { config: v }.into());
+                } else if let Ok(v) = config.extract::<RecorderWriterConfig>(py) {
+                    writer_configs.push(WriterConfigEnum::Recorder { config: v }.into());
+                } else if let Ok(v) = config.extract::<CaptureWriterConfig>(py) {
+                    writer_configs.push(WriterConfigEnum::Capture { config: v }.into());
                 } else {
                     return Err(LoggingError(fastlogging::LoggingError::InvalidValue(
                         format!("Writer configuration {config:?} has invalid type"),
@@ -129,6 +134,11 @@ impl Logging {
         })
     }
 
+    #[staticmethod]
+    pub fn builder() -> LoggingBuilder {
+        LoggingBuilder::default()
+    }
+
     #[pyo3(signature=(now=None,))]
     pub fn shutdown(&mut self, now: Option<bool>, py: Python) -> Result<(), LoggingError> {
         py.detach(|| -> Result<(), LoggingError> {
@@ -181,6 +191,10 @@ impl Logging {
             fastlogging::WriterConfigEnum::Syslog(config.0)
         } else if let Ok(config) = config.extract::<CallbackWriterConfig>(py) {
             fastlogging::WriterConfigEnum::Callback(config.0)
+        } else if let Ok(config) = config.extract::<RecorderWriterConfig>(py) {
+            fastlogging::WriterConfigEnum::Recorder(config.0)
+        } else if let Ok(config) = config.extract::<CaptureWriterConfig>(py) {
+            fastlogging::WriterConfigEnum::Capture(config.0)
         } else {
             return Err(fastlogging::LoggingError::InvalidValue(
                 "writer has invalid argument type".to_string(),
@@ -251,8 +265,21 @@ impl Logging {
         Ok(self.instance.sync_all(timeout.unwrap_or(1.0))?)
     }
 
+    pub fn dump_recorder(&self) -> Result<(), LoggingError> {
+        Ok(self.instance.dump_recorder()?)
+    }
+
     // File logger
 
+    #[pyo3(signature=(timeout=None, /))]
+    pub fn fsync(&self, timeout: Option<f64>) -> Result<(), LoggingError> {
+        Ok(self.instance.fsync(timeout.unwrap_or(1.0))?)
+    }
+
+    pub fn reopen_files(&self) -> Result<(), LoggingError> {
+        Ok(self.instance.reopen_files()?)
+    }
+
     #[pyo3(signature=(path=None, /))]
     pub fn rotate(&self, path: Option<PathBuf>) -> Result<(), LoggingError> {
         Ok(self.instance.rotate(path)?)
@@ -473,3 +500,102 @@ impl Drop for Logging {
         self.instance.shutdown(false).unwrap();
     }
 }
+
+#[pyclass]
+#[derive(Debug, Default)]
+pub struct LoggingBuilder {
+    level: Option<u8>,
+    domain: Option<String>,
+    configs: Vec<Py<PyAny>>,
+    ext_config: Option<Py<ExtConfig>>,
+    config_path: Option<PathBuf>,
+    indent: Option<(usize, usize, usize)>,
+    level2sym: Option<Py<LevelSyms>>,
+    debug: u8,
+}
+
+#[pymethods]
+impl LoggingBuilder {
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.level = Some(level);
+        slf
+    }
+
+    pub fn domain(mut slf: PyRefMut<'_, Self>, domain: String) -> PyRefMut<'_, Self> {
+        slf.domain = Some(domain);
+        slf
+    }
+
+    pub fn writer(mut slf: PyRefMut<'_, Self>, config: Py<PyAny>) -> PyRefMut<'_, Self> {
+        slf.configs.push(config);
+        slf
+    }
+
+    pub fn writers(mut slf: PyRefMut<'_, Self>, configs: Vec<Py<PyAny>>) -> PyRefMut<'_, Self> {
+        slf.configs.extend(configs);
+        slf
+    }
+
+    pub fn ext_config(
+        mut slf: PyRefMut<'_, Self>,
+        ext_config: Py<ExtConfig>,
+    ) -> PyRefMut<'_, Self> {
+        slf.ext_config = Some(ext_config);
+        slf
+    }
+
+    pub fn config_path(mut slf: PyRefMut<'_, Self>, config_path: PathBuf) -> PyRefMut<'_, Self> {
+        slf.config_path = Some(config_path);
+        slf
+    }
+
+    pub fn indent(
+        mut slf: PyRefMut<'_, Self>,
+        indent: (usize, usize, usize),
+    ) -> PyRefMut<'_, Self> {
+        slf.indent = Some(indent);
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: Py<LevelSyms>) -> PyRefMut<'_, Self> {
+        slf.level2sym = Some(level2sym);
+        slf
+    }
+
+    pub fn debug(mut slf: PyRefMut<'_, Self>, debug: u8) -> PyRefMut<'_, Self> {
+        slf.debug = debug;
+        slf
+    }
+
+    pub fn build(&self, py: Python) -> Result<Logging, LoggingError> {
+        if self.domain.as_ref().is_some_and(|d| d.is_empty()) {
+            return Err(LoggingError(fastlogging::LoggingError::InvalidValue(
+                "Logging domain must not be empty".to_string(),
+            )));
+        }
+        let mut logging = Logging::new(
+            self.level,
+            self.domain.clone(),
+            Some(self.configs.iter().map(|c| c.clone_ref(py)).collect()),
+            self.ext_config.as_ref().map(|c| c.bind(py)),
+            self.config_path.clone(),
+            self.indent,
+            py,
+        )?;
+        if let Some(ref level2sym) = self.level2sym {
+            logging.set_level2sym(level2sym.bind(py));
+        }
+        if self.debug > 0 {
+            logging.set_debug(self.debug);
+        }
+        Ok(logging)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
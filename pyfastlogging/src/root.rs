@@ -14,7 +14,10 @@ use crate::{
     ClientWriterConfig, ConsoleWriterConfig, EncryptionMethod, FileWriterConfig, LevelSyms,
     LoggingError, ServerConfig, WriterConfigEnum, WriterTypeEnum,
     logger::Logger,
-    writer::{CallbackWriterConfig, ExtConfig, RootConfig, SyslogWriterConfig},
+    writer::{
+        CallbackWriterConfig, CaptureWriterConfig, ExtConfig, RecorderWriterConfig, RootConfig,
+        SyslogWriterConfig,
+    },
 };
 
 static LEVEL: AtomicU8 = AtomicU8::new(NOTSET);
@@ -50,6 +53,10 @@ fn extract_writer_config_enum(
         fastlogging::WriterConfigEnum::Syslog(config.0)
     } else if let Ok(config) = config.extract::<CallbackWriterConfig>(py) {
         fastlogging::WriterConfigEnum::Callback(config.0)
+    } else if let Ok(config) = config.extract::<RecorderWriterConfig>(py) {
+        fastlogging::WriterConfigEnum::Recorder(config.0)
+    } else if let Ok(config) = config.extract::<CaptureWriterConfig>(py) {
+        fastlogging::WriterConfigEnum::Capture(config.0)
     } else {
         return Err(fastlogging::LoggingError::InvalidValue(
             "writer has invalid argument type".to_string(),
@@ -200,6 +207,22 @@ pub fn sync_all(timeout: Option<f64>) -> Result<(), LoggingError> {
     Ok(fastlogging::root::sync_all(timeout.unwrap_or_default())?)
 }
 
+#[pyfunction]
+pub fn dump_recorder() -> Result<(), LoggingError> {
+    Ok(fastlogging::root::dump_recorder()?)
+}
+
+#[pyfunction]
+#[pyo3(signature=(timeout=None, /))]
+pub fn fsync(timeout: Option<f64>) -> Result<(), LoggingError> {
+    Ok(fastlogging::root::fsync(timeout.unwrap_or(1.0))?)
+}
+
+#[pyfunction]
+pub fn reopen_files() -> Result<(), LoggingError> {
+    Ok(fastlogging::root::reopen_files()?)
+}
+
 #[pyfunction]
 #[pyo3(signature=(path=None, /))]
 pub fn rotate(path: Option<PathBuf>) -> Result<(), LoggingError> {
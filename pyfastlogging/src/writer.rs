@@ -6,7 +6,9 @@ use parking_lot::RwLock;
 use pyo3::types::PyTuple;
 use pyo3::{IntoPyObjectExt, prelude::*};
 
-use crate::def::{CompressionMethodEnum, MessageStructEnum};
+use crate::def::{
+    ColorMode, CompressionMethodEnum, ConsoleTargetEnum, LevelSyms, MessageStructEnum, OversizeEnum,
+};
 use crate::{EncryptionMethod, LoggingError};
 
 #[pyclass(from_py_object)]
@@ -93,6 +95,115 @@ impl ConsoleWriterConfig {
         Self(fastlogging::ConsoleWriterConfig::new(level, colors))
     }
 
+    #[staticmethod]
+    pub fn builder() -> ConsoleWriterConfigBuilder {
+        ConsoleWriterConfigBuilder(fastlogging::ConsoleWriterConfig::builder())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct ConsoleWriterConfigBuilder(fastlogging::ConsoleWriterConfigBuilder);
+
+#[pymethods]
+impl ConsoleWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.0.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.0.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.0.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.0.time_format(time_format);
+        slf
+    }
+
+    pub fn colors(mut slf: PyRefMut<'_, Self>, colors: bool) -> PyRefMut<'_, Self> {
+        slf.0.colors(colors);
+        slf
+    }
+
+    pub fn color_mode(mut slf: PyRefMut<'_, Self>, color_mode: ColorMode) -> PyRefMut<'_, Self> {
+        slf.0.color_mode(color_mode.into());
+        slf
+    }
+
+    pub fn target(mut slf: PyRefMut<'_, Self>, target: ConsoleTargetEnum) -> PyRefMut<'_, Self> {
+        slf.0.target(target.into());
+        slf
+    }
+
+    pub fn split(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.split(level);
+        slf
+    }
+
+    #[pyo3(signature=(domain_width=16, relative_time=true, fields=true, wrap=true, width=None))]
+    pub fn pretty(
+        mut slf: PyRefMut<'_, Self>,
+        domain_width: usize,
+        relative_time: bool,
+        fields: bool,
+        wrap: bool,
+        width: Option<usize>,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.pretty(fastlogging::PrettyLayout {
+            domain_width,
+            relative_time,
+            fields,
+            wrap,
+            width,
+        });
+        slf
+    }
+
+    pub fn debug(mut slf: PyRefMut<'_, Self>, debug: u8) -> PyRefMut<'_, Self> {
+        slf.0.debug(debug);
+        slf
+    }
+
+    pub fn build(&self) -> Result<ConsoleWriterConfig, LoggingError> {
+        Ok(ConsoleWriterConfig(self.0.build()?))
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
@@ -153,6 +264,99 @@ impl FileWriterConfig {
             compression.map(|x| x.into()),
         )?))
     }
+
+    #[staticmethod]
+    pub fn builder(path: PathBuf) -> FileWriterConfigBuilder {
+        FileWriterConfigBuilder(fastlogging::FileWriterConfig::builder(path))
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct FileWriterConfigBuilder(fastlogging::FileWriterConfigBuilder);
+
+#[pymethods]
+impl FileWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.0.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.0.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.0.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.0.time_format(time_format);
+        slf
+    }
+
+    pub fn size(mut slf: PyRefMut<'_, Self>, size: usize) -> PyRefMut<'_, Self> {
+        slf.0.size(size);
+        slf
+    }
+
+    pub fn backlog(mut slf: PyRefMut<'_, Self>, backlog: usize) -> PyRefMut<'_, Self> {
+        slf.0.backlog(backlog);
+        slf
+    }
+
+    pub fn timeout(mut slf: PyRefMut<'_, Self>, timeout: Duration) -> PyRefMut<'_, Self> {
+        slf.0.timeout(timeout);
+        slf
+    }
+
+    pub fn time(mut slf: PyRefMut<'_, Self>, time: SystemTime) -> PyRefMut<'_, Self> {
+        slf.0.time(time);
+        slf
+    }
+
+    pub fn compression(
+        mut slf: PyRefMut<'_, Self>,
+        compression: CompressionMethodEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.compression(compression.into());
+        slf
+    }
+
+    pub fn build(&self) -> Result<FileWriterConfig, LoggingError> {
+        Ok(FileWriterConfig(self.0.build()?))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
 }
 
 impl From<FileWriterConfig> for fastlogging::FileWriterConfig {
@@ -192,6 +396,53 @@ impl ServerConfig {
         Self(fastlogging::ServerConfig::new(level, address, key.into()))
     }
 
+    #[staticmethod]
+    pub fn builder(address: String) -> ServerConfigBuilder {
+        ServerConfigBuilder(fastlogging::ServerConfig::builder(address))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct ServerConfigBuilder(fastlogging::ServerConfigBuilder);
+
+#[pymethods]
+impl ServerConfigBuilder {
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn key(mut slf: PyRefMut<'_, Self>, key: EncryptionMethod) -> PyRefMut<'_, Self> {
+        slf.0.key(key.into());
+        slf
+    }
+
+    pub fn port_file(mut slf: PyRefMut<'_, Self>, port_file: PathBuf) -> PyRefMut<'_, Self> {
+        slf.0.port_file(port_file);
+        slf
+    }
+
+    pub fn max_message_size(
+        mut slf: PyRefMut<'_, Self>,
+        max_message_size: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.max_message_size(max_message_size);
+        slf
+    }
+
+    pub fn build(&self) -> Result<ServerConfig, LoggingError> {
+        Ok(ServerConfig(self.0.build()?))
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
@@ -242,6 +493,94 @@ impl ClientWriterConfig {
         ))
     }
 
+    #[staticmethod]
+    pub fn builder(address: String) -> ClientWriterConfigBuilder {
+        ClientWriterConfigBuilder(fastlogging::ClientWriterConfig::builder(address))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct ClientWriterConfigBuilder(fastlogging::ClientWriterConfigBuilder);
+
+#[pymethods]
+impl ClientWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.0.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.0.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.0.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.0.time_format(time_format);
+        slf
+    }
+
+    pub fn key(mut slf: PyRefMut<'_, Self>, key: EncryptionMethod) -> PyRefMut<'_, Self> {
+        slf.0.key(key.into());
+        slf
+    }
+
+    pub fn max_message_size(
+        mut slf: PyRefMut<'_, Self>,
+        max_message_size: usize,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.max_message_size(max_message_size);
+        slf
+    }
+
+    pub fn oversize(mut slf: PyRefMut<'_, Self>, oversize: OversizeEnum) -> PyRefMut<'_, Self> {
+        slf.0.oversize(oversize.into());
+        slf
+    }
+
+    pub fn debug(mut slf: PyRefMut<'_, Self>, debug: u8) -> PyRefMut<'_, Self> {
+        slf.0.debug(debug);
+        slf
+    }
+
+    pub fn build(&self) -> Result<ClientWriterConfig, LoggingError> {
+        Ok(ClientWriterConfig(self.0.build()?))
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
@@ -297,6 +636,86 @@ impl SyslogWriterConfig {
         ))
     }
 
+    #[staticmethod]
+    pub fn builder() -> SyslogWriterConfigBuilder {
+        SyslogWriterConfigBuilder(fastlogging::SyslogWriterConfig::builder())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct SyslogWriterConfigBuilder(fastlogging::SyslogWriterConfigBuilder);
+
+#[pymethods]
+impl SyslogWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.0.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.0.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.0.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.0.time_format(time_format);
+        slf
+    }
+
+    pub fn hostname(mut slf: PyRefMut<'_, Self>, hostname: String) -> PyRefMut<'_, Self> {
+        slf.0.hostname(hostname);
+        slf
+    }
+
+    pub fn pname(mut slf: PyRefMut<'_, Self>, pname: String) -> PyRefMut<'_, Self> {
+        slf.0.pname(pname);
+        slf
+    }
+
+    pub fn pid(mut slf: PyRefMut<'_, Self>, pid: u32) -> PyRefMut<'_, Self> {
+        slf.0.pid(pid);
+        slf
+    }
+
+    pub fn build(&self) -> Result<SyslogWriterConfig, LoggingError> {
+        Ok(SyslogWriterConfig(self.0.build()?))
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
@@ -379,6 +798,300 @@ impl CallbackWriterConfig {
         *CALLBACK_PY_FUNC.write() = callback;
     }
 
+    #[staticmethod]
+    pub fn builder() -> CallbackWriterConfigBuilder {
+        CallbackWriterConfigBuilder {
+            builder: fastlogging::CallbackWriterConfig::builder(),
+            callback: None,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct CallbackWriterConfigBuilder {
+    builder: fastlogging::CallbackWriterConfigBuilder,
+    callback: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl CallbackWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.builder.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.builder.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.builder.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.builder.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.builder.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.builder.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.builder.time_format(time_format);
+        slf
+    }
+
+    pub fn callback(mut slf: PyRefMut<'_, Self>, callback: Py<PyAny>) -> PyRefMut<'_, Self> {
+        slf.builder.callback(Box::new(callback_func));
+        slf.callback = Some(callback);
+        slf
+    }
+
+    pub fn debug(mut slf: PyRefMut<'_, Self>, debug: u8) -> PyRefMut<'_, Self> {
+        slf.builder.debug(debug);
+        slf
+    }
+
+    pub fn build(&self, py: Python) -> Result<CallbackWriterConfig, LoggingError> {
+        let config = self.builder.build()?;
+        *CALLBACK_PY_FUNC.write() = self.callback.as_ref().map(|c| c.clone_ref(py));
+        Ok(CallbackWriterConfig(config))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass(from_py_object)]
+#[derive(Debug, Clone)]
+pub struct RecorderWriterConfig(pub fastlogging::RecorderWriterConfig);
+
+#[pymethods]
+impl RecorderWriterConfig {
+    #[new]
+    #[pyo3(signature=(capacity, path=None))]
+    pub fn new(capacity: usize, path: Option<PathBuf>) -> Self {
+        Self(fastlogging::RecorderWriterConfig::new(capacity, path))
+    }
+
+    #[staticmethod]
+    pub fn builder() -> RecorderWriterConfigBuilder {
+        RecorderWriterConfigBuilder(fastlogging::RecorderWriterConfig::builder())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct RecorderWriterConfigBuilder(fastlogging::RecorderWriterConfigBuilder);
+
+#[pymethods]
+impl RecorderWriterConfigBuilder {
+    pub fn enabled(mut slf: PyRefMut<'_, Self>, enabled: bool) -> PyRefMut<'_, Self> {
+        slf.0.enabled(enabled);
+        slf
+    }
+
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: u8) -> PyRefMut<'_, Self> {
+        slf.0.level(level);
+        slf
+    }
+
+    pub fn domain_filter(mut slf: PyRefMut<'_, Self>, domain_filter: String) -> PyRefMut<'_, Self> {
+        slf.0.domain_filter(domain_filter);
+        slf
+    }
+
+    pub fn message_filter(
+        mut slf: PyRefMut<'_, Self>,
+        message_filter: String,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.message_filter(message_filter);
+        slf
+    }
+
+    pub fn structured(
+        mut slf: PyRefMut<'_, Self>,
+        structured: MessageStructEnum,
+    ) -> PyRefMut<'_, Self> {
+        slf.0.structured(structured.into());
+        slf
+    }
+
+    pub fn level2sym(mut slf: PyRefMut<'_, Self>, level2sym: LevelSyms) -> PyRefMut<'_, Self> {
+        slf.0.level2sym(level2sym.0);
+        slf
+    }
+
+    pub fn time_format(mut slf: PyRefMut<'_, Self>, time_format: String) -> PyRefMut<'_, Self> {
+        slf.0.time_format(time_format);
+        slf
+    }
+
+    pub fn capacity(mut slf: PyRefMut<'_, Self>, capacity: usize) -> PyRefMut<'_, Self> {
+        slf.0.capacity(capacity);
+        slf
+    }
+
+    pub fn max_bytes(mut slf: PyRefMut<'_, Self>, max_bytes: usize) -> PyRefMut<'_, Self> {
+        slf.0.max_bytes(max_bytes);
+        slf
+    }
+
+    pub fn trigger_level(mut slf: PyRefMut<'_, Self>, trigger_level: u8) -> PyRefMut<'_, Self> {
+        slf.0.trigger_level(trigger_level);
+        slf
+    }
+
+    pub fn path(mut slf: PyRefMut<'_, Self>, path: PathBuf) -> PyRefMut<'_, Self> {
+        slf.0.path(path);
+        slf
+    }
+
+    pub fn dump_on_panic(mut slf: PyRefMut<'_, Self>, dump_on_panic: bool) -> PyRefMut<'_, Self> {
+        slf.0.dump_on_panic(dump_on_panic);
+        slf
+    }
+
+    pub fn debug(mut slf: PyRefMut<'_, Self>, debug: u8) -> PyRefMut<'_, Self> {
+        slf.0.debug(debug);
+        slf
+    }
+
+    pub fn build(&self) -> Result<RecorderWriterConfig, LoggingError> {
+        Ok(RecorderWriterConfig(self.0.build()?))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass(from_py_object)]
+#[derive(Debug, Clone)]
+pub struct CaptureWriterConfig(pub fastlogging::CaptureWriterConfig);
+
+#[pymethods]
+impl CaptureWriterConfig {
+    #[new]
+    #[pyo3(signature=(level=fastlogging::NOTSET, capacity=0))]
+    pub fn new(level: u8, capacity: usize) -> Self {
+        let mut config = fastlogging::CaptureWriterConfig::new(level);
+        config.capacity = capacity;
+        Self(config)
+    }
+
+    /// Handle to the log messages captured by writers created from this configuration.
+    pub fn handle(&self) -> CaptureHandle {
+        CaptureHandle(self.0.handle())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn __str__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pyclass(from_py_object)]
+#[derive(Debug, Clone)]
+pub struct CaptureHandle(pub fastlogging::CaptureHandle);
+
+#[pymethods]
+impl CaptureHandle {
+    /// List of captured log messages as tuples `(level, domain, message)`.
+    pub fn records(&self) -> Vec<(u8, String, String)> {
+        self.0
+            .records()
+            .into_iter()
+            .map(|r| (r.level, r.domain, r.message))
+            .collect()
+    }
+
+    /// Remove and return captured log messages as tuples `(level, domain, message)`.
+    pub fn drain(&self) -> Vec<(u8, String, String)> {
+        self.0
+            .drain()
+            .into_iter()
+            .map(|r| (r.level, r.domain, r.message))
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.clear();
+    }
+
+    #[pyo3(signature=(pattern, level=None, domain=None))]
+    pub fn contains(&self, pattern: &str, level: Option<u8>, domain: Option<&str>) -> bool {
+        self.0.contains(level, domain, pattern)
+    }
+
+    #[pyo3(signature=(pattern, level=None, domain=None))]
+    pub fn assert_logged(
+        &self,
+        pattern: &str,
+        level: Option<u8>,
+        domain: Option<&str>,
+    ) -> PyResult<()> {
+        if self.0.contains(level, domain, pattern) {
+            Ok(())
+        } else {
+            Err(pyo3::exceptions::PyAssertionError::new_err(format!(
+                "No log message with level={level:?} domain={domain:?} matching {pattern:?} captured"
+            )))
+        }
+    }
+
+    pub fn wait_for(&self, py: Python, count: usize, timeout: f64) -> Result<(), LoggingError> {
+        py.detach(|| Ok(self.0.wait_for(count, timeout)?))
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }